@@ -31,7 +31,8 @@ echo "=== Building Backend (Rust) ==="
 cd /build
 cargo build --release --target wasm32-unknown-unknown
 echo "Publishing modules..."
-APP_ID=$(linera publish-and-create target/wasm32-unknown-unknown/release/quiz_{contract,service}.wasm)
+APP_ID=$(linera publish-and-create target/wasm32-unknown-unknown/release/quiz_{contract,service}.wasm \
+    --json-parameters '{"default_page_size": 100, "max_page_size": 500, "default_sort": "Ascending"}')
 
 # Save CHAIN_ID and APP_ID to .env file for frontend use
 ENV_FILE="/build/front-end/.env"
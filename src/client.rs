@@ -0,0 +1,1882 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 原生Rust客户端辅助工具：构造GraphQL请求并解析响应（需启用 `client` 特性） */
+
+use std::fmt;
+
+use linera_sdk::linera_base_types::{AccountOwner, CryptoHash, Timestamp};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::validation::Violation;
+use crate::{
+    AddBlackoutWindowParams, AddTokenPrizeParams, AdminAction, AdminProposalView, Answer,
+    AppSummary, AppealQuestionParams, ApplyTagParams, ArchivedQuizView, AttemptDetail,
+    AttemptHistoryExport, BlackoutWindowView, BookmarkedQuestion, BuildPracticeExamParams,
+    CategoryCount, CertificateView, ChainId, ConfirmAttemptParams, Connection,
+    CreatePreviewTokenParams, CreateQuizParams, CreatorAnnouncement, DuplicateQuestionGroup,
+    Envelope, EventStreamView, ExpireAbandonedAttemptsParams, ExportFormat, FinalizationView,
+    FinalizeAttemptParams, GlobalStandingView, GradeEssayParams, GrantAccommodationParams,
+    GrantExtraAttemptParams, HeartbeatParams, LeaderboardKind, LeaderboardRank,
+    LeaderboardSnapshotView, LeaderboardWindow, MarkNotificationsReadParams, MergeTagsParams,
+    NotificationView, OrgAnalytics, OrgMemberParams, OrgView, PlayoffView, PostAnnouncementParams,
+    PracticePoolQuestion, PreviewTokenView, Principal, PrizePoolView, QuestionDifficulty,
+    QuestionReview, QuestionStats, QuizAttempt, QuizFilter, QuizSetView, QuizStats, RateLimitHint,
+    RegisterOrgParams, RemoteQuizView, RenameTagParams, ResolveRegradeCaseParams,
+    RevealAnswerKeyParams, RevealContentParams, RevealResultsParams, SaveAnswerParams, SavedAnswer,
+    ScorePreview, SetAttemptNoteParams, SetBookmarkParams, SetNicknameParams, SetOrgBrandingParams,
+    SetOrgSettingsParams, SetPrivacyParams, SetQuizMetadataParams, SimilarityPair,
+    StartAttemptParams, SubmissionWindow, SubmitAnswersParams, TagCount, UpdateQuizParams,
+    UserAttemptView, UserStatsView, UserStreakView, UserView,
+};
+
+/// QuizSetView 的查询字段
+pub const QUIZ_SET_FIELDS: &str = "id title description creator creatorName \
+    questions { id text options points kind media { kind blob durationSecs transcript altText } screenReaderHint } startMode scoring allowDontKnow isStarted status cancelled finalized \
+    startTime endTime createdAt practice playoffOf hideResults resultsVisible proctor owner \
+    metadata { key value } org membersOnly timeLimit shuffleQuestions questionsPerAttempt maxAttempts attemptPolicy attestationApp entryFee category tags passScore softLaunch { percentPerWave waveSecs } embargoed contentCommitment answerKeyHash answerKeySalt";
+
+/// QuestionReview 的查询字段
+pub const QUESTION_REVIEW_FIELDS: &str =
+    "questionId text options correctOptions acceptedAnswers rubric { name weight levels } explanation";
+
+/// OrgView 的查询字段
+pub const ORG_FIELDS: &str = "slug name owner admins members branding { key value } \
+    settings { defaultMembersOnly maxQuestions maxTimeLimit } createdAt";
+
+/// OrgAnalytics 的查询字段
+pub const ORG_ANALYTICS_FIELDS: &str = "org quizCount activeQuizzes cancelledQuizzes attemptCount \
+    participantCount averageScore";
+
+/// UserAttemptView 的查询字段
+pub const USER_ATTEMPT_FIELDS: &str =
+    "quizId attemptNo user nickName answers { choices text number dontKnow } score preciseScore questionScores timeTaken completedAt resultsHidden receipt questionOrder passed";
+
+/// RemoteQuizView 的查询字段
+pub const REMOTE_QUIZ_FIELDS: &str =
+    "hostChainId quizId title creator startTime endTime refreshedAt standingsHash";
+
+/// PageInfo 的查询字段
+pub const PAGE_INFO_FIELDS: &str = "endCursor hasNextPage";
+
+/// UserView 的查询字段
+pub const USER_FIELDS: &str = "user nickName trophies { quizId quizTitle place score awardedAt }";
+
+/// UserStreakView 的查询字段
+pub const USER_STREAK_FIELDS: &str =
+    "user currentStreak longestStreak lastCompletedDay bonusMultiplier season seasonPoints";
+
+/// GlobalStandingView 的查询字段
+pub const GLOBAL_STANDING_FIELDS: &str =
+    "rank user nickName points precisePoints quizzesCompleted updatedAt";
+
+/// UserStatsView 的查询字段
+pub const USER_STATS_FIELDS: &str = "user quizzesTaken quizzesCreated gradedAttempts totalPoints \
+    averageScore bestRank";
+
+/// PrizePoolView 的查询字段
+pub const PRIZE_POOL_FIELDS: &str =
+    "quizId funder amount entryFees splits payouts { rank user amount } distributed \
+    tokenPrizes { token sponsor amount splits payouts { rank user amount } refunded } \
+    unclaimed { user rank amount claimDeadline } sweeps { user rank amount recipient sweptAt }";
+
+/// FinalizationView 的查询字段
+pub const FINALIZATION_FIELDS: &str = "quizId finalizedAt blockHeight revision standingsHash \
+    prizeDistribution { payouts { rank user amount } refunded distributedAt blockHeight } \
+    tokenRewards { rank user amount } \
+    revisions { revision frozenAt } regrades { questionId votes status }";
+
+/// ArchivedQuizView 的查询字段
+pub const ARCHIVED_QUIZ_FIELDS: &str = "quizId title creator category tags startTime endTime \
+    cancelled participants attempts averageScore standings { user nickName score timeTaken completedAt } \
+    standingsHash archivedAt";
+
+/// PreviewTokenView 的查询字段
+pub const PREVIEW_TOKEN_FIELDS: &str = "token quizId label createdAt expiresAt";
+
+/// PlayoffView 的查询字段
+pub const PLAYOFF_FIELDS: &str = "quizId playoffQuizId tiedUsers createdAt";
+
+/// SavedAnswer 的查询字段
+pub const SAVED_ANSWER_FIELDS: &str = "questionId answer { choices text number dontKnow }";
+
+/// Violation 的查询字段
+pub const VIOLATION_FIELDS: &str = "field message";
+
+/// ScorePreview 的查询字段
+pub const SCORE_PREVIEW_FIELDS: &str = "quizId score preciseScore maxScore";
+
+/// AdminProposalView 的查询字段
+pub const ADMIN_PROPOSAL_FIELDS: &str = "id action confirmations executed createdAt";
+
+/// QuestionStats 的查询字段
+pub const QUESTION_STATS_FIELDS: &str =
+    "questionId appealVotes disputeStatus answerTimeSamples medianAnswerTime answered correct skipped accuracy difficulty";
+
+/// QuizStats 的查询字段
+pub const QUIZ_STATS_FIELDS: &str =
+    "quizId timeLimit recommendedTimeLimit views uniqueViewers starters submitters";
+
+/// PracticePoolQuestion 的查询字段
+pub const PRACTICE_POOL_FIELDS: &str = "quizId questionId text difficulty";
+
+/// BlackoutWindowView 的查询字段
+pub const BLACKOUT_WINDOW_FIELDS: &str = "id startTime endTime reason";
+
+/// CertificateView 的查询字段
+pub const CERTIFICATE_FIELDS: &str =
+    "quizId quizTitle user attemptNo score passScore completedAt chainId receipt";
+
+/// SubmissionWindow 的查询字段
+pub const SUBMISSION_WINDOW_FIELDS: &str =
+    "quizId user wave waveCount opensAt closesAt timeLimit open";
+
+/// DuplicateQuestionGroup 的查询字段
+pub const DUPLICATE_QUESTION_GROUP_FIELDS: &str = "fingerprint questions { quizId questionId }";
+
+/// AttemptDetail 的查询字段
+pub const ATTEMPT_DETAIL_FIELDS: &str = "quizId user attemptNo score preciseScore \
+    results { questionId correct skipped pointsAwarded answerChanges \
+    rubric { criterion weight level levelCount descriptor } }";
+
+/// LeaderboardSnapshotView 的查询字段
+pub const LEADERBOARD_SNAPSHOT_FIELDS: &str =
+    "quizId revision frozenAt entries { rank user nickName score }";
+
+/// ChainMetadata 的查询字段
+pub const CHAIN_METADATA_FIELDS: &str = "chainId applicationId nextBlockHeight timestamp";
+
+/// 发送给应用GraphQL端点的请求体
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphQLRequest {
+    pub query: String,
+    pub variables: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Value>,
+    /// 响应中承载结果的顶层字段名
+    #[serde(skip)]
+    pub field: &'static str,
+}
+
+impl GraphQLRequest {
+    fn new(field: &'static str, query: String, variables: Value) -> Self {
+        GraphQLRequest {
+            query,
+            variables,
+            extensions: None,
+            field,
+        }
+    }
+
+    /// 标识调用方，服务据此统计查询量并返回限流提示
+    pub fn with_caller(mut self, caller: &str) -> Self {
+        self.extensions = Some(json!({ "caller": caller }));
+        self
+    }
+
+    /// 从响应体中解析本请求的结果
+    pub fn parse_response<T: DeserializeOwned>(&self, body: &str) -> Result<T, ClientError> {
+        parse_response(body, self.field)
+    }
+}
+
+/// 客户端错误
+#[derive(Debug)]
+pub enum ClientError {
+    /// 服务返回的GraphQL错误信息
+    GraphQL(Vec<String>),
+    /// 响应中缺少指定字段
+    MissingField(String),
+    /// 响应无法解码
+    Decode(serde_json::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::GraphQL(messages) => write!(f, "GraphQL errors: {}", messages.join("; ")),
+            ClientError::MissingField(field) => write!(f, "Missing field `{field}` in response"),
+            ClientError::Decode(error) => write!(f, "Failed to decode response: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Decode(error)
+    }
+}
+
+/// 获取单个Quiz集合
+pub fn quiz_set(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizSet",
+        format!("query($quizId: Int!) {{ quizSet(quizId: $quizId) {{ {QUIZ_SET_FIELDS} }} }}"),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 凭预览令牌获取尚未开始的Quiz
+pub fn quiz_by_share_token(token: &CryptoHash) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizByShareToken",
+        format!(
+            "query($token: CryptoHash!) {{ quizByShareToken(token: $token) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "token": token }),
+    )
+}
+
+/// 获取Quiz的预览令牌，viewer 须为创建者
+pub fn preview_tokens(quiz_id: u64, viewer: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "previewTokens",
+        format!(
+            "query($quizId: Int!, $viewer: AccountOwner) \
+             {{ previewTokens(quizId: $quizId, viewer: $viewer) {{ {PREVIEW_TOKEN_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "viewer": viewer }),
+    )
+}
+
+/// 获取所有Quiz集合
+pub fn quiz_sets() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizSets",
+        format!("query {{ quizSets {{ {QUIZ_SET_FIELDS} }} }}"),
+        json!({}),
+    )
+}
+
+/// 获取符合筛选条件的Quiz集合
+pub fn quiz_sets_matching(filter: &QuizFilter) -> Result<GraphQLRequest, ClientError> {
+    Ok(GraphQLRequest::new(
+        "quizSets",
+        format!(
+            "query($filter: QuizFilter!) {{ quizSets(filter: $filter) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "filter": to_graphql_value(filter)? }),
+    ))
+}
+
+/// 获取符合筛选条件的Quiz数量
+pub fn quiz_sets_count(filter: &QuizFilter) -> Result<GraphQLRequest, ClientError> {
+    Ok(GraphQLRequest::new(
+        "quizSetsCount",
+        "query($filter: QuizFilter!) { quizSetsCount(filter: $filter) }".to_string(),
+        json!({ "filter": to_graphql_value(filter)? }),
+    ))
+}
+
+/// 按游标获取一页Quiz集合，after 为上一页的 endCursor
+pub fn quiz_sets_connection(after: Option<&str>, limit: Option<u32>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizSetsConnection",
+        format!(
+            "query($page: CursorArgs!) {{ quizSetsConnection(page: $page) \
+             {{ edges {{ cursor node {{ {QUIZ_SET_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 获取用户的答题记录，查看者为答题者本人时附带私人笔记
+pub fn user_attempts(user: &AccountOwner, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userAttempts",
+        format!(
+            "query($user: AccountOwner!, $viewer: AccountOwner) {{ userAttempts(user: $user, viewer: $viewer) \
+             {{ quizId attempt {{ {USER_ATTEMPT_FIELDS} }} note }} }}"
+        ),
+        json!({ "user": user, "viewer": viewer }),
+    )
+}
+
+/// 按游标获取一页用户答题记录，after 为上一页的 endCursor
+pub fn user_attempts_connection(
+    user: &AccountOwner,
+    viewer: Option<&AccountOwner>,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userAttemptsConnection",
+        format!(
+            "query($user: AccountOwner!, $viewer: AccountOwner, $page: CursorArgs!) \
+             {{ userAttemptsConnection(user: $user, viewer: $viewer, page: $page) \
+             {{ edges {{ cursor node {{ quizId attempt {{ {USER_ATTEMPT_FIELDS} }} note }} }} \
+             pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "user": user, "viewer": viewer, "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 获取用户档案，查看者为用户本人时包含私密用户的奖杯
+pub fn user(address: &AccountOwner, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "user",
+        format!(
+            "query($address: AccountOwner!, $viewer: AccountOwner) \
+             {{ user(address: $address, viewer: $viewer) {{ {USER_FIELDS} }} }}"
+        ),
+        json!({ "address": address, "viewer": viewer }),
+    )
+}
+
+/// 按昵称查找登记该昵称的用户
+pub fn user_by_nickname(nick_name: &str) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userByNickname",
+        "query($nickName: String!) { userByNickname(nickName: $nickName) }".to_string(),
+        json!({ "nickName": nick_name }),
+    )
+}
+
+/// 获取用户的连续完成天数和当前赛季积分
+pub fn user_streak(user: &AccountOwner, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userStreak",
+        format!(
+            "query($user: AccountOwner!, $viewer: AccountOwner) \
+             {{ userStreak(user: $user, viewer: $viewer) {{ {USER_STREAK_FIELDS} }} }}"
+        ),
+        json!({ "user": user, "viewer": viewer }),
+    )
+}
+
+/// 获取用户的累计统计
+pub fn user_stats(user: &AccountOwner, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userStats",
+        format!(
+            "query($user: AccountOwner!, $viewer: AccountOwner) \
+             {{ userStats(user: $user, viewer: $viewer) {{ {USER_STATS_FIELDS} }} }}"
+        ),
+        json!({ "user": user, "viewer": viewer }),
+    )
+}
+
+/// 按提交回执ID查找答卷
+pub fn attempt_by_receipt(receipt: &CryptoHash, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "attemptByReceipt",
+        format!(
+            "query($receipt: CryptoHash!, $viewer: AccountOwner) \
+             {{ attemptByReceipt(receipt: $receipt, viewer: $viewer) {{ {USER_ATTEMPT_FIELDS} }} }}"
+        ),
+        json!({ "receipt": receipt, "viewer": viewer }),
+    )
+}
+
+/// 获取总排行榜
+pub fn leaderboard() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "leaderboard",
+        format!("query {{ leaderboard {{ {USER_ATTEMPT_FIELDS} }} }}"),
+        json!({}),
+    )
+}
+
+/// 获取按时间衰减的全局排行榜
+pub fn decayed_leaderboard(viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "decayedLeaderboard",
+        format!(
+            "query($viewer: AccountOwner) {{ decayedLeaderboard(viewer: $viewer) \
+             {{ {USER_ATTEMPT_FIELDS} }} }}"
+        ),
+        json!({ "viewer": viewer }),
+    )
+}
+
+/// 获取一页跨Quiz的累计排行榜
+pub fn global_leaderboard(
+    viewer: Option<&AccountOwner>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "globalLeaderboard",
+        format!(
+            "query($viewer: AccountOwner, $page: PageArgs!) \
+             {{ globalLeaderboard(viewer: $viewer, page: $page) {{ {GLOBAL_STANDING_FIELDS} }} }}"
+        ),
+        json!({ "viewer": viewer, "page": { "offset": offset, "limit": limit } }),
+    )
+}
+
+/// 获取用户在累计排行榜上的名次
+pub fn global_rank(user: &AccountOwner, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "globalRank",
+        format!(
+            "query($user: AccountOwner!, $viewer: AccountOwner) \
+             {{ globalRank(user: $user, viewer: $viewer) {{ {GLOBAL_STANDING_FIELDS} }} }}"
+        ),
+        json!({ "user": user, "viewer": viewer }),
+    )
+}
+
+/// 按游标获取一页总排行榜，after 为上一页的 endCursor
+pub fn leaderboard_connection(after: Option<&str>, limit: Option<u32>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "leaderboardConnection",
+        format!(
+            "query($page: CursorArgs!) {{ leaderboardConnection(page: $page) \
+             {{ edges {{ cursor node {{ {USER_ATTEMPT_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 按游标获取一页单个Quiz的排行榜，after 为上一页的 endCursor
+pub fn quiz_leaderboard_connection(
+    quiz_id: u64,
+    kind: LeaderboardKind,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizLeaderboardConnection",
+        format!(
+            "query($quizId: Int!, $kind: LeaderboardKind!, $page: CursorArgs!) \
+             {{ quizLeaderboardConnection(quizId: $quizId, kind: $kind, page: $page) \
+             {{ edges {{ cursor node {{ {USER_ATTEMPT_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "kind": kind, "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 获取单个Quiz的排行榜
+pub fn quiz_leaderboard(quiz_id: u64, kind: LeaderboardKind) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizLeaderboard",
+        format!(
+            "query($quizId: Int!, $kind: LeaderboardKind!) \
+             {{ quizLeaderboard(quizId: $quizId, kind: $kind) {{ {USER_ATTEMPT_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "kind": kind }),
+    )
+}
+
+/// 测验结束后获取答卷的逐题得分明细，attempt_no 为空时取最近一次答题
+pub fn attempt_detail(
+    quiz_id: u64,
+    user: &AccountOwner,
+    attempt_no: Option<u32>,
+    viewer: Option<&AccountOwner>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "attemptDetail",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!, $attemptNo: Int, $viewer: AccountOwner) \
+             {{ attemptDetail(quizId: $quizId, user: $user, attemptNo: $attemptNo, viewer: $viewer) \
+             {{ {ATTEMPT_DETAIL_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user, "attemptNo": attempt_no, "viewer": viewer }),
+    )
+}
+
+/// 导出用户完整答题记录
+pub fn export_my_attempts(address: &AccountOwner, format: ExportFormat) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "exportMyAttempts",
+        "query($address: AccountOwner!, $format: ExportFormat!) \
+         { exportMyAttempts(address: $address, viewer: $address, format: $format) }"
+            .to_string(),
+        json!({ "address": address, "format": format }),
+    )
+}
+
+/// 获取用户看到的问题顺序（原始问题ID）
+pub fn question_order(quiz_id: u64, user: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "questionOrder",
+        "query($quizId: Int!, $user: AccountOwner!) \
+         { questionOrder(quizId: $quizId, user: $user) }"
+            .to_string(),
+        json!({ "quizId": quiz_id, "user": user }),
+    )
+}
+
+/// 获取Quiz的托管奖池
+pub fn prize_pool(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "prizePool",
+        format!("query($quizId: Int!) {{ prizePool(quizId: $quizId) {{ {PRIZE_POOL_FIELDS} }} }}"),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取Quiz最终结果的可验证摘要
+pub fn finalization(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "finalization",
+        format!(
+            "query($quizId: Int!) {{ finalization(quizId: $quizId) {{ {FINALIZATION_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取创建者已归档的Quiz摘要
+pub fn archived_quizzes(creator: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "archivedQuizzes",
+        format!(
+            "query($creator: AccountOwner!) {{ archivedQuizzes(creator: $creator) \
+             {{ {ARCHIVED_QUIZ_FIELDS} }} }}"
+        ),
+        json!({ "creator": creator }),
+    )
+}
+
+/// 获取创建者的Quiz保留天数
+pub fn retention_policy(creator: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "retentionPolicy",
+        "query($creator: AccountOwner!) { retentionPolicy(creator: $creator) }".to_string(),
+        json!({ "creator": creator }),
+    )
+}
+
+/// 获取Quiz奖项名次同分同时时创建的加赛
+pub fn playoff(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "playoff",
+        format!("query($quizId: Int!) {{ playoff(quizId: $quizId) {{ {PLAYOFF_FIELDS} }} }}"),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取进行中的答题会话里已保存的答案
+pub fn saved_answers(quiz_id: u64, user: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "savedAnswers",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!) \
+             {{ savedAnswers(quizId: $quizId, user: $user) {{ {SAVED_ANSWER_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user }),
+    )
+}
+
+/// 获取答题回顾（正确答案和解析）
+pub fn quiz_review(quiz_id: u64, user: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizReview",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!) \
+             {{ quizReview(quizId: $quizId, user: $user) {{ {QUESTION_REVIEW_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user }),
+    )
+}
+
+/// 获取组织信息
+pub fn org(slug: &str) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "org",
+        format!("query($slug: String!) {{ org(slug: $slug) {{ {ORG_FIELDS} }} }}"),
+        json!({ "slug": slug }),
+    )
+}
+
+/// 获取所有组织
+pub fn orgs() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "orgs",
+        format!("query {{ orgs {{ {ORG_FIELDS} }} }}"),
+        json!({}),
+    )
+}
+
+/// 获取组织统计，viewer 须为组织管理员
+pub fn org_analytics(org: &str, viewer: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "orgAnalytics",
+        format!(
+            "query($org: String!, $viewer: AccountOwner!) \
+             {{ orgAnalytics(org: $org, viewer: $viewer) {{ {ORG_ANALYTICS_FIELDS} }} }}"
+        ),
+        json!({ "org": org, "viewer": viewer }),
+    )
+}
+
+/// 获取组织下对 viewer 可见的Quiz
+pub fn org_quizzes(org: &str, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "orgQuizzes",
+        format!(
+            "query($org: String!, $viewer: AccountOwner) \
+             {{ orgQuizzes(org: $org, viewer: $viewer) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "org": org, "viewer": viewer }),
+    )
+}
+
+/// 按游标获取一页组织下的Quiz，after 为上一页的 endCursor
+pub fn org_quizzes_connection(
+    org: &str,
+    viewer: Option<&AccountOwner>,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "orgQuizzesConnection",
+        format!(
+            "query($org: String!, $viewer: AccountOwner, $page: CursorArgs!) \
+             {{ orgQuizzesConnection(org: $org, viewer: $viewer, page: $page) \
+             {{ edges {{ cursor node {{ {QUIZ_SET_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "org": org, "viewer": viewer, "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 按标签列出Quiz
+pub fn quizzes_by_tag(tag: &str, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizzesByTag",
+        format!(
+            "query($tag: String!, $viewer: AccountOwner) \
+             {{ quizzesByTag(tag: $tag, viewer: $viewer) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "tag": tag, "viewer": viewer }),
+    )
+}
+
+/// 按游标获取一页带指定标签的Quiz，after 为上一页的 endCursor
+pub fn quizzes_by_tag_connection(
+    tag: &str,
+    viewer: Option<&AccountOwner>,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizzesByTagConnection",
+        format!(
+            "query($tag: String!, $viewer: AccountOwner, $page: CursorArgs!) \
+             {{ quizzesByTagConnection(tag: $tag, viewer: $viewer, page: $page) \
+             {{ edges {{ cursor node {{ {QUIZ_SET_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "tag": tag, "viewer": viewer, "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 列出全部标签及其Quiz数
+pub fn tags(viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "tags",
+        "query($viewer: AccountOwner) { tags(viewer: $viewer) { tag quizCount } }".to_string(),
+        json!({ "viewer": viewer }),
+    )
+}
+
+/// 获取Quiz的创建者公告
+pub fn quiz_announcements(quiz_id: u64, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizAnnouncements",
+        "query($quizId: Int!, $viewer: AccountOwner) \
+         { quizAnnouncements(quizId: $quizId, viewer: $viewer) { text postedAt } }"
+            .to_string(),
+        json!({ "quizId": quiz_id, "viewer": viewer }),
+    )
+}
+
+/// 按标题和描述全文搜索Quiz，limit 为空时使用默认页大小
+pub fn search_quizzes(
+    query: &str,
+    viewer: Option<&AccountOwner>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "searchQuizzes",
+        format!(
+            "query($query: String!, $viewer: AccountOwner, $limit: Int) \
+             {{ searchQuizzes(query: $query, viewer: $viewer, limit: $limit) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "query": query, "viewer": viewer, "limit": limit }),
+    )
+}
+
+/// 按分类列出Quiz
+pub fn quizzes_by_category(category: &str, viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizzesByCategory",
+        format!(
+            "query($category: String!, $viewer: AccountOwner) \
+             {{ quizzesByCategory(category: $category, viewer: $viewer) {{ {QUIZ_SET_FIELDS} }} }}"
+        ),
+        json!({ "category": category, "viewer": viewer }),
+    )
+}
+
+/// 按游标获取一页指定分类的Quiz，after 为上一页的 endCursor
+pub fn quizzes_by_category_connection(
+    category: &str,
+    viewer: Option<&AccountOwner>,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizzesByCategoryConnection",
+        format!(
+            "query($category: String!, $viewer: AccountOwner, $page: CursorArgs!) \
+             {{ quizzesByCategoryConnection(category: $category, viewer: $viewer, page: $page) \
+             {{ edges {{ cursor node {{ {QUIZ_SET_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({ "category": category, "viewer": viewer, "page": { "after": after, "limit": limit } }),
+    )
+}
+
+/// 列出全部分类及其Quiz数
+pub fn categories(viewer: Option<&AccountOwner>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "categories",
+        "query($viewer: AccountOwner) { categories(viewer: $viewer) { category quizCount } }"
+            .to_string(),
+        json!({ "viewer": viewer }),
+    )
+}
+
+/// 获取用户在Quiz排行榜上的名次
+pub fn my_rank(quiz_id: u64, user: &AccountOwner, kind: LeaderboardKind) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "myRank",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!, $kind: LeaderboardKind!) \
+             {{ myRank(quizId: $quizId, user: $user, kind: $kind) \
+             {{ quizId rank total entry {{ {USER_ATTEMPT_FIELDS} }} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user, "kind": kind }),
+    )
+}
+
+/// 获取用户名次上下各 radius 名的排行榜片段
+pub fn leaderboard_window(
+    quiz_id: u64,
+    user: &AccountOwner,
+    radius: u32,
+    kind: LeaderboardKind,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "leaderboardWindow",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!, $radius: Int!, $kind: LeaderboardKind!) \
+             {{ leaderboardWindow(quizId: $quizId, user: $user, radius: $radius, kind: $kind) \
+             {{ quizId rank total firstRank entries {{ {USER_ATTEMPT_FIELDS} }} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user, "radius": radius, "kind": kind }),
+    )
+}
+
+/// 获取Quiz的冻结排行榜修订版
+pub fn leaderboard_snapshots(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "leaderboardSnapshots",
+        format!(
+            "query($quizId: Int!) {{ leaderboardSnapshots(quizId: $quizId) \
+             {{ {LEADERBOARD_SNAPSHOT_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取附带链元数据的所有Quiz集合
+pub fn quiz_sets_envelope() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizSetsEnvelope",
+        format!(
+            "query {{ quizSetsEnvelope {{ metadata {{ {CHAIN_METADATA_FIELDS} }} \
+             data {{ {QUIZ_SET_FIELDS} }} }} }}"
+        ),
+        json!({}),
+    )
+}
+
+/// 获取管理员列表
+pub fn admins() -> GraphQLRequest {
+    GraphQLRequest::new("admins", "query { admins }".to_string(), json!({}))
+}
+
+/// 获取管理操作提案
+pub fn admin_proposals() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "adminProposals",
+        format!("query {{ adminProposals {{ {ADMIN_PROPOSAL_FIELDS} }} }}"),
+        json!({}),
+    )
+}
+
+/// 获取应用事件流的位置
+pub fn event_stream(since_index: Option<u32>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "eventStream",
+        "query($sinceIndex: Int) { eventStream(sinceIndex: $sinceIndex) \
+         { chainId applicationId streamName nextIndex pending } }"
+            .to_string(),
+        json!({ "sinceIndex": since_index }),
+    )
+}
+
+/// 获取Quiz的答卷相似度报告（仅创建者）
+pub fn similarity_report(
+    quiz_id: u64,
+    viewer: &AccountOwner,
+    threshold: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "similarityReport",
+        "query($quizId: Int!, $viewer: AccountOwner!, $threshold: Int) \
+         { similarityReport(quizId: $quizId, viewer: $viewer, threshold: $threshold) \
+         { questionId userA attemptA userB attemptB similarity } }"
+            .to_string(),
+        json!({ "quizId": quiz_id, "viewer": viewer, "threshold": threshold }),
+    )
+}
+
+/// 获取平台禁排时段
+pub fn blackout_windows() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "blackoutWindows",
+        format!("query {{ blackoutWindows {{ {BLACKOUT_WINDOW_FIELDS} }} }}"),
+        json!({}),
+    )
+}
+
+/// 获取开始时间所在的平台禁排时段
+pub fn schedule_conflicts(start_time: Timestamp) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "scheduleConflicts",
+        format!(
+            "query($startTime: Timestamp!) {{ scheduleConflicts(startTime: $startTime) \
+             {{ {BLACKOUT_WINDOW_FIELDS} }} }}"
+        ),
+        json!({ "startTime": start_time }),
+    )
+}
+
+/// 获取创建者往期Quiz中可以抽入练习卷的问题，tags 为空时不限标签
+pub fn practice_exam_pool(
+    creator: &AccountOwner,
+    tags: &[String],
+    difficulty: Option<QuestionDifficulty>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "practiceExamPool",
+        format!(
+            "query($creator: AccountOwner!, $tags: [String!]!, \
+             $difficulty: QuestionDifficulty) {{ practiceExamPool(creator: $creator, \
+             tags: $tags, difficulty: $difficulty) {{ {PRACTICE_POOL_FIELDS} }} }}"
+        ),
+        json!({ "creator": creator, "tags": tags, "difficulty": difficulty }),
+    )
+}
+
+/// 获取Quiz每个问题的统计
+pub fn question_stats(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "questionStats",
+        format!(
+            "query($quizId: Int!) {{ questionStats(quizId: $quizId) \
+             {{ {QUESTION_STATS_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取Quiz统计和建议答题时限
+pub fn quiz_stats(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "quizStats",
+        format!("query($quizId: Int!) {{ quizStats(quizId: $quizId) {{ {QUIZ_STATS_FIELDS} }} }}"),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 获取从订阅的链收到公告的Quiz目录，可按所在链筛选或只列出尚未结束的Quiz
+pub fn remote_quizzes(host_chain_id: Option<&ChainId>, open_only: bool) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "remoteQuizzes",
+        format!(
+            "query($hostChainId: ChainId, $openOnly: Boolean!) \
+             {{ remoteQuizzes(hostChainId: $hostChainId, openOnly: $openOnly) \
+             {{ {REMOTE_QUIZ_FIELDS} }} }}"
+        ),
+        json!({ "hostChainId": host_chain_id, "openOnly": open_only }),
+    )
+}
+
+/// 按游标获取一页远程Quiz目录，after 为上一页的 endCursor
+pub fn remote_quizzes_connection(
+    host_chain_id: Option<&ChainId>,
+    open_only: bool,
+    after: Option<&str>,
+    limit: Option<u32>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "remoteQuizzesConnection",
+        format!(
+            "query($hostChainId: ChainId, $openOnly: Boolean!, $page: CursorArgs!) \
+             {{ remoteQuizzesConnection(hostChainId: $hostChainId, openOnly: $openOnly, page: $page) \
+             {{ edges {{ cursor node {{ {REMOTE_QUIZ_FIELDS} }} }} pageInfo {{ {PAGE_INFO_FIELDS} }} }} }}"
+        ),
+        json!({
+            "hostChainId": host_chain_id,
+            "openOnly": open_only,
+            "page": { "after": after, "limit": limit },
+        }),
+    )
+}
+
+/// 获取用户在各Quiz中收藏的问题
+pub fn bookmarked_questions(
+    address: &AccountOwner,
+    viewer: Option<&AccountOwner>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "bookmarkedQuestions",
+        format!(
+            "query($address: AccountOwner!, $viewer: AccountOwner) \
+             {{ bookmarkedQuestions(address: $address, viewer: $viewer) \
+             {{ quizId quizTitle bookmarkedAt question {{ {QUESTION_REVIEW_FIELDS} }} }} }}"
+        ),
+        json!({ "address": address, "viewer": viewer }),
+    )
+}
+
+/// 获取用户的通知
+pub fn user_notifications(
+    user: &AccountOwner,
+    unread_only: bool,
+    viewer: Option<&AccountOwner>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "userNotifications",
+        "query($user: AccountOwner!, $unreadOnly: Boolean, $viewer: AccountOwner) \
+         { userNotifications(user: $user, unreadOnly: $unreadOnly, viewer: $viewer) \
+         { id kind quizId message createdAt read } }"
+            .to_string(),
+        json!({ "user": user, "unreadOnly": unread_only, "viewer": viewer }),
+    )
+}
+
+/// 获取用户通过Quiz的证书记录
+pub fn certificate(
+    quiz_id: u64,
+    user: &AccountOwner,
+    viewer: Option<&AccountOwner>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "certificate",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!, $viewer: AccountOwner) \
+             {{ certificate(quizId: $quizId, user: $user, viewer: $viewer) {{ {CERTIFICATE_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user, "viewer": viewer }),
+    )
+}
+
+/// 获取用户在分批开放中的批次和答题时间窗口
+pub fn submittable_by(quiz_id: u64, user: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "submittableBy",
+        format!(
+            "query($quizId: Int!, $user: AccountOwner!) \
+             {{ submittableBy(quizId: $quizId, user: $user) {{ {SUBMISSION_WINDOW_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "user": user }),
+    )
+}
+
+/// 获取创建者所有Quiz中重复出现的问题
+pub fn duplicate_questions(creator: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "duplicateQuestions",
+        format!(
+            "query($creator: AccountOwner!) {{ duplicateQuestions(creator: $creator) \
+             {{ {DUPLICATE_QUESTION_GROUP_FIELDS} }} }}"
+        ),
+        json!({ "creator": creator }),
+    )
+}
+
+/// 获取应用概要
+pub fn app_summary() -> GraphQLRequest {
+    GraphQLRequest::new(
+        "appSummary",
+        format!(
+            "query {{ appSummary {{ metadata {{ {CHAIN_METADATA_FIELDS} }} version quizCount \
+             activeQuizzes attemptsLastDay admins }} }}"
+        ),
+        json!({}),
+    )
+}
+
+/// 试运行创建Quiz的参数校验
+pub fn validate_quiz(params: &CreateQuizParams) -> Result<GraphQLRequest, ClientError> {
+    Ok(GraphQLRequest::new(
+        "validateQuiz",
+        format!(
+            "query($params: CreateQuizParams!) {{ validateQuiz(params: $params) \
+             {{ {VIOLATION_FIELDS} }} }}"
+        ),
+        json!({ "params": to_graphql_value(params)? }),
+    ))
+}
+
+/// 试运行评分
+pub fn score_preview(
+    quiz_id: u64,
+    answers: &[Answer],
+    viewer: Option<&AccountOwner>,
+) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "scorePreview",
+        format!(
+            "query($quizId: Int!, $answers: [Answer!]!, $viewer: AccountOwner) \
+             {{ scorePreview(quizId: $quizId, answers: $answers, viewer: $viewer) {{ {SCORE_PREVIEW_FIELDS} }} }}"
+        ),
+        json!({ "quizId": quiz_id, "answers": answers, "viewer": viewer }),
+    )
+}
+
+/// 凭预览令牌试答尚未开始的Quiz，答卷不会被记录
+pub fn preview_attempt(token: &CryptoHash, answers: &[Answer]) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "previewAttempt",
+        format!(
+            "query($token: CryptoHash!, $answers: [Answer!]!) \
+             {{ previewAttempt(token: $token, answers: $answers) {{ {SCORE_PREVIEW_FIELDS} }} }}"
+        ),
+        json!({ "token": token, "answers": answers }),
+    )
+}
+
+/// 构造单参数操作的变更请求（对应 `Operation` 的元组变体）
+pub fn mutation(
+    field: &'static str,
+    input_type: &str,
+    params: &impl Serialize,
+) -> Result<GraphQLRequest, ClientError> {
+    Ok(GraphQLRequest::new(
+        field,
+        format!("mutation($field0: {input_type}!) {{ {field}(field0: $field0) }}"),
+        json!({ "field0": to_graphql_value(params)? }),
+    ))
+}
+
+/// 跨链操作的变更请求：参数为目标链ID和 input_type 类型的 params
+fn remote_mutation(
+    field: &'static str,
+    input_type: &str,
+    host_chain_id: &ChainId,
+    params: &impl Serialize,
+) -> Result<GraphQLRequest, ClientError> {
+    Ok(GraphQLRequest::new(
+        field,
+        format!(
+            "mutation($hostChainId: ChainId!, $params: {input_type}!) \
+             {{ {field}(hostChainId: $hostChainId, params: $params) }}"
+        ),
+        json!({ "hostChainId": host_chain_id, "params": to_graphql_value(params)? }),
+    ))
+}
+
+/// 创建Quiz集合的变更请求
+pub fn create_quiz(params: &CreateQuizParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("createQuiz", "CreateQuizParams", params)
+}
+
+/// 手动开始Quiz的变更请求
+pub fn start_quiz(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "startQuiz",
+        "mutation($quizId: Int!) { startQuiz(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 测验结束后冻结排行榜的变更请求
+pub fn freeze_leaderboard(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "freezeLeaderboard",
+        "mutation($quizId: Int!) { freezeLeaderboard(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 发放奖池奖金的变更请求
+pub fn distribute_prizes(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "distributePrizes",
+        "mutation($quizId: Int!) { distributePrizes(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 领取奖池奖金的变更请求
+pub fn claim_prize(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "claimPrize",
+        "mutation($quizId: Int!) { claimPrize(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 清扫逾期未领奖金的变更请求
+pub fn sweep_unclaimed_prizes(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "sweepUnclaimedPrizes",
+        "mutation($quizId: Int!) { sweepUnclaimedPrizes(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 归档并删除签名者在给定时间前结束的Quiz的变更请求
+pub fn prune_quizzes(ended_before: Timestamp) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "pruneQuizzes",
+        "mutation($endedBefore: Timestamp!) { pruneQuizzes(endedBefore: $endedBefore) }"
+            .to_string(),
+        json!({ "endedBefore": ended_before }),
+    )
+}
+
+/// 设置或取消签名者Quiz保留策略的变更请求
+pub fn set_retention_policy(retention_days: Option<u32>) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "setRetentionPolicy",
+        "mutation($retentionDays: Int) { setRetentionPolicy(retentionDays: $retentionDays) }"
+            .to_string(),
+        json!({ "retentionDays": retention_days }),
+    )
+}
+
+/// 按创建者的保留策略归档过期Quiz的变更请求
+pub fn apply_retention_policy(creator: &AccountOwner) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "applyRetentionPolicy",
+        "mutation($creator: AccountOwner!) { applyRetentionPolicy(creator: $creator) }".to_string(),
+        json!({ "creator": creator }),
+    )
+}
+
+/// 在奖池上追加代币奖金的变更请求
+pub fn add_token_prize(params: &AddTokenPrizeParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("addTokenPrize", "AddTokenPrizeParams", params)
+}
+
+/// 为用户额外增加一次答题机会的变更请求
+pub fn grant_extra_attempt(
+    params: &GrantExtraAttemptParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("grantExtraAttempt", "GrantExtraAttemptParams", params)
+}
+
+/// 生成预览令牌的变更请求
+pub fn create_preview_token(
+    params: &CreatePreviewTokenParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("createPreviewToken", "CreatePreviewTokenParams", params)
+}
+
+/// 撤销预览令牌的变更请求
+pub fn revoke_preview_token(token: &CryptoHash) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "revokePreviewToken",
+        "mutation($token: CryptoHash!) { revokePreviewToken(token: $token) }".to_string(),
+        json!({ "token": token }),
+    )
+}
+
+/// 发布创建者公告的变更请求
+pub fn post_announcement(params: &PostAnnouncementParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("postAnnouncement", "PostAnnouncementParams", params)
+}
+
+/// 按倍数延长用户答题时限的变更请求
+pub fn grant_accommodation(
+    params: &GrantAccommodationParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("grantAccommodation", "GrantAccommodationParams", params)
+}
+
+/// 测验结束后最终确定结果的变更请求
+pub fn finalize_quiz(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "finalizeQuiz",
+        "mutation($quizId: Int!) { finalizeQuiz(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 记录一次Quiz浏览的变更请求
+pub fn record_quiz_view(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "recordQuizView",
+        "mutation($quizId: Int!) { recordQuizView(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 从自己的链向Quiz所在的链开始答题的变更请求
+pub fn remote_start_attempt(
+    host_chain_id: &ChainId,
+    params: &StartAttemptParams,
+) -> Result<GraphQLRequest, ClientError> {
+    remote_mutation(
+        "remoteStartAttempt",
+        "StartAttemptParams",
+        host_chain_id,
+        params,
+    )
+}
+
+/// 从自己的链向Quiz所在的链提交答案的变更请求
+pub fn remote_submit_answers(
+    host_chain_id: &ChainId,
+    params: &SubmitAnswersParams,
+) -> Result<GraphQLRequest, ClientError> {
+    remote_mutation(
+        "remoteSubmitAnswers",
+        "SubmitAnswersParams",
+        host_chain_id,
+        params,
+    )
+}
+
+/// 订阅Quiz所在的链的公告的变更请求
+pub fn subscribe(host_chain_id: &ChainId) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "subscribe",
+        "mutation($hostChainId: ChainId!) { subscribe(hostChainId: $hostChainId) }".to_string(),
+        json!({ "hostChainId": host_chain_id }),
+    )
+}
+
+/// 取消订阅的变更请求
+pub fn unsubscribe(host_chain_id: &ChainId) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "unsubscribe",
+        "mutation($hostChainId: ChainId!) { unsubscribe(hostChainId: $hostChainId) }".to_string(),
+        json!({ "hostChainId": host_chain_id }),
+    )
+}
+
+/// 修改Quiz的变更请求
+pub fn update_quiz(params: &UpdateQuizParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("updateQuiz", "UpdateQuizParams", params)
+}
+
+/// 删除Quiz的变更请求
+pub fn delete_quiz(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "deleteQuiz",
+        "mutation($quizId: Int!) { deleteQuiz(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 取消Quiz的变更请求
+pub fn cancel_quiz(quiz_id: u64) -> GraphQLRequest {
+    GraphQLRequest::new(
+        "cancelQuiz",
+        "mutation($quizId: Int!) { cancelQuiz(quizId: $quizId) }".to_string(),
+        json!({ "quizId": quiz_id }),
+    )
+}
+
+/// 提交答案的变更请求
+pub fn submit_answers(params: &SubmitAnswersParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("submitAnswers", "SubmitAnswersParams", params)
+}
+
+/// 设置答题隐私的变更请求
+pub fn set_privacy(params: &SetPrivacyParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setPrivacy", "SetPrivacyParams", params)
+}
+
+/// 登记唯一昵称的变更请求
+pub fn set_nickname(params: &SetNicknameParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setNickname", "SetNicknameParams", params)
+}
+
+/// 从往期Quiz抽题创建练习卷的变更请求
+pub fn build_practice_exam(
+    params: &BuildPracticeExamParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("buildPracticeExam", "BuildPracticeExamParams", params)
+}
+
+/// 设置答题私人笔记的变更请求
+pub fn set_attempt_note(params: &SetAttemptNoteParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setAttemptNote", "SetAttemptNoteParams", params)
+}
+
+/// 收藏或取消收藏问题的变更请求
+pub fn set_bookmark(params: &SetBookmarkParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setBookmark", "SetBookmarkParams", params)
+}
+
+/// 公开封存问题内容的变更请求
+pub fn reveal_content(params: &RevealContentParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("revealContent", "RevealContentParams", params)
+}
+
+/// 公开答案校验和的盐的变更请求
+pub fn reveal_answer_key(params: &RevealAnswerKeyParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("revealAnswerKey", "RevealAnswerKeyParams", params)
+}
+
+/// 公布测验结果的变更请求
+pub fn reveal_results(params: &RevealResultsParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("revealResults", "RevealResultsParams", params)
+}
+
+/// 监考人确认答卷的变更请求
+pub fn confirm_attempt(params: &ConfirmAttemptParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("confirmAttempt", "ConfirmAttemptParams", params)
+}
+
+/// 开始答题会话的变更请求
+pub fn start_attempt(params: &StartAttemptParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("startAttempt", "StartAttemptParams", params)
+}
+
+/// 逐题保存答案的变更请求
+pub fn save_answer(params: &SaveAnswerParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("saveAnswer", "SaveAnswerParams", params)
+}
+
+/// 按已保存的答案交卷的变更请求
+pub fn finalize_attempt(params: &FinalizeAttemptParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("finalizeAttempt", "FinalizeAttemptParams", params)
+}
+
+/// 答题会话心跳的变更请求
+pub fn heartbeat(params: &HeartbeatParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("heartbeat", "HeartbeatParams", params)
+}
+
+/// 自动提交超时答题会话的变更请求
+pub fn expire_abandoned_attempts(
+    params: &ExpireAbandonedAttemptsParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation(
+        "expireAbandonedAttempts",
+        "ExpireAbandonedAttemptsParams",
+        params,
+    )
+}
+
+/// 注册组织的变更请求
+pub fn register_org(params: &RegisterOrgParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("registerOrg", "RegisterOrgParams", params)
+}
+
+/// 添加组织成员的变更请求
+pub fn add_org_member(params: &OrgMemberParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("addOrgMember", "OrgMemberParams", params)
+}
+
+/// 移除组织成员的变更请求
+pub fn remove_org_member(params: &OrgMemberParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("removeOrgMember", "OrgMemberParams", params)
+}
+
+/// 替换组织品牌元数据的变更请求
+pub fn set_org_branding(params: &SetOrgBrandingParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setOrgBranding", "SetOrgBrandingParams", params)
+}
+
+/// 替换组织设置的变更请求
+pub fn set_org_settings(params: &SetOrgSettingsParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setOrgSettings", "SetOrgSettingsParams", params)
+}
+
+/// 任命组织管理员的变更请求
+pub fn add_org_admin(params: &OrgMemberParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("addOrgAdmin", "OrgMemberParams", params)
+}
+
+/// 撤销组织管理员的变更请求
+pub fn remove_org_admin(params: &OrgMemberParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("removeOrgAdmin", "OrgMemberParams", params)
+}
+
+/// 替换Quiz自定义元数据的变更请求
+pub fn set_quiz_metadata(params: &SetQuizMetadataParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("setQuizMetadata", "SetQuizMetadataParams", params)
+}
+
+/// 重命名标签的变更请求
+pub fn rename_tag(params: &RenameTagParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("renameTag", "RenameTagParams", params)
+}
+
+/// 合并标签的变更请求
+pub fn merge_tags(params: &MergeTagsParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("mergeTags", "MergeTagsParams", params)
+}
+
+/// 为多个Quiz添加标签的变更请求
+pub fn apply_tag(params: &ApplyTagParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("applyTag", "ApplyTagParams", params)
+}
+
+/// 问题申诉投票的变更请求
+pub fn appeal_question(params: &AppealQuestionParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("appealQuestion", "AppealQuestionParams", params)
+}
+
+/// 处理重新评分案件的变更请求
+pub fn resolve_regrade_case(
+    params: &ResolveRegradeCaseParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("resolveRegradeCase", "ResolveRegradeCaseParams", params)
+}
+
+/// 添加管理员的变更请求
+pub fn add_admin(admin: &Principal) -> Result<GraphQLRequest, ClientError> {
+    mutation("addAdmin", "Principal", admin)
+}
+
+/// 添加平台禁排时段的变更请求
+pub fn add_blackout_window(
+    params: &AddBlackoutWindowParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation("addBlackoutWindow", "AddBlackoutWindowParams", params)
+}
+
+/// 移除平台禁排时段的变更请求
+pub fn remove_blackout_window(window_id: u64) -> Result<GraphQLRequest, ClientError> {
+    mutation("removeBlackoutWindow", "Int", &window_id)
+}
+
+/// 发起管理操作提案的变更请求
+pub fn propose_admin_action(action: &AdminAction) -> Result<GraphQLRequest, ClientError> {
+    mutation("proposeAdminAction", "AdminAction", action)
+}
+
+/// 确认管理操作提案的变更请求
+pub fn confirm_admin_action(proposal_id: u64) -> Result<GraphQLRequest, ClientError> {
+    mutation("confirmAdminAction", "Int", &proposal_id)
+}
+
+/// 把通知标记为已读的变更请求
+pub fn mark_notifications_read(
+    params: &MarkNotificationsReadParams,
+) -> Result<GraphQLRequest, ClientError> {
+    mutation(
+        "markNotificationsRead",
+        "MarkNotificationsReadParams",
+        params,
+    )
+}
+
+/// 按评分标准给问答题评分的变更请求
+pub fn grade_essay(params: &GradeEssayParams) -> Result<GraphQLRequest, ClientError> {
+    mutation("gradeEssay", "GradeEssayParams", params)
+}
+
+/// 解析GraphQL响应体中指定顶层字段的数据
+pub fn parse_response<T: DeserializeOwned>(body: &str, field: &str) -> Result<T, ClientError> {
+    let response: Value = serde_json::from_str(body)?;
+    if let Some(errors) = response.get("errors").and_then(Value::as_array) {
+        if !errors.is_empty() {
+            let messages = errors
+                .iter()
+                .map(|error| {
+                    error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown error")
+                        .to_string()
+                })
+                .collect();
+            return Err(ClientError::GraphQL(messages));
+        }
+    }
+    let data = response
+        .get("data")
+        .and_then(|data| data.get(field))
+        .cloned()
+        .ok_or_else(|| ClientError::MissingField(field.to_string()))?;
+    Ok(serde_json::from_value(convert_keys(data, snake_case))?)
+}
+
+/// 解析响应中的建议性限流信息
+pub fn parse_rate_limit(body: &str) -> Result<Option<RateLimitHint>, ClientError> {
+    let response: Value = serde_json::from_str(body)?;
+    match response.pointer("/extensions/rateLimit") {
+        Some(hint) => Ok(Some(serde_json::from_value(hint.clone())?)),
+        None => Ok(None),
+    }
+}
+
+/// 解析 quiz_set 响应
+pub fn parse_quiz_set(body: &str) -> Result<Option<QuizSetView>, ClientError> {
+    parse_response(body, "quizSet")
+}
+
+/// 解析 quiz_sets 响应
+pub fn parse_quiz_sets(body: &str) -> Result<Vec<QuizSetView>, ClientError> {
+    parse_response(body, "quizSets")
+}
+
+/// 解析 user_attempts 响应
+pub fn parse_user_attempts(body: &str) -> Result<Vec<QuizAttempt>, ClientError> {
+    parse_response(body, "userAttempts")
+}
+
+/// 解析 quiz_sets_count 响应
+pub fn parse_quiz_sets_count(body: &str) -> Result<u64, ClientError> {
+    parse_response(body, "quizSetsCount")
+}
+
+/// 解析 quiz_sets_connection、org_quizzes_connection 或 quizzes_by_tag_connection 响应，
+/// field 为对应的查询名
+pub fn parse_quiz_sets_connection(
+    body: &str,
+    field: &str,
+) -> Result<Connection<QuizSetView>, ClientError> {
+    parse_response(body, field)
+}
+
+/// 解析 user_attempts_connection 响应
+pub fn parse_user_attempts_connection(body: &str) -> Result<Connection<QuizAttempt>, ClientError> {
+    parse_response(body, "userAttemptsConnection")
+}
+
+/// 解析 user 响应
+pub fn parse_user(body: &str) -> Result<UserView, ClientError> {
+    parse_response(body, "user")
+}
+
+/// 解析 user_by_nickname 响应
+pub fn parse_user_by_nickname(body: &str) -> Result<Option<AccountOwner>, ClientError> {
+    parse_response(body, "userByNickname")
+}
+
+/// 解析 user_streak 响应
+pub fn parse_user_streak(body: &str) -> Result<UserStreakView, ClientError> {
+    parse_response(body, "userStreak")
+}
+
+/// 解析 global_leaderboard 响应
+pub fn parse_global_leaderboard(body: &str) -> Result<Vec<GlobalStandingView>, ClientError> {
+    parse_response(body, "globalLeaderboard")
+}
+
+/// 解析 global_rank 响应
+pub fn parse_global_rank(body: &str) -> Result<Option<GlobalStandingView>, ClientError> {
+    parse_response(body, "globalRank")
+}
+
+/// 解析 user_stats 响应
+pub fn parse_user_stats(body: &str) -> Result<UserStatsView, ClientError> {
+    parse_response(body, "userStats")
+}
+
+/// 解析 attempt_by_receipt 响应
+pub fn parse_attempt_by_receipt(body: &str) -> Result<Option<UserAttemptView>, ClientError> {
+    parse_response(body, "attemptByReceipt")
+}
+
+/// 解析 attempt_detail 响应
+pub fn parse_attempt_detail(body: &str) -> Result<Option<AttemptDetail>, ClientError> {
+    parse_response(body, "attemptDetail")
+}
+
+/// 解析 leaderboard_snapshots 响应
+pub fn parse_leaderboard_snapshots(
+    body: &str,
+) -> Result<Vec<LeaderboardSnapshotView>, ClientError> {
+    parse_response(body, "leaderboardSnapshots")
+}
+
+/// 解析 leaderboard / quiz_leaderboard / decayed_leaderboard 响应
+pub fn parse_leaderboard(body: &str, field: &str) -> Result<Vec<UserAttemptView>, ClientError> {
+    parse_response(body, field)
+}
+
+/// 解析 leaderboard_connection 或 quiz_leaderboard_connection 响应，field 为对应的查询名
+pub fn parse_leaderboard_connection(
+    body: &str,
+    field: &str,
+) -> Result<Connection<UserAttemptView>, ClientError> {
+    parse_response(body, field)
+}
+
+/// 解析 quiz_sets_envelope 响应
+pub fn parse_quiz_sets_envelope(body: &str) -> Result<Envelope<QuizSetView>, ClientError> {
+    parse_response(body, "quizSetsEnvelope")
+}
+
+/// 解析 validate_quiz 响应
+pub fn parse_violations(body: &str) -> Result<Vec<Violation>, ClientError> {
+    parse_response(body, "validateQuiz")
+}
+
+/// 解析 score_preview 响应
+pub fn parse_score_preview(body: &str) -> Result<ScorePreview, ClientError> {
+    parse_response(body, "scorePreview")
+}
+
+/// 解析 practice_exam_pool 响应
+pub fn parse_practice_exam_pool(body: &str) -> Result<Vec<PracticePoolQuestion>, ClientError> {
+    parse_response(body, "practiceExamPool")
+}
+
+/// 解析 question_stats 响应
+pub fn parse_question_stats(body: &str) -> Result<Vec<QuestionStats>, ClientError> {
+    parse_response(body, "questionStats")
+}
+
+/// 解析 quiz_stats 响应
+pub fn parse_quiz_stats(body: &str) -> Result<QuizStats, ClientError> {
+    parse_response(body, "quizStats")
+}
+
+/// 解析 remote_quizzes 响应
+pub fn parse_remote_quizzes(body: &str) -> Result<Vec<RemoteQuizView>, ClientError> {
+    parse_response(body, "remoteQuizzes")
+}
+
+/// 解析 remote_quizzes_connection 响应
+pub fn parse_remote_quizzes_connection(
+    body: &str,
+) -> Result<Connection<RemoteQuizView>, ClientError> {
+    parse_response(body, "remoteQuizzesConnection")
+}
+
+/// 解析 bookmarked_questions 响应
+pub fn parse_bookmarked_questions(body: &str) -> Result<Vec<BookmarkedQuestion>, ClientError> {
+    parse_response(body, "bookmarkedQuestions")
+}
+
+/// 解析 certificate 响应
+pub fn parse_certificate(body: &str) -> Result<Option<CertificateView>, ClientError> {
+    parse_response(body, "certificate")
+}
+
+/// 解析 submittable_by 响应
+pub fn parse_submittable_by(body: &str) -> Result<SubmissionWindow, ClientError> {
+    parse_response(body, "submittableBy")
+}
+
+/// 解析 duplicate_questions 响应
+pub fn parse_duplicate_questions(body: &str) -> Result<Vec<DuplicateQuestionGroup>, ClientError> {
+    parse_response(body, "duplicateQuestions")
+}
+
+/// 解析 app_summary 响应
+pub fn parse_app_summary(body: &str) -> Result<AppSummary, ClientError> {
+    parse_response(body, "appSummary")
+}
+
+/// 解析 export_my_attempts 响应，返回导出文档
+pub fn parse_export_my_attempts(body: &str) -> Result<AttemptHistoryExport, ClientError> {
+    let document: String = parse_response(body, "exportMyAttempts")?;
+    Ok(serde_json::from_str(&document)?)
+}
+
+/// 解析 question_order 响应
+pub fn parse_question_order(body: &str) -> Result<Vec<u32>, ClientError> {
+    parse_response(body, "questionOrder")
+}
+
+/// 解析 prize_pool 响应
+pub fn parse_prize_pool(body: &str) -> Result<Option<PrizePoolView>, ClientError> {
+    parse_response(body, "prizePool")
+}
+
+/// 解析 finalization 响应
+pub fn parse_finalization(body: &str) -> Result<Option<FinalizationView>, ClientError> {
+    parse_response(body, "finalization")
+}
+
+/// 解析 archived_quizzes 响应
+pub fn parse_archived_quizzes(body: &str) -> Result<Vec<ArchivedQuizView>, ClientError> {
+    parse_response(body, "archivedQuizzes")
+}
+
+/// 解析 retention_policy 响应
+pub fn parse_retention_policy(body: &str) -> Result<Option<u32>, ClientError> {
+    parse_response(body, "retentionPolicy")
+}
+
+/// 解析 quiz_by_share_token 响应
+pub fn parse_quiz_by_share_token(body: &str) -> Result<Option<QuizSetView>, ClientError> {
+    parse_response(body, "quizByShareToken")
+}
+
+/// 解析 preview_attempt 响应
+pub fn parse_preview_attempt(body: &str) -> Result<ScorePreview, ClientError> {
+    parse_response(body, "previewAttempt")
+}
+
+/// 解析 preview_tokens 响应
+pub fn parse_preview_tokens(body: &str) -> Result<Vec<PreviewTokenView>, ClientError> {
+    parse_response(body, "previewTokens")
+}
+
+/// 解析 my_rank 响应
+pub fn parse_my_rank(body: &str) -> Result<Option<LeaderboardRank>, ClientError> {
+    parse_response(body, "myRank")
+}
+
+/// 解析 leaderboard_window 响应
+pub fn parse_leaderboard_window(body: &str) -> Result<Option<LeaderboardWindow>, ClientError> {
+    parse_response(body, "leaderboardWindow")
+}
+
+/// 解析 playoff 响应
+pub fn parse_playoff(body: &str) -> Result<Option<PlayoffView>, ClientError> {
+    parse_response(body, "playoff")
+}
+
+/// 解析 saved_answers 响应
+pub fn parse_saved_answers(body: &str) -> Result<Vec<SavedAnswer>, ClientError> {
+    parse_response(body, "savedAnswers")
+}
+
+/// 解析 quiz_review 响应
+pub fn parse_quiz_review(body: &str) -> Result<Vec<QuestionReview>, ClientError> {
+    parse_response(body, "quizReview")
+}
+
+/// 解析 org 响应
+pub fn parse_org(body: &str) -> Result<Option<OrgView>, ClientError> {
+    parse_response(body, "org")
+}
+
+/// 解析 quizzes_by_tag 响应
+pub fn parse_quizzes_by_tag(body: &str) -> Result<Vec<QuizSetView>, ClientError> {
+    parse_response(body, "quizzesByTag")
+}
+
+/// 解析 tags 响应
+pub fn parse_tags(body: &str) -> Result<Vec<TagCount>, ClientError> {
+    parse_response(body, "tags")
+}
+
+/// 解析 quiz_announcements 响应
+pub fn parse_quiz_announcements(body: &str) -> Result<Vec<CreatorAnnouncement>, ClientError> {
+    parse_response(body, "quizAnnouncements")
+}
+
+/// 解析 search_quizzes 响应
+pub fn parse_search_quizzes(body: &str) -> Result<Vec<QuizSetView>, ClientError> {
+    parse_response(body, "searchQuizzes")
+}
+
+/// 解析 quizzes_by_category 响应
+pub fn parse_quizzes_by_category(body: &str) -> Result<Vec<QuizSetView>, ClientError> {
+    parse_response(body, "quizzesByCategory")
+}
+
+/// 解析 categories 响应
+pub fn parse_categories(body: &str) -> Result<Vec<CategoryCount>, ClientError> {
+    parse_response(body, "categories")
+}
+
+/// 解析 orgs 响应
+pub fn parse_orgs(body: &str) -> Result<Vec<OrgView>, ClientError> {
+    parse_response(body, "orgs")
+}
+
+/// 解析 org_analytics 响应
+pub fn parse_org_analytics(body: &str) -> Result<OrgAnalytics, ClientError> {
+    parse_response(body, "orgAnalytics")
+}
+
+/// 解析 admins 响应
+pub fn parse_admins(body: &str) -> Result<Vec<String>, ClientError> {
+    parse_response(body, "admins")
+}
+
+/// 解析 event_stream 响应
+pub fn parse_event_stream(body: &str) -> Result<EventStreamView, ClientError> {
+    parse_response(body, "eventStream")
+}
+
+/// 解析 user_notifications 响应
+pub fn parse_user_notifications(body: &str) -> Result<Vec<NotificationView>, ClientError> {
+    parse_response(body, "userNotifications")
+}
+
+/// 解析 similarity_report 响应
+pub fn parse_similarity_report(body: &str) -> Result<Vec<SimilarityPair>, ClientError> {
+    parse_response(body, "similarityReport")
+}
+
+/// 解析 blackout_windows 响应
+pub fn parse_blackout_windows(body: &str) -> Result<Vec<BlackoutWindowView>, ClientError> {
+    parse_response(body, "blackoutWindows")
+}
+
+/// 解析 schedule_conflicts 响应
+pub fn parse_schedule_conflicts(body: &str) -> Result<Vec<BlackoutWindowView>, ClientError> {
+    parse_response(body, "scheduleConflicts")
+}
+
+/// 解析 admin_proposals 响应
+pub fn parse_admin_proposals(body: &str) -> Result<Vec<AdminProposalView>, ClientError> {
+    parse_response(body, "adminProposals")
+}
+
+/// 将Rust参数序列化为GraphQL输入对象（字段名转为驼峰命名）
+fn to_graphql_value(value: &impl Serialize) -> Result<Value, ClientError> {
+    Ok(convert_keys(serde_json::to_value(value)?, camel_case))
+}
+
+fn convert_keys(value: Value, convert: fn(&str) -> String) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (convert(&key), convert_keys(value, convert)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| convert_keys(value, convert))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn snake_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_uppercase() {
+            result.push('_');
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -3,17 +3,83 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-mod state;
+use std::collections::BTreeSet;
 
-use linera_sdk::linera_base_types::TimeDelta;
+use linera_sdk::abis::fungible::{self, FungibleOperation, FungibleTokenAbi};
 use linera_sdk::{
     linera_base_types::WithContractAbi,
-    views::{RootView, View},
+    views::{MapView, RootView, View},
     Contract, ContractRuntime,
 };
 
-use crate::state::{Question, QuizSet, QuizState, UserAttempt};
-use quiz::{CreateQuizParams, LeaderboardEntry, Operation, SubmitAnswersParams};
+use linera_sdk::linera_base_types::{
+    Account, AccountOwner, Amount, ApplicationId, ChainId, CryptoHash, StreamName, TimeDelta,
+    Timestamp,
+};
+use quiz::logging::{self, variant_name, Level, Span, CONTRACT_TARGET};
+use quiz::packing::unpack_answers;
+use quiz::scoring::{
+    check_answer_kind, fixed_points, leaderboard_order, policy_attempt, policy_score, rubric_score,
+    score_answered, score_answers, total_score, FixedScore,
+};
+use quiz::similarity::fingerprint;
+use quiz::state::{
+    answer_key_hash, content_commitment, nickname_key, playoff_standings, preview_token,
+    prize_ties, sample_questions, standings_hash, AdminProposal, ArchivedQuiz, AttemptSession,
+    Finalization, LeaderboardSnapshot, Notification, Organization, Playoff, PreviewToken,
+    PrizeDistribution, PrizePool, PrizeSweep, Question, QuestionAppeal, QuizSet, QuizState,
+    RemoteQuiz, Trophy, UnclaimedPrize, UserAttempt, UserStats, DAY_MICROS,
+};
+use quiz::validation::{
+    valid_prize_splits, validate_announcement, validate_attempt_note, validate_blackout_window,
+    validate_blackouts, validate_create_quiz, validate_metadata, validate_nickname,
+    validate_org_limits, validate_org_slug, validate_practice_exam, validate_preview_token,
+    validate_question_media, validate_questions, validate_questions_per_attempt,
+    validate_retention_days, validate_schedule, validate_tags, validate_time_multiplier,
+    QuizSchedule, MAX_ANNOUNCEMENTS_PER_QUIZ,
+};
+use quiz::{
+    log_event, AddBlackoutWindowParams, AddTokenPrizeParams, AdminAction, AnnouncementPostedEvent,
+    Answer, AnswerSubmittedEvent, AppEvent, AppealQuestionParams, ApplyTagParams,
+    AttemptAbandonedEvent, AttemptPolicy, AttestationAbi, AttestationOperation,
+    BuildPracticeExamParams, CertificateAbi, CertificateMetadata, CertificateOperation,
+    ConfirmAttemptParams, ContentRevealedEvent, CreatePreviewTokenParams, CreateQuizParams,
+    CreatorAnnouncement, DisputeStatus, DuplicateQuestion, ExpireAbandonedAttemptsParams,
+    ExtraAttemptGrantedEvent, FinalizeAttemptParams, GradeEssayParams, GrantAccommodationParams,
+    GrantExtraAttemptParams, HeartbeatParams, LeaderboardEntry, LeaderboardFrozenEvent,
+    MarkNotificationsReadParams, MergeTagsParams, Message, NicknameTakenError, NotificationKind,
+    Operation, OrgMemberParams, OrgRole, OrgSettings, PlayoffStartedEvent, PostAnnouncementParams,
+    Principal, PrizePayout, PrizeSweptEvent, PrizesDistributedEvent, QuestionAnswer,
+    QuestionParams, QuestionRef, QuizCancelledEvent, QuizCreatedEvent, QuizError,
+    QuizFinalizedEvent, QuizLockedError, QuizParameters, QuizResponse, QuizResult, QuizStartMode,
+    QuizStartedEvent, RegisterOrgParams, RegradeCaseOpenedEvent, RenameTagParams,
+    ResolveRegradeCaseParams, RevealAnswerKeyParams, RevealContentParams, RevealResultsParams,
+    SaveAnswerParams, ScoringStrategy, SetAttemptNoteParams, SetBookmarkParams, SetNicknameParams,
+    SetOrgBrandingParams, SetOrgSettingsParams, SetPrivacyParams, SetQuizMetadataParams,
+    StartAttemptParams, SubmitAnswersParams, TimeLimitExceededError, TokenPrize, UpdateQuizParams,
+    EVENT_STREAM_NAME,
+};
+
+/// 冻结排行榜时获得奖杯的名次数
+const PODIUM_SIZE: usize = 3;
+
+/// QuizFinalized 事件中附带的最终排行榜名次数
+const FINAL_TOP_ENTRIES: usize = 10;
+
+/// 练习卷每位答题者可以答题的次数
+const PRACTICE_EXAM_MAX_ATTEMPTS: u32 = 10;
+
+/// 每次归档清理最多处理的Quiz数，其余留给下一次调用
+const MAX_QUIZZES_PRUNED_PER_CALL: usize = 20;
+
+/// 加赛每道题的答题时限（秒）
+const PLAYOFF_SECS_PER_QUESTION: u64 = 60;
+
+/// 原本位于排行榜前多少名的答题者被超过时收到通知
+const OVERTAKEN_NOTIFY_RANKS: usize = 10;
+
+/// Quiz开始或发布公告时最多通知的用户数，其余用户在Quiz页面上查看
+const MAX_AUDIENCE_NOTIFICATIONS: usize = 200;
 
 pub struct QuizContract {
     state: QuizState,
@@ -27,15 +93,16 @@ impl WithContractAbi for QuizContract {
 }
 
 impl Contract for QuizContract {
-    type Message = ();
+    type Message = Message;
     type InstantiationArgument = ();
-    type Parameters = ();
-    type EventValue = ();
+    type Parameters = QuizParameters;
+    type EventValue = AppEvent;
 
-    async fn load(runtime: ContractRuntime<Self>) -> Self {
+    async fn load(mut runtime: ContractRuntime<Self>) -> Self {
         let state = QuizState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load QuizState");
+        logging::init(runtime.application_parameters().log_level);
         QuizContract { state, runtime }
     }
 
@@ -45,230 +112,4399 @@ impl Contract for QuizContract {
         if *current_value == 0 {
             self.state.next_quiz_id.set(1);
         }
+        // 部署者成为第一个管理员
+        if let Some(signer) = self.runtime.authenticated_signer() {
+            self.state
+                .admins
+                .insert(&Principal::Owner(signer))
+                .expect("Failed to add initial admin");
+        }
     }
 
     async fn execute_operation(&mut self, operation: Operation) -> Self::Response {
-        match operation {
-            Operation::CreateQuiz(params) => {
-                self.create_quiz(params).await;
+        let _span = Span::enter(CONTRACT_TARGET, || variant_name(&operation));
+        let response = match operation {
+            Operation::CreateQuiz(params) => self.create_quiz(*params).await,
+            Operation::FreezeLeaderboard { quiz_id } => {
+                self.freeze_leaderboard(quiz_id).await;
+                QuizResponse::Ok
             }
-            Operation::SubmitAnswers(params) => {
-                self.submit_answers(params).await;
+            Operation::FinalizeQuiz { quiz_id } => {
+                self.finalize_quiz(quiz_id).await;
+                QuizResponse::Ok
             }
-        }
+            Operation::GrantExtraAttempt(params) => {
+                self.grant_extra_attempt(params).await;
+                QuizResponse::Ok
+            }
+            Operation::GrantAccommodation(params) => {
+                self.grant_accommodation(params).await;
+                QuizResponse::Ok
+            }
+            Operation::PostAnnouncement(params) => {
+                self.post_announcement(params).await;
+                QuizResponse::Ok
+            }
+            Operation::CreatePreviewToken(params) => {
+                let token = self.create_preview_token(params).await;
+                QuizResponse::PreviewTokenCreated { token }
+            }
+            Operation::RevokePreviewToken { token } => {
+                self.revoke_preview_token(token).await;
+                QuizResponse::Ok
+            }
+            Operation::PruneQuizzes { ended_before } => {
+                let creator = self.signer();
+                self.prune_quizzes(creator, ended_before).await;
+                QuizResponse::Ok
+            }
+            Operation::SetRetentionPolicy { retention_days } => {
+                self.set_retention_policy(retention_days);
+                QuizResponse::Ok
+            }
+            Operation::ApplyRetentionPolicy { creator } => {
+                self.apply_retention_policy(creator).await;
+                QuizResponse::Ok
+            }
+            Operation::DistributePrizes { quiz_id } => {
+                self.distribute_prizes(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::AddTokenPrize(params) => {
+                self.add_token_prize(params).await;
+                QuizResponse::Ok
+            }
+            Operation::ClaimPrize { quiz_id } => {
+                self.claim_prize(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::SweepUnclaimedPrizes { quiz_id } => {
+                self.sweep_unclaimed_prizes(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::RecordQuizView { quiz_id } => {
+                self.record_quiz_view(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::UpdateQuiz(params) => {
+                self.update_quiz(params).await?;
+                QuizResponse::Ok
+            }
+            Operation::StartQuiz { quiz_id } => {
+                self.start_quiz(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::DeleteQuiz { quiz_id } => {
+                self.delete_quiz(quiz_id).await?;
+                QuizResponse::Ok
+            }
+            Operation::CancelQuiz { quiz_id } => {
+                self.cancel_quiz(quiz_id).await;
+                QuizResponse::Ok
+            }
+            Operation::SubmitAnswers(params) => self.submit_answers(params).await?,
+            Operation::SetPrivacy(params) => {
+                self.set_privacy(params);
+                QuizResponse::Ok
+            }
+            Operation::SetNickname(params) => {
+                self.set_nickname(params).await?;
+                QuizResponse::Ok
+            }
+            Operation::BuildPracticeExam(params) => self.build_practice_exam(params).await,
+            Operation::SetAttemptNote(params) => {
+                self.set_attempt_note(params).await;
+                QuizResponse::Ok
+            }
+            Operation::SetBookmark(params) => {
+                self.set_bookmark(params).await;
+                QuizResponse::Ok
+            }
+            Operation::MarkNotificationsRead(params) => {
+                self.mark_notifications_read(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RevealContent(params) => {
+                self.reveal_content(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RevealAnswerKey(params) => {
+                self.reveal_answer_key(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RevealResults(params) => {
+                self.reveal_results(params).await;
+                QuizResponse::Ok
+            }
+            Operation::ConfirmAttempt(params) => {
+                self.confirm_attempt(params).await;
+                QuizResponse::Ok
+            }
+            Operation::StartAttempt(params) => {
+                let question_order = self.start_attempt(params).await;
+                QuizResponse::AttemptStarted { question_order }
+            }
+            Operation::Subscribe { host_chain_id } => {
+                self.runtime
+                    .prepare_message(Message::Subscribe)
+                    .send_to(host_chain_id);
+                QuizResponse::Ok
+            }
+            Operation::Unsubscribe { host_chain_id } => {
+                self.runtime
+                    .prepare_message(Message::Unsubscribe)
+                    .send_to(host_chain_id);
+                QuizResponse::Ok
+            }
+            Operation::RemoteStartAttempt {
+                host_chain_id,
+                params,
+            } => {
+                self.send_remote(host_chain_id, Message::RemoteStartAttempt { params });
+                QuizResponse::Ok
+            }
+            Operation::RemoteSubmitAnswers {
+                host_chain_id,
+                params,
+            } => {
+                self.send_remote(host_chain_id, Message::RemoteSubmission { params });
+                QuizResponse::Ok
+            }
+            Operation::SaveAnswer(params) => {
+                self.save_answer(params).await;
+                QuizResponse::Ok
+            }
+            Operation::Heartbeat(params) => {
+                self.heartbeat(params).await;
+                QuizResponse::Ok
+            }
+            Operation::FinalizeAttempt(params) => self.finalize_session(params).await?,
+            Operation::ExpireAbandonedAttempts(params) => {
+                self.expire_abandoned_attempts(params).await;
+                QuizResponse::Ok
+            }
+            Operation::SetQuizMetadata(params) => {
+                self.set_quiz_metadata(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RenameTag(params) => {
+                self.rename_tag(params).await;
+                QuizResponse::Ok
+            }
+            Operation::MergeTags(params) => {
+                self.merge_tags(params).await;
+                QuizResponse::Ok
+            }
+            Operation::ApplyTag(params) => {
+                self.apply_tag(params).await;
+                QuizResponse::Ok
+            }
+            Operation::AppealQuestion(params) => {
+                self.appeal_question(params).await;
+                QuizResponse::Ok
+            }
+            Operation::ResolveRegradeCase(params) => {
+                self.resolve_regrade_case(params).await;
+                QuizResponse::Ok
+            }
+            Operation::GradeEssay(params) => {
+                self.grade_essay(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RegisterOrg(params) => {
+                self.register_org(params).await;
+                QuizResponse::Ok
+            }
+            Operation::AddOrgMember(params) => {
+                self.add_org_member(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RemoveOrgMember(params) => {
+                self.remove_org_member(params).await;
+                QuizResponse::Ok
+            }
+            Operation::SetOrgBranding(params) => {
+                self.set_org_branding(params).await;
+                QuizResponse::Ok
+            }
+            Operation::SetOrgSettings(params) => {
+                self.set_org_settings(params).await;
+                QuizResponse::Ok
+            }
+            Operation::AddOrgAdmin(params) => {
+                self.add_org_admin(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RemoveOrgAdmin(params) => {
+                self.remove_org_admin(params).await;
+                QuizResponse::Ok
+            }
+            Operation::AddAdmin(admin) => {
+                self.add_admin(admin).await;
+                QuizResponse::Ok
+            }
+            Operation::AddBlackoutWindow(params) => {
+                self.add_blackout_window(params).await;
+                QuizResponse::Ok
+            }
+            Operation::RemoveBlackoutWindow(window_id) => {
+                self.remove_blackout_window(window_id).await;
+                QuizResponse::Ok
+            }
+            Operation::ProposeAdminAction(action) => {
+                let proposal_id = self.propose_admin_action(action).await;
+                QuizResponse::ProposalCreated { proposal_id }
+            }
+            Operation::ConfirmAdminAction(proposal_id) => {
+                self.confirm_admin_action(proposal_id).await;
+                QuizResponse::Ok
+            }
+        };
+        Ok(response)
     }
 
     async fn store(mut self) {
         self.state.save().await.expect("Failed to save state");
     }
 
-    async fn execute_message(&mut self, _message: ()) {
-        // Not implemented yet
+    async fn execute_message(&mut self, message: Message) {
+        let _span = Span::enter(CONTRACT_TARGET, || variant_name(&message));
+        // 被Quiz所在的链拒绝而退回的消息不做处理，答题者可以重新提交
+        if self.runtime.message_is_bouncing() == Some(true) {
+            log_event!(
+                Level::Warn,
+                CONTRACT_TARGET,
+                "message bounced",
+                kind = variant_name(&message),
+            );
+            return;
+        }
+        match message {
+            Message::RemoteStartAttempt { params } => {
+                self.start_attempt(params).await;
+            }
+            Message::RemoteSubmission { params } => {
+                // 以本链执行消息的时间评分，答题者链上的时间不可信
+                self.submit_answers(params)
+                    .await
+                    .unwrap_or_else(|error| panic!("{error}"));
+            }
+            Message::Subscribe => {
+                let origin = self.message_origin();
+                self.state
+                    .subscribers
+                    .insert(&origin)
+                    .expect("Failed to add subscriber");
+                self.send_catalog(origin).await;
+            }
+            Message::Unsubscribe => {
+                let origin = self.message_origin();
+                self.state
+                    .subscribers
+                    .remove(&origin)
+                    .expect("Failed to remove subscriber");
+            }
+            Message::QuizCreated(announcement) | Message::QuizUpdated(announcement) => {
+                let key = (self.message_origin(), announcement.quiz_id);
+                let standings_hash = self
+                    .state
+                    .remote_quizzes
+                    .get(&key)
+                    .await
+                    .expect("Failed to retrieve remote quiz")
+                    .and_then(|remote_quiz| remote_quiz.standings_hash);
+                let remote_quiz = RemoteQuiz {
+                    announcement,
+                    standings_hash,
+                    refreshed_at: self.runtime.system_time(),
+                };
+                let _ = self.state.remote_quizzes.insert(&key, remote_quiz);
+            }
+            Message::QuizWithdrawn { quiz_id } => {
+                let key = (self.message_origin(), quiz_id);
+                self.state
+                    .remote_quizzes
+                    .remove(&key)
+                    .expect("Failed to remove remote quiz");
+            }
+            Message::QuizFinalized {
+                quiz_id,
+                standings_hash,
+                ..
+            } => {
+                let key = (self.message_origin(), quiz_id);
+                if let Some(mut remote_quiz) = self
+                    .state
+                    .remote_quizzes
+                    .get(&key)
+                    .await
+                    .expect("Failed to retrieve remote quiz")
+                {
+                    remote_quiz.standings_hash = Some(standings_hash);
+                    remote_quiz.refreshed_at = self.runtime.system_time();
+                    let _ = self.state.remote_quizzes.insert(&key, remote_quiz);
+                }
+            }
+        }
     }
 }
 
 impl QuizContract {
-    async fn create_quiz(&mut self, params: CreateQuizParams) {
+    async fn create_quiz(&mut self, params: CreateQuizParams) -> QuizResponse {
         let current_time = self.runtime.system_time();
 
-        // 验证测验时间范围
-        let start_time_millis = params
-            .start_time
-            .parse::<u64>()
-            .expect("Invalid start time format");
-
-        // 检查时间戳长度是否合理（毫秒级时间戳应该是13位左右）
-        assert!(
-            start_time_millis.to_string().len() >= 10 && start_time_millis.to_string().len() <= 14,
-            "Start time seems invalid (should be a millisecond timestamp)"
-        );
-
-        let start_time: linera_sdk::linera_base_types::Timestamp = start_time_millis
-            .checked_mul(1000)
-            .expect("Start time overflow when converting to microseconds")
-            .into(); // 毫秒转微秒
-
-        let end_time_millis = params
-            .end_time
-            .parse::<u64>()
-            .expect("Invalid end time format");
-
-        // 检查时间戳长度是否合理（毫秒级时间戳应该是13位左右）
-        assert!(
-            end_time_millis.to_string().len() >= 10 && end_time_millis.to_string().len() <= 14,
-            "End time seems invalid (should be a millisecond timestamp)"
-        );
-
-        let end_time: linera_sdk::linera_base_types::Timestamp = end_time_millis
-            .checked_mul(1000)
-            .expect("End time overflow when converting to microseconds")
-            .into(); // 毫秒转微秒
-
-        assert!(
-            start_time > current_time,
-            "Start time must be in the future"
-        );
-        assert!(end_time > start_time, "End time must be after start time");
-        // 检查时间范围是否合理（不超过100年）
-        assert!(
-            end_time.delta_since(start_time) <= TimeDelta::from_secs(3600 * 24 * 365 * 100),
-            "Time range is too long (maximum 100 years)"
-        );
+        // 验证测验参数（与服务端 validate_quiz 共用同一套规则）
+        let QuizSchedule {
+            start_time,
+            end_time,
+        } = validate_create_quiz(&params, current_time)
+            .unwrap_or_else(|violations| panic!("{}", violations[0].message));
+        self.check_question_media(&params.questions);
+        if params.start_mode == QuizStartMode::Scheduled {
+            self.check_blackouts(start_time).await;
+        }
 
         let quiz_id = *self.state.next_quiz_id.get();
-        let _creator_owner = self
-            .runtime
-            .authenticated_signer()
-            .expect("Failed to get authenticated signer: no user authenticated");
-        let creator = params.nick_name.clone();
+        let creator = self.signer();
+        let mut members_only = params.members_only.unwrap_or(false);
+        if let Some(org) = &params.org {
+            let organization = self
+                .state
+                .orgs
+                .get(org)
+                .await
+                .expect("Failed to retrieve organization from storage")
+                .expect("Organization not found");
+            assert!(
+                self.state.is_org_member(org, &creator).await,
+                "Only organization members can create quizzes in it"
+            );
+            // 组织设置的限制和默认可见性
+            if let Some(violation) = validate_org_limits(&params, &organization.settings).first() {
+                panic!("{}", violation.message);
+            }
+            members_only = params
+                .members_only
+                .unwrap_or(organization.settings.default_members_only);
+        }
 
         let quiz_set = QuizSet {
             id: quiz_id,
             title: params.title,
             description: params.description,
             creator,
+            creator_name: params.nick_name,
             questions: params
                 .questions
                 .into_iter()
                 .enumerate()
-                .map(|(i, q)| Question {
-                    id: i as u32,
-                    text: q.text,
-                    options: q.options,
-                    correct_options: q.correct_options,
-                    points: q.points,
-                })
+                .map(|(i, q)| Question::new(i as u32, q))
                 .collect(),
             time_limit: params.time_limit,
             start_time,
             end_time,
             created_at: current_time,
+            practice: params.practice,
+            playoff_of: None,
+            hide_results: params.hide_results,
+            reveal_delay: params.reveal_delay,
+            results_revealed: false,
+            proctor: params.proctor,
+            owner: params.owner,
+            metadata: params
+                .metadata
+                .into_iter()
+                .map(|entry| (entry.key, entry.value))
+                .collect(),
+            start_mode: params.start_mode,
+            scoring: params.scoring,
+            allow_dont_know: params.allow_dont_know,
+            org: params.org,
+            members_only,
+            shuffle_questions: params.shuffle_questions,
+            questions_per_attempt: params.questions_per_attempt,
+            embargo: params.embargo,
+            max_attempts: params.max_attempts,
+            attempt_policy: params.attempt_policy,
+            attestation_app: params.attestation_app,
+            entry_fee: params.entry_fee,
+            category: params.category,
+            tags: params.tags,
+            pass_score: params.pass_score,
+            soft_launch: params.soft_launch,
+            answer_key_hash: params.answer_key_hash,
+            answer_key_salt: None,
+            content_revealed: false,
+            is_started: false,
+            cancelled: false,
+            finalized: false,
         };
 
-        // 存储新Quiz
+        // 登记问题指纹，并提醒创建者与其已有问题重复的问题
+        let duplicate_questions = self.index_question_fingerprints(&quiz_set).await;
+
+        // 向订阅链公告公开的新Quiz
+        if !quiz_set.members_only {
+            self.broadcast(Message::QuizCreated(quiz_set.announcement()))
+                .await;
+        }
+        self.store_new_quiz(quiz_set).await;
+        self.update_user_stats(creator, |stats| stats.quizzes_created += 1)
+            .await;
+        // 奖池从创建者账户转入应用账户托管，只收参赛费时奖池从0开始
+        if params.prize_pool.is_some() || params.entry_fee.is_some() {
+            let amount = params.prize_pool.unwrap_or_default();
+            if amount > Amount::ZERO {
+                let escrow = self.escrow_account();
+                self.runtime.transfer(creator, escrow, amount);
+            }
+            let _ = self.state.prize_pools.insert(
+                &quiz_id,
+                PrizePool {
+                    funder: creator,
+                    amount,
+                    entry_fees: Amount::ZERO,
+                    splits: params.prize_splits,
+                    distribution: None,
+                    token_prizes: Vec::new(),
+                },
+            );
+        }
+        QuizResponse::QuizCreated {
+            quiz_id,
+            duplicate_questions,
+        }
+    }
+
+    /// 存储新Quiz：登记标签、分类、搜索词和创建者索引、发出创建事件并分配下一个Quiz ID
+    async fn store_new_quiz(&mut self, quiz_set: QuizSet) {
+        let quiz_id = quiz_set.id;
+        for tag in &quiz_set.tags {
+            index_quiz(&mut self.state.tag_index, tag, quiz_id).await;
+        }
+        if !quiz_set.category.is_empty() {
+            index_quiz(&mut self.state.category_index, &quiz_set.category, quiz_id).await;
+        }
+        self.index_search_terms(&quiz_set).await;
+        let mut created_quizzes = self
+            .state
+            .creator_quizzes
+            .get(&quiz_set.creator)
+            .await
+            .expect("Failed to retrieve created quizzes")
+            .unwrap_or_default();
+        created_quizzes.push(quiz_id);
+        let _ = self
+            .state
+            .creator_quizzes
+            .insert(&quiz_set.creator, created_quizzes);
+
+        self.emit_event(AppEvent::QuizCreated(QuizCreatedEvent {
+            quiz_id,
+            creator: quiz_set.creator,
+            title: quiz_set.title.clone(),
+            start_time: quiz_set.start_time,
+            end_time: quiz_set.end_time,
+        }));
+
         let _ = self.state.quiz_sets.insert(&quiz_id, quiz_set);
-        // 更新下一个Quiz ID
         let next_id = quiz_id.checked_add(1).expect("Quiz ID overflow");
         self.state.next_quiz_id.set(next_id);
     }
 
-    async fn submit_answers(&mut self, params: SubmitAnswersParams) {
-        let user = params.nick_name.clone();
-
-        let quiz_id = params.quiz_id;
+    /// 从创建者往期Quiz中抽题，创建归签名者所有、立即开始的练习卷
+    async fn build_practice_exam(&mut self, params: BuildPracticeExamParams) -> QuizResponse {
         let now = self.runtime.system_time();
-
-        // 检查Quiz是否存在
-        let quiz_set = self
+        validate_practice_exam(&params, now)
+            .unwrap_or_else(|violation| panic!("{}", violation.message));
+        let owner = self.signer();
+        let pool = self
             .state
-            .quiz_sets
-            .get(&quiz_id)
+            .practice_pool(&params.creator, &params.tags, params.difficulty, now)
             .await
-            .expect("Failed to retrieve quiz from storage")
-            .expect("QuizSet not found");
+            .expect("Failed to collect practice questions");
+        assert!(
+            pool.len() >= params.question_count as usize,
+            "Only {} matching questions are available",
+            pool.len()
+        );
+
+        let quiz_id = *self.state.next_quiz_id.get();
+        let questions = sample_questions(
+            pool,
+            params.question_count as usize,
+            quiz_id,
+            owner,
+            self.runtime.block_height(),
+        );
+        let quiz_set = QuizSet {
+            id: quiz_id,
+            title: params.title,
+            description: String::new(),
+            creator: owner,
+            creator_name: params.nick_name,
+            questions,
+            time_limit: params.time_limit,
+            start_time: now,
+            end_time: params.end_time,
+            created_at: now,
+            practice: true,
+            playoff_of: None,
+            hide_results: false,
+            reveal_delay: None,
+            results_revealed: false,
+            proctor: None,
+            owner: None,
+            metadata: Vec::new(),
+            start_mode: QuizStartMode::Scheduled,
+            scoring: ScoringStrategy::default(),
+            allow_dont_know: false,
+            org: None,
+            members_only: false,
+            shuffle_questions: false,
+            questions_per_attempt: None,
+            embargo: None,
+            max_attempts: PRACTICE_EXAM_MAX_ATTEMPTS,
+            attempt_policy: AttemptPolicy::default(),
+            attestation_app: None,
+            entry_fee: None,
+            category: String::new(),
+            tags: params.tags,
+            pass_score: None,
+            soft_launch: None,
+            answer_key_hash: None,
+            answer_key_salt: None,
+            content_revealed: false,
+            is_started: false,
+            cancelled: false,
+            finalized: false,
+        };
+        self.store_new_quiz(quiz_set).await;
+        QuizResponse::QuizCreated {
+            quiz_id,
+            duplicate_questions: Vec::new(),
+        }
+    }
+
+    /// 将Quiz的问题登记到创建者的指纹索引，返回与已有问题重复的问题
+    async fn index_question_fingerprints(&mut self, quiz_set: &QuizSet) -> Vec<DuplicateQuestion> {
+        let mut duplicates = Vec::new();
+        for question in &quiz_set.questions {
+            let key = (quiz_set.creator, question.fingerprint());
+            let mut refs = self
+                .state
+                .question_fingerprints
+                .get(&key)
+                .await
+                .expect("Failed to retrieve question fingerprints")
+                .unwrap_or_default();
+            let existing: Vec<_> = refs
+                .iter()
+                .filter(|r| r.quiz_id != quiz_set.id)
+                .copied()
+                .collect();
+            if !existing.is_empty() {
+                duplicates.push(DuplicateQuestion {
+                    question_id: question.id,
+                    existing,
+                });
+            }
+            refs.push(QuestionRef {
+                quiz_id: quiz_set.id,
+                question_id: question.id,
+            });
+            let _ = self.state.question_fingerprints.insert(&key, refs);
+        }
+        duplicates
+    }
+
+    /// 从创建者的指纹索引中移除Quiz的问题
+    async fn unindex_question_fingerprints(&mut self, quiz_set: &QuizSet) {
+        for question in &quiz_set.questions {
+            let key = (quiz_set.creator, question.fingerprint());
+            let Some(mut refs) = self
+                .state
+                .question_fingerprints
+                .get(&key)
+                .await
+                .expect("Failed to retrieve question fingerprints")
+            else {
+                continue;
+            };
+            refs.retain(|r| r.quiz_id != quiz_set.id);
+            if refs.is_empty() {
+                self.state
+                    .question_fingerprints
+                    .remove(&key)
+                    .expect("Failed to remove question fingerprints");
+            } else {
+                let _ = self.state.question_fingerprints.insert(&key, refs);
+            }
+        }
+    }
+
+    /// 把Quiz登记到其标题和描述中每个搜索词的倒排列表
+    async fn index_search_terms(&mut self, quiz_set: &QuizSet) {
+        for term in quiz_set.indexed_terms() {
+            index_quiz(&mut self.state.search_index, &term, quiz_set.id).await;
+        }
+    }
+
+    async fn unindex_search_terms(&mut self, quiz_set: &QuizSet) {
+        for term in quiz_set.indexed_terms() {
+            unindex_quiz(&mut self.state.search_index, &term, quiz_set.id).await;
+        }
+    }
 
-        // 检查测验时间范围
-        assert!(now >= quiz_set.start_time, "Quiz has not started yet");
+    async fn start_quiz(&mut self, quiz_id: u64) {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can start the quiz"
+        );
+        assert_eq!(
+            quiz_set.start_mode,
+            QuizStartMode::Manual,
+            "Quiz starts automatically at its start time"
+        );
+        assert!(!quiz_set.is_started, "Quiz has already been started");
         assert!(now <= quiz_set.end_time, "Quiz has ended");
+        self.check_blackouts(now).await;
+
+        // 分批开放从实际开始时间起算
+        quiz_set.is_started = true;
+        quiz_set.start_time = now;
+
+        // 通知浏览过该Quiz的用户
+        let viewers = QuizState::quiz_users(
+            &self.state.quiz_viewers,
+            quiz_id,
+            MAX_AUDIENCE_NOTIFICATIONS,
+        )
+        .await
+        .expect("Failed to read quiz viewers");
+        for viewer in viewers {
+            self.notify(
+                viewer,
+                NotificationKind::QuizStarting,
+                quiz_id,
+                format!("\"{}\" has started", quiz_set.title),
+            )
+            .await;
+        }
+        if !quiz_set.members_only {
+            self.broadcast(Message::QuizUpdated(quiz_set.announcement()))
+                .await;
+        }
+        let _ = self.state.quiz_sets.insert(&quiz_id, quiz_set);
+        self.emit_event(AppEvent::QuizStarted(QuizStartedEvent {
+            quiz_id,
+            started_at: now,
+        }));
+    }
+
+    /// 修改Quiz：仅在开始前且无人答题时允许，时间窗口按创建时的规则重新校验
+    async fn update_quiz(&mut self, params: UpdateQuizParams) -> QuizResult<()> {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can update the quiz"
+        );
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        if quiz_set.has_started(now) {
+            return Err(quiz_locked(params.quiz_id, "Quiz has already started"));
+        }
+        if self.has_attempts(params.quiz_id).await {
+            return Err(quiz_locked(params.quiz_id, "Quiz already has attempts"));
+        }
+
+        if params.start_time.is_some() || params.end_time.is_some() {
+            let start_time = params.start_time.unwrap_or(quiz_set.start_time);
+            let end_time = params.end_time.unwrap_or(quiz_set.end_time);
+            let schedule = validate_schedule(start_time, end_time, now)
+                .unwrap_or_else(|violations| panic!("{}", violations[0].message));
+            if quiz_set.start_mode == QuizStartMode::Scheduled {
+                self.check_blackouts(schedule.start_time).await;
+            }
+            quiz_set.start_time = schedule.start_time;
+            quiz_set.end_time = schedule.end_time;
+        }
+        if params.title.is_some() || params.description.is_some() {
+            self.unindex_search_terms(&quiz_set).await;
+            if let Some(title) = params.title {
+                quiz_set.title = title;
+            }
+            if let Some(description) = params.description {
+                quiz_set.description = description;
+            }
+            self.index_search_terms(&quiz_set).await;
+        }
+        if let Some(scoring) = params.scoring {
+            quiz_set.scoring = scoring;
+        }
+        if let Some(questions) = params.questions {
+            assert!(
+                quiz_set.embargo.is_none(),
+                "Embargoed quiz content can only be set with RevealContent"
+            );
+            if let Some(violation) = validate_questions(&questions).into_iter().next() {
+                panic!("{}", violation.message);
+            }
+            self.check_question_media(&questions);
+            validate_questions_per_attempt(quiz_set.questions_per_attempt, questions.len())
+                .unwrap_or_else(|violation| panic!("{}", violation.message));
+            self.unindex_question_fingerprints(&quiz_set).await;
+            quiz_set.questions = questions
+                .into_iter()
+                .enumerate()
+                .map(|(i, q)| Question::new(i as u32, q))
+                .collect();
+            quiz_set.answer_key_hash = params.answer_key_hash;
+            self.index_question_fingerprints(&quiz_set).await;
+        } else if params.answer_key_hash.is_some() {
+            quiz_set.answer_key_hash = params.answer_key_hash;
+        }
+
+        if !quiz_set.members_only {
+            self.broadcast(Message::QuizUpdated(quiz_set.announcement()))
+                .await;
+        }
+        let _ = self.state.quiz_sets.insert(&params.quiz_id, quiz_set);
+        Ok(())
+    }
 
-        // 检查用户是否已提交过该Quiz
+    /// Quiz是否已有答题记录（包括待确认答卷和进行中的会话）
+    async fn has_attempts(&self, quiz_id: u64) -> bool {
         if self
             .state
-            .user_attempts
-            .get(&(quiz_id, user.clone()))
+            .quiz_attempt_index
+            .contains_key(&quiz_id)
             .await
-            .unwrap()
-            .is_some()
+            .expect("Failed to read quiz attempt index")
+        {
+            return true;
+        }
+        for user in self
+            .state
+            .participants_of(quiz_id)
+            .await
+            .expect("Failed to read quiz participants")
         {
-            panic!("User has already attempted this quiz");
+            let key = (quiz_id, user);
+            if self
+                .state
+                .pending_attempts
+                .contains_key(&key)
+                .await
+                .expect("Failed to read pending attempts")
+                || self
+                    .state
+                    .attempt_sessions
+                    .contains_key(&key)
+                    .await
+                    .expect("Failed to read attempt sessions")
+            {
+                return true;
+            }
         }
+        false
+    }
 
-        // 验证答案数量是否匹配问题数量
-        assert_eq!(
-            params.answers.len(),
-            quiz_set.questions.len(),
-            "Answer count mismatch with questions"
+    async fn delete_quiz(&mut self, quiz_id: u64) -> QuizResult<()> {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_quiz(quiz_id).await;
+        assert!(
+            self.can_moderate_quiz(&quiz_set).await,
+            "Only the quiz creator or an organization admin can delete the quiz"
         );
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        if quiz_set.has_started(now) {
+            return Err(quiz_locked(
+                quiz_id,
+                "Quiz has already started; cancel it instead",
+            ));
+        }
+        self.remove_quiz(&quiz_set).await;
+        Ok(())
+    }
 
-        // 计算得分
-        let mut score = 0;
-        for (i, user_answers) in params.answers.iter().enumerate() {
-            let question = &quiz_set.questions[i];
-
-            // 检查用户选择的答案是否与所有正确选项完全匹配（顺序无关）
-            let mut user_answers_sorted = user_answers.clone();
-            user_answers_sorted.sort();
-            let mut correct_options_sorted = question.correct_options.clone();
-            correct_options_sorted.sort();
+    async fn cancel_quiz(&mut self, quiz_id: u64) {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(quiz_id).await;
+        assert!(
+            self.can_moderate_quiz(&quiz_set).await,
+            "Only the quiz creator or an organization admin can cancel the quiz"
+        );
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        assert!(
+            quiz_set.has_started(now),
+            "Quiz has not started yet; delete it instead"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has already been cancelled");
 
-            if user_answers_sorted == correct_options_sorted {
-                score += question.points;
-            }
+        self.purge_quiz_records(&quiz_set).await;
+        if !quiz_set.members_only {
+            self.broadcast(Message::QuizWithdrawn { quiz_id }).await;
         }
-
-        // 创建答题记录
-        let attempt = UserAttempt {
+        quiz_set.cancelled = true;
+        let _ = self.state.quiz_sets.insert(&quiz_id, quiz_set);
+        self.emit_event(AppEvent::QuizCancelled(QuizCancelledEvent {
             quiz_id,
-            user: user.clone(),
-            answers: params.answers,
-            score,
-            time_taken: params.time_taken,
-            completed_at: now,
-        };
+            deleted: false,
+        }));
+    }
 
-        // 存储答题记录
-        let _ = self
+    /// 删除Quiz及其全部关联数据
+    async fn remove_quiz(&mut self, quiz_set: &QuizSet) {
+        self.purge_quiz_records(quiz_set).await;
+        self.unindex_question_fingerprints(quiz_set).await;
+        self.unindex_search_terms(quiz_set).await;
+        for tag in &quiz_set.tags {
+            unindex_quiz(&mut self.state.tag_index, tag, quiz_set.id).await;
+        }
+        if !quiz_set.category.is_empty() {
+            unindex_quiz(
+                &mut self.state.category_index,
+                &quiz_set.category,
+                quiz_set.id,
+            )
+            .await;
+        }
+        if let Some(mut created_quizzes) = self
             .state
-            .user_attempts
-            .insert(&(quiz_id, user.clone()), attempt.clone());
-        // 记录答题事件
-        self.state.quiz_events.push(attempt);
+            .creator_quizzes
+            .get(&quiz_set.creator)
+            .await
+            .expect("Failed to retrieve created quizzes")
+        {
+            created_quizzes.retain(|id| *id != quiz_set.id);
+            let _ = self
+                .state
+                .creator_quizzes
+                .insert(&quiz_set.creator, created_quizzes);
+        }
+        self.state
+            .quiz_sets
+            .remove(&quiz_set.id)
+            .expect("Failed to remove quiz");
+        if !quiz_set.members_only {
+            self.broadcast(Message::QuizWithdrawn {
+                quiz_id: quiz_set.id,
+            })
+            .await;
+        }
+        self.emit_event(AppEvent::QuizCancelled(QuizCancelledEvent {
+            quiz_id: quiz_set.id,
+            deleted: true,
+        }));
+    }
 
-        // 记录用户参与
-        let mut participations = self
+    /// 归档并删除创建者在给定时间前结束的Quiz；只处理已最终确定且奖池已发放、或已取消的Quiz
+    async fn prune_quizzes(&mut self, creator: AccountOwner, ended_before: Timestamp) {
+        let now = self.runtime.system_time();
+        let quiz_ids = self
             .state
-            .user_participations
-            .get(&user)
+            .creator_quizzes
+            .get(&creator)
             .await
-            .unwrap()
+            .expect("Failed to retrieve created quizzes")
             .unwrap_or_default();
-        participations.push(quiz_id);
-        let _ = self.state.user_participations.insert(&user, participations);
+        let mut pruned = 0;
+        for quiz_id in quiz_ids {
+            if pruned == MAX_QUIZZES_PRUNED_PER_CALL {
+                break;
+            }
+            let quiz_set = self.load_quiz(quiz_id).await;
+            if quiz_set.end_time >= ended_before.min(now) || !self.can_archive(&quiz_set).await {
+                continue;
+            }
+            self.archive_quiz(&quiz_set, now).await;
+            pruned += 1;
+        }
+    }
 
-        // 更新排行榜
-        self.update_leaderboard(quiz_id, user, score).await;
+    /// Quiz能否归档：已取消，或已最终确定且奖池（如有）已发放
+    async fn can_archive(&self, quiz_set: &QuizSet) -> bool {
+        if quiz_set.cancelled {
+            return true;
+        }
+        quiz_set.finalized
+            && self
+                .state
+                .prize_pools
+                .get(&quiz_set.id)
+                .await
+                .expect("Failed to retrieve prize pool")
+                .is_none_or(|pool| pool.distribution.is_some())
     }
 
-    async fn update_leaderboard(&mut self, quiz_id: u64, user: String, score: u32) {
-        // 这里简单实现一个排行榜更新逻辑
-        // 实际项目中可能需要更复杂的排序和存储策略
-        let mut entries = self
+    /// 把Quiz的答卷压缩为参与统计和最终排行榜存入归档后删除Quiz；
+    /// 已颁发的奖杯和用户的累计统计保留
+    async fn archive_quiz(&mut self, quiz_set: &QuizSet, now: Timestamp) {
+        let quiz_id = quiz_set.id;
+        let attempts = self
             .state
-            .leaderboard
+            .quiz_attempts(quiz_id)
+            .await
+            .expect("Failed to read attempts");
+        let participants = attempts
+            .iter()
+            .map(|attempt| attempt.user)
+            .collect::<BTreeSet<_>>()
+            .len() as u32;
+        let standings = match self
+            .state
+            .leaderboard_snapshots
             .get(&quiz_id)
             .await
-            .unwrap()
-            .unwrap_or_default();
+            .expect("Failed to read leaderboard snapshots")
+            .and_then(|mut snapshots| snapshots.pop())
+        {
+            Some(snapshot) => snapshot.entries,
+            None => self
+                .state
+                .leaderboard
+                .get(&quiz_id)
+                .await
+                .expect("Failed to retrieve leaderboard")
+                .unwrap_or_default(),
+        };
+        let standings_hash = self
+            .state
+            .finalizations
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve finalization")
+            .map(|finalization| finalization.standings_hash);
+        let archive = ArchivedQuiz {
+            title: quiz_set.title.clone(),
+            creator: quiz_set.creator,
+            category: quiz_set.category.clone(),
+            tags: quiz_set.tags.clone(),
+            start_time: quiz_set.start_time,
+            end_time: quiz_set.end_time,
+            cancelled: quiz_set.cancelled,
+            participants,
+            attempts: attempts.len() as u32,
+            total_score: attempts
+                .iter()
+                .map(|attempt| u64::from(attempt.score))
+                .sum(),
+            standings,
+            standings_hash,
+            archived_at: now,
+        };
+        let _ = self.state.archived_quizzes.insert(&quiz_id, archive);
+        // 先移除排行榜修订版和累计积分的来源记录，清理时就不会收回已颁发的奖杯和累计积分
+        self.state
+            .leaderboard_snapshots
+            .remove(&quiz_id)
+            .expect("Failed to remove leaderboard snapshots");
+        self.take_global_contributions(quiz_id).await;
+        self.remove_quiz(quiz_set).await;
+    }
 
-        // 查找用户是否已有条目
-        let existing_index = entries.iter().position(|entry| entry.user == user);
+    /// 设置或取消签名者的Quiz保留策略
+    fn set_retention_policy(&mut self, retention_days: Option<u32>) {
+        let creator = self.signer();
+        match retention_days {
+            Some(retention_days) => {
+                validate_retention_days(retention_days)
+                    .unwrap_or_else(|violation| panic!("{}", violation.message));
+                let _ = self
+                    .state
+                    .retention_policies
+                    .insert(&creator, retention_days);
+            }
+            None => self
+                .state
+                .retention_policies
+                .remove(&creator)
+                .expect("Failed to remove retention policy"),
+        }
+    }
+
+    /// 归档创建者结束时间早于保留期限的Quiz
+    async fn apply_retention_policy(&mut self, creator: AccountOwner) {
+        let retention_days = self
+            .state
+            .retention_policies
+            .get(&creator)
+            .await
+            .expect("Failed to retrieve retention policy")
+            .expect("Creator has no retention policy");
+        let ended_before = self
+            .runtime
+            .system_time()
+            .saturating_sub(TimeDelta::from_micros(
+                u64::from(retention_days) * DAY_MICROS,
+            ));
+        self.prune_quizzes(creator, ended_before).await;
+    }
+
+    /// 清理Quiz的答题记录、会话、笔记、申诉、排行榜和用户参与记录。按用户的记录只访问
+    /// 该Quiz参与者的键，按问题的记录只访问该Quiz的问题ID，不扫描全部Quiz的数据
+    async fn purge_quiz_records(&mut self, quiz_set: &QuizSet) {
+        let quiz_id = quiz_set.id;
+        self.state
+            .finalizations
+            .remove(&quiz_id)
+            .expect("Failed to remove finalization");
+        self.state
+            .announcements
+            .remove(&quiz_id)
+            .expect("Failed to remove announcements");
+        self.state
+            .playoffs
+            .remove(&quiz_id)
+            .expect("Failed to remove playoff");
+        // 取消或删除的Quiz不再计入累计排行榜
+        for (user, points) in self.take_global_contributions(quiz_id).await {
+            let Some(mut standing) = self
+                .state
+                .global_standings
+                .get(&user)
+                .await
+                .expect("Failed to retrieve global standing")
+            else {
+                continue;
+            };
+            standing.points = standing.points.saturating_sub(points);
+            standing.quizzes_completed = standing.quizzes_completed.saturating_sub(1);
+            let _ = self.state.global_standings.insert(&user, standing);
+        }
+        // 退还已收取的参赛费
+        for (user, fee) in self.take_entry_fees(quiz_id).await {
+            self.pay_from_escrow(user, fee);
+        }
+        // 未发放的奖池退回给出资者
+        if let Some(pool) = self
+            .state
+            .prize_pools
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve prize pool")
+        {
+            if pool.distribution.is_none() {
+                if pool.amount > Amount::ZERO {
+                    self.pay_from_escrow(pool.funder, pool.amount);
+                }
+                for prize in &pool.token_prizes {
+                    self.transfer_token(prize.token, prize.sponsor, prize.amount);
+                }
+            }
+            self.state
+                .prize_pools
+                .remove(&quiz_id)
+                .expect("Failed to remove prize pool");
+        }
+
+        let participants = self
+            .state
+            .participants_of(quiz_id)
+            .await
+            .expect("Failed to read quiz participants");
+        // 已记录答卷和待确认答卷，用于删除回执和答案指纹
+        let mut attempt_keys = self
+            .state
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve quiz attempt index")
+            .unwrap_or_default();
+        let mut receipts = Vec::new();
+        for (user, attempt_no) in &attempt_keys {
+            let key = (quiz_id, *user, *attempt_no);
+            if let Some(attempt) = self
+                .state
+                .user_attempts
+                .get(&key)
+                .await
+                .expect("Failed to retrieve attempt")
+            {
+                receipts.push(attempt.receipt);
+            }
+            self.state
+                .user_attempts
+                .remove(&key)
+                .expect("Failed to remove attempt");
+        }
+        self.state
+            .quiz_attempt_index
+            .remove(&quiz_id)
+            .expect("Failed to remove quiz attempt index");
+
+        for user in participants {
+            let key = (quiz_id, user);
+            // 删除Quiz时尚未领取的奖金直接发给获奖者
+            if let Some(unclaimed) = self
+                .state
+                .unclaimed_prizes
+                .get(&key)
+                .await
+                .expect("Failed to retrieve unclaimed prize")
+            {
+                self.pay_from_escrow(user, unclaimed.amount);
+                self.state
+                    .unclaimed_prizes
+                    .remove(&key)
+                    .expect("Failed to remove unclaimed prize");
+            }
+            if let Some(pending) = self
+                .state
+                .pending_attempts
+                .get(&key)
+                .await
+                .expect("Failed to retrieve pending attempt")
+            {
+                receipts.push(pending.receipt);
+                attempt_keys.push((user, pending.attempt_no));
+                self.state
+                    .pending_attempts
+                    .remove(&key)
+                    .expect("Failed to remove pending attempt");
+            }
+            self.state
+                .prize_sweeps
+                .remove(&key)
+                .expect("Failed to remove prize sweep");
+            self.state
+                .attempt_sessions
+                .remove(&key)
+                .expect("Failed to remove attempt session");
+            self.state
+                .attempt_notes
+                .remove(&key)
+                .expect("Failed to remove attempt note");
+            self.state
+                .extra_attempts
+                .remove(&key)
+                .expect("Failed to remove extra attempts");
+            self.state
+                .accommodations
+                .remove(&key)
+                .expect("Failed to remove accommodation");
+            if let Some(mut user_index) = self
+                .state
+                .user_attempt_index
+                .get(&user)
+                .await
+                .expect("Failed to retrieve user attempt index")
+            {
+                user_index.retain(|(q_id, _attempt_no)| *q_id != quiz_id);
+                if user_index.is_empty() {
+                    self.state
+                        .user_attempt_index
+                        .remove(&user)
+                        .expect("Failed to remove user attempt index");
+                } else {
+                    let _ = self.state.user_attempt_index.insert(&user, user_index);
+                }
+            }
+            if let Some(mut participations) = self
+                .state
+                .user_participations
+                .get(&user)
+                .await
+                .expect("Failed to retrieve participations")
+            {
+                participations.retain(|id| *id != quiz_id);
+                let _ = self.state.user_participations.insert(&user, participations);
+            }
+            let bookmarked: Vec<u32> = self
+                .state
+                .user_bookmark_index
+                .get(&user)
+                .await
+                .expect("Failed to retrieve bookmark index")
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(q_id, _question_id)| *q_id == quiz_id)
+                .map(|(_quiz_id, question_id)| question_id)
+                .collect();
+            for question_id in bookmarked {
+                self.state
+                    .bookmarks
+                    .remove(&(user, quiz_id, question_id))
+                    .expect("Failed to remove bookmark");
+                self.unindex_bookmark(user, quiz_id, question_id).await;
+            }
+        }
+        for receipt in &receipts {
+            self.state
+                .attempt_receipts
+                .remove(receipt)
+                .expect("Failed to remove attempt receipt");
+        }
+        for question in &quiz_set.questions {
+            let key = (quiz_id, question.id);
+            self.state
+                .question_appeals
+                .remove(&key)
+                .expect("Failed to remove question appeal");
+            self.state
+                .question_times
+                .remove(&key)
+                .expect("Failed to remove question times");
+            self.state
+                .question_tallies
+                .remove(&key)
+                .expect("Failed to remove question tally");
+            for (user, attempt_no) in &attempt_keys {
+                self.state
+                    .answer_fingerprints
+                    .remove(&(quiz_id, question.id, *user, *attempt_no))
+                    .expect("Failed to remove answer fingerprint");
+            }
+        }
+        for token in self
+            .state
+            .quiz_preview_tokens
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve preview tokens")
+            .unwrap_or_default()
+        {
+            self.state
+                .preview_tokens
+                .remove(&token)
+                .expect("Failed to remove preview token");
+        }
+        self.state
+            .quiz_preview_tokens
+            .remove(&quiz_id)
+            .expect("Failed to remove preview tokens");
+        self.state
+            .quiz_identities
+            .remove_entry(&quiz_id)
+            .expect("Failed to remove quiz identities");
+        self.state
+            .quiz_viewers
+            .remove_entry(&quiz_id)
+            .expect("Failed to remove quiz viewers");
+        self.state
+            .quiz_starters
+            .remove_entry(&quiz_id)
+            .expect("Failed to remove quiz starters");
+        self.state
+            .quiz_participants
+            .remove_entry(&quiz_id)
+            .expect("Failed to remove quiz participants");
+        self.state
+            .quiz_views
+            .remove(&quiz_id)
+            .expect("Failed to remove quiz views");
+        self.state
+            .leaderboard
+            .remove(&quiz_id)
+            .expect("Failed to remove leaderboard");
+        if let Some(snapshot) = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+            .and_then(|mut snapshots| snapshots.pop())
+        {
+            self.revoke_trophies(quiz_id, &snapshot.entries).await;
+        }
+        self.state
+            .leaderboard_snapshots
+            .remove(&quiz_id)
+            .expect("Failed to remove leaderboard snapshots");
+    }
+
+    async fn submit_answers(&mut self, params: SubmitAnswersParams) -> QuizResult<QuizResponse> {
+        let now = self.runtime.system_time();
+        self.grade_submission(params, now).await
+    }
+
+    /// 以签名者身份向Quiz所在的链发送消息，被拒绝的消息会退回
+    fn send_remote(&mut self, host_chain_id: ChainId, message: Message) {
+        assert!(
+            self.runtime.authenticated_signer().is_some(),
+            "Remote attempts must be signed by the participant"
+        );
+        self.runtime
+            .prepare_message(message)
+            .with_authentication()
+            .with_tracking()
+            .send_to(host_chain_id);
+    }
+
+    fn message_origin(&mut self) -> ChainId {
+        self.runtime
+            .message_origin_chain_id()
+            .expect("Incoming message has no origin chain")
+    }
+
+    /// 向所有订阅链发送公告
+    async fn broadcast(&mut self, message: Message) {
+        let subscribers = self
+            .state
+            .subscribers
+            .indices()
+            .await
+            .expect("Failed to read subscribers");
+        for chain_id in subscribers {
+            self.runtime
+                .prepare_message(message.clone())
+                .send_to(chain_id);
+        }
+    }
+
+    /// 向新订阅的链发送尚未结束的公开Quiz目录
+    async fn send_catalog(&mut self, chain_id: ChainId) {
+        let now = self.runtime.system_time();
+        let mut announcements = Vec::new();
+        self.state
+            .quiz_sets
+            .for_each_index_value(|_quiz_id, quiz_set| {
+                if !quiz_set.members_only && !quiz_set.cancelled && now <= quiz_set.end_time {
+                    announcements.push(quiz_set.announcement());
+                }
+                Ok(())
+            })
+            .await
+            .expect("Failed to read quizzes");
+        for announcement in announcements {
+            self.runtime
+                .prepare_message(Message::QuizCreated(announcement))
+                .send_to(chain_id);
+        }
+    }
+
+    /// 对整份提交评分并记录答卷，now 为本链处理提交的时间
+    async fn grade_submission(
+        &mut self,
+        params: SubmitAnswersParams,
+        now: Timestamp,
+    ) -> QuizResult<QuizResponse> {
+        let user = self.signer();
+        let quiz_id = params.quiz_id;
+
+        // 检查Quiz是否存在以及测验时间范围
+        let quiz_set = self.load_active_quiz(quiz_id, now).await;
+
+        // 检查用户是否还有答题次数（等待监考确认时不能再次答题）
+        let key = (quiz_id, user);
+        let attempt_no = self.next_attempt_no(&quiz_set, user).await;
+
+        // 用时由合约根据答题会话的开始时间计算，并检查答题时限
+        let session = self
+            .state
+            .attempt_sessions
+            .get(&key)
+            .await
+            .expect("Failed to retrieve attempt session from storage")
+            .expect("Start the attempt first");
+        let parameters = self.runtime.application_parameters();
+        let time_taken = now.delta_since(session.started_at).as_micros() / 1000;
+        let time_multiplier = self.time_multiplier(quiz_id, user).await;
+        if quiz_set.time_limit_exceeded(
+            session.started_at,
+            now,
+            parameters.time_limit_grace_secs,
+            time_multiplier,
+        ) {
+            // 超时的会话按已保存的答案记为放弃的答卷，不留给清理操作；
+            // 跨链提交的错误会使消息被退回，会话仍由 ExpireAbandonedAttempts 清理
+            self.expire_session(&quiz_set, session).await;
+            return Err(QuizError::TimeLimitExceeded(TimeLimitExceededError {
+                quiz_id,
+                time_limit: quiz_set.time_limit_for(time_multiplier),
+                elapsed: time_taken,
+            }));
+        }
+
+        self.claim_identity(&quiz_set, user).await;
+        self.record_answer_times(&session).await;
+
+        // 答案按问题ID提交，大型Quiz可提交紧凑编码，旧客户端按答题者看到的顺序提交
+        let question_order = session.question_order;
+        let answers = if !params.packed_answers.is_empty() {
+            assert!(
+                params.answers.is_empty() && params.positional_answers.is_empty(),
+                "Packed answers cannot be combined with other answers"
+            );
+            let answers =
+                unpack_answers(&params.packed_answers).unwrap_or_else(|error| panic!("{error}"));
+            answers_by_question_id(answers, &question_order)
+        } else if params.positional_answers.is_empty() {
+            answers_by_question_id(params.answers, &question_order)
+        } else {
+            assert!(
+                params.answers.is_empty(),
+                "Submit answers either by question ID or by position, not both"
+            );
+            assert_eq!(
+                params.positional_answers.len(),
+                question_order.len(),
+                "Answer count mismatch with questions"
+            );
+            restore_question_order(params.positional_answers, &question_order)
+        };
+
+        check_dont_know(&quiz_set, &answers);
+
+        // 计算得分（答案数量必须匹配问题数量）
+        let questions = quiz_set.drawn_questions(&question_order);
+        let question_results = score_answers(&questions, &answers, quiz_set.scoring)
+            .unwrap_or_else(|error| panic!("{error}"));
+        let fixed_score = total_score(&question_results);
+        let score = parameters.display_score(fixed_score);
+
+        // 创建答题记录
+        let receipt = self.new_receipt(quiz_id, user, attempt_no);
+        let attempt = UserAttempt {
+            quiz_id,
+            user,
+            nick_name: params.nick_name,
+            answers,
+            question_order,
+            score,
+            fixed_score,
+            question_results,
+            time_taken,
+            completed_at: now,
+            attempt_no,
+            receipt,
+            answer_changes: Vec::new(),
+            passed: quiz_set.passed(score),
+        };
+
+        // 整份提交会取代进行中的逐题答题会话
+        self.state
+            .attempt_sessions
+            .remove(&key)
+            .expect("Failed to remove attempt session");
+
+        let rank = self.record_attempt(&quiz_set, attempt).await;
+        log_event!(
+            Level::Info,
+            CONTRACT_TARGET,
+            "answers graded",
+            quiz_id = quiz_id,
+            user = user,
+            attempt_no = attempt_no,
+            score = score,
+        );
+        // 结果隐藏期间不向答题者透露分数和名次
+        let visible = quiz_set.results_visible(now);
+        Ok(QuizResponse::AnswersSubmitted {
+            score: visible.then_some(score),
+            precise_score: visible.then(|| parameters.format_score(fixed_score)),
+            rank: rank.filter(|_| visible),
+            receipt,
+        })
+    }
+
+    /// 为答卷生成提交回执ID
+    fn new_receipt(&mut self, quiz_id: u64, user: AccountOwner, attempt_no: u32) -> CryptoHash {
+        let block_height = self.runtime.block_height();
+        UserAttempt::receipt_for(quiz_id, user, attempt_no, block_height)
+    }
+
+    /// 浏览次数计入所有调用，不同浏览者只统计已认证的签名者
+    async fn record_quiz_view(&mut self, quiz_id: u64) {
+        let quiz_set = self.load_quiz(quiz_id).await;
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        let views = self
+            .state
+            .quiz_views
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve quiz views")
+            .unwrap_or_default();
+        let _ = self.state.quiz_views.insert(&quiz_id, views + 1);
+        if let Some(viewer) = self.runtime.authenticated_signer() {
+            self.state
+                .quiz_viewers
+                .load_entry_mut(&quiz_id)
+                .await
+                .expect("Failed to retrieve quiz viewers")
+                .insert(&viewer)
+                .expect("Failed to record quiz viewer");
+        }
+    }
+
+    async fn start_attempt(&mut self, params: StartAttemptParams) -> Vec<u32> {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_active_quiz(params.quiz_id, now).await;
+        let key = (params.quiz_id, self.signer());
+        self.next_attempt_no(&quiz_set, key.1).await;
+        assert!(
+            !self
+                .state
+                .attempt_sessions
+                .contains_key(&key)
+                .await
+                .unwrap(),
+            "Attempt has already started"
+        );
+        // 开始答题时即确认身份，之后由会话生成的答卷（包括放弃的答卷）都已经过认证
+        self.claim_identity(&quiz_set, key.1).await;
+        self.collect_entry_fee(&quiz_set, key.1).await;
+        self.state
+            .quiz_starters
+            .load_entry_mut(&key.0)
+            .await
+            .expect("Failed to retrieve quiz starters")
+            .insert(&key.1)
+            .expect("Failed to record quiz starter");
+        self.track_participant(key.0, key.1).await;
+
+        let session = self.new_session(&quiz_set, key.1, params.nick_name, now);
+        let question_order = session.question_order.clone();
+        let _ = self.state.attempt_sessions.insert(&key, session);
+        question_order
+    }
+
+    /// 关联身份认证应用的Quiz：通过跨应用调用确认答题者有已验证身份，
+    /// 并将该身份绑定到答题账户，同一个人的其他账户不能再参加
+    async fn claim_identity(&mut self, quiz_set: &QuizSet, user: AccountOwner) {
+        if let Err(error) = self.try_claim_identity(quiz_set, user).await {
+            panic!("{error}");
+        }
+    }
+
+    /// 同 claim_identity，但身份无效时返回错误而不中止操作
+    async fn try_claim_identity(
+        &mut self,
+        quiz_set: &QuizSet,
+        user: AccountOwner,
+    ) -> Result<(), &'static str> {
+        let Some(attestation_app) = quiz_set.attestation_app else {
+            return Ok(());
+        };
+        let identity = self
+            .runtime
+            .call_application(
+                false,
+                attestation_app.with_abi::<AttestationAbi>(),
+                &AttestationOperation::VerifiedIdentity { owner: user },
+            )
+            .ok_or("A verified identity is required to take this quiz")?;
+        let identities = self
+            .state
+            .quiz_identities
+            .load_entry_mut(&quiz_set.id)
+            .await
+            .expect("Failed to retrieve quiz identities");
+        match identities
+            .get(&identity)
+            .await
+            .expect("Failed to retrieve quiz identities")
+        {
+            Some(owner) if owner != user => {
+                Err("This identity has already taken part in the quiz with another account")
+            }
+            Some(_) => Ok(()),
+            None => {
+                let _ = identities.insert(&identity, user);
+                Ok(())
+            }
+        }
+    }
+
+    /// 答卷首次达到及格分时，调用参数中配置的证书应用为答题者铸造完成证书
+    async fn mint_certificate(
+        &mut self,
+        quiz_set: &QuizSet,
+        user: AccountOwner,
+        score: u32,
+        completed_at: Timestamp,
+    ) {
+        let Some(certificate_app) = self.runtime.application_parameters().certificate_app else {
+            return;
+        };
+        if quiz_set.passed(score) != Some(true) {
+            return;
+        }
+        let key = (quiz_set.id, user);
+        if self
+            .state
+            .certificates
+            .contains_key(&key)
+            .await
+            .expect("Failed to read certificates")
+        {
+            return;
+        }
+        let operation = CertificateOperation::Mint {
+            owner: user,
+            name: format!("{} completion certificate", quiz_set.title),
+            metadata: CertificateMetadata {
+                quiz_id: quiz_set.id,
+                score,
+                completed_at,
+            },
+        };
+        self.runtime.call_application(
+            true,
+            certificate_app.with_abi::<CertificateAbi>(),
+            &operation,
+        );
+        let _ = self
+            .state
+            .certificates
+            .insert(&key, self.runtime.system_time());
+    }
+
+    /// 将会话中逐题保存过的问题用时记入问题用时样本
+    async fn record_answer_times(&mut self, session: &AttemptSession) {
+        for (index, answer) in session.answers.iter().enumerate() {
+            if answer.is_none() {
+                continue;
+            }
+            let key = (session.quiz_id, index as u32);
+            let mut samples = self
+                .state
+                .question_times
+                .get(&key)
+                .await
+                .expect("Failed to retrieve question times")
+                .unwrap_or_default();
+            samples.push(session.answer_times[index]);
+            let _ = self.state.question_times.insert(&key, samples);
+        }
+    }
+
+    /// 新建答题会话，确定问题顺序（题库模式下即抽题）
+    fn new_session(
+        &mut self,
+        quiz_set: &QuizSet,
+        user: AccountOwner,
+        nick_name: String,
+        now: Timestamp,
+    ) -> AttemptSession {
+        AttemptSession {
+            quiz_id: quiz_set.id,
+            user,
+            nick_name,
+            answers: vec![None; quiz_set.questions.len()],
+            question_order: quiz_set.question_order(user, self.runtime.block_height()),
+            started_at: now,
+            last_activity: now,
+            last_answer_at: now,
+            answer_times: vec![0; quiz_set.questions.len()],
+            answer_changes: vec![0; quiz_set.questions.len()],
+        }
+    }
+
+    async fn save_answer(&mut self, params: SaveAnswerParams) {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_active_quiz(params.quiz_id, now).await;
+        let key = (params.quiz_id, self.signer());
+        self.next_attempt_no(&quiz_set, key.1).await;
+
+        let question_index = params.question_id as usize;
+        let question = quiz_set
+            .questions
+            .get(question_index)
+            .expect("Question not found");
+        check_answer_kind(question, &params.answer).unwrap_or_else(|error| panic!("{error}"));
+        check_dont_know(&quiz_set, std::slice::from_ref(&params.answer));
+        let session = self
+            .state
+            .attempt_sessions
+            .get(&key)
+            .await
+            .expect("Failed to retrieve attempt session from storage");
+        // 首次保存答案即开始答题，与 StartAttempt 一样先确认身份
+        if session.is_none() {
+            self.claim_identity(&quiz_set, key.1).await;
+        }
+        self.collect_entry_fee(&quiz_set, key.1).await;
+        self.state
+            .quiz_starters
+            .load_entry_mut(&key.0)
+            .await
+            .expect("Failed to retrieve quiz starters")
+            .insert(&key.1)
+            .expect("Failed to record quiz starter");
+        self.track_participant(key.0, key.1).await;
+
+        let mut session = session
+            .unwrap_or_else(|| self.new_session(&quiz_set, key.1, params.nick_name.clone(), now));
+        let grace_secs = self.runtime.application_parameters().time_limit_grace_secs;
+        let time_multiplier = self.time_multiplier(params.quiz_id, key.1).await;
+        assert!(
+            !quiz_set.time_limit_exceeded(session.started_at, now, grace_secs, time_multiplier),
+            "Time limit exceeded"
+        );
+        assert!(
+            session.question_order.contains(&params.question_id),
+            "Question is not part of this attempt"
+        );
+        // 结束答题或超时前可以修改已保存的答案，修改次数按问题记录
+        if session.answers[question_index]
+            .as_ref()
+            .is_some_and(|previous| *previous != params.answer)
+        {
+            session.answer_changes[question_index] += 1;
+        }
+        session.answers[question_index] = Some(params.answer);
+        // 距上一次保存答案的时间计入该问题的用时
+        session.answer_times[question_index] +=
+            now.delta_since(session.last_answer_at).as_micros() / 1000;
+        session.last_answer_at = now;
+        session.last_activity = now;
+        let _ = self.state.attempt_sessions.insert(&key, session);
+    }
+
+    async fn heartbeat(&mut self, params: HeartbeatParams) {
+        let now = self.runtime.system_time();
+        let key = (params.quiz_id, self.signer());
+        let mut session = self
+            .state
+            .attempt_sessions
+            .get(&key)
+            .await
+            .expect("Failed to retrieve attempt session from storage")
+            .expect("No attempt in progress");
+        session.last_activity = now;
+        let _ = self.state.attempt_sessions.insert(&key, session);
+    }
+
+    async fn expire_abandoned_attempts(&mut self, params: ExpireAbandonedAttemptsParams) {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        let parameters = self.runtime.application_parameters();
+        let timeout = TimeDelta::from_secs(parameters.abandon_timeout_secs);
+
+        // 超时未活动、超过答题时限或测验已结束的会话视为放弃
+        let mut abandoned = Vec::new();
+        for user in self
+            .state
+            .participants_of(params.quiz_id)
+            .await
+            .expect("Failed to read quiz participants")
+        {
+            let Some(session) = self
+                .state
+                .attempt_sessions
+                .get(&(params.quiz_id, user))
+                .await
+                .expect("Failed to read attempt sessions")
+            else {
+                continue;
+            };
+            let time_multiplier = self.time_multiplier(params.quiz_id, user).await;
+            if now.delta_since(session.last_activity) >= timeout
+                || quiz_set.time_limit_exceeded(
+                    session.started_at,
+                    now,
+                    parameters.time_limit_grace_secs,
+                    time_multiplier,
+                )
+                || now > quiz_set.end_time
+            {
+                abandoned.push(session);
+            }
+        }
+
+        for session in abandoned {
+            self.expire_session(&quiz_set, session).await;
+        }
+    }
+
+    /// 移除会话，并按已保存的答案把它记为以最后活动时间完成的放弃答卷；
+    /// 答题者的身份认证无效时只丢弃会话，不记录答卷
+    async fn expire_session(&mut self, quiz_set: &QuizSet, session: AttemptSession) {
+        let key = (session.quiz_id, session.user);
+        self.state
+            .attempt_sessions
+            .remove(&key)
+            .expect("Failed to remove attempt session");
+        if self.try_claim_identity(quiz_set, key.1).await.is_err() {
+            return;
+        }
+        self.record_answer_times(&session).await;
+
+        let attempt_no = self
+            .state
+            .user_quiz_attempts(session.quiz_id, &session.user)
+            .await
+            .expect("Failed to read attempts")
+            .last()
+            .map_or(1, |attempt| attempt.attempt_no + 1);
+        let (quiz_id, user) = key;
+        let completed_at = session.last_activity;
+        let (attempt, answered) = self.grade_session(quiz_set, session, completed_at, attempt_no);
+        let score = attempt.score;
+        self.record_attempt(quiz_set, attempt).await;
+
+        self.emit_event(AppEvent::AttemptAbandoned(AttemptAbandonedEvent {
+            quiz_id,
+            user,
+            score,
+            answered,
+        }));
+    }
+
+    /// 按会话中逐题保存的答案交卷，未作答的问题按空白答案评分
+    async fn finalize_session(
+        &mut self,
+        params: FinalizeAttemptParams,
+    ) -> QuizResult<QuizResponse> {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_active_quiz(params.quiz_id, now).await;
+        let key = (params.quiz_id, self.signer());
+        let attempt_no = self.next_attempt_no(&quiz_set, key.1).await;
+        let session = self
+            .state
+            .attempt_sessions
+            .get(&key)
+            .await
+            .expect("Failed to retrieve attempt session from storage")
+            .expect("No attempt in progress");
+        let parameters = self.runtime.application_parameters();
+        let time_multiplier = self.time_multiplier(params.quiz_id, key.1).await;
+        if quiz_set.time_limit_exceeded(
+            session.started_at,
+            now,
+            parameters.time_limit_grace_secs,
+            time_multiplier,
+        ) {
+            // 超时的会话按已保存的答案记为放弃的答卷
+            let elapsed = now.delta_since(session.started_at).as_micros() / 1000;
+            self.expire_session(&quiz_set, session).await;
+            return Err(QuizError::TimeLimitExceeded(TimeLimitExceededError {
+                quiz_id: params.quiz_id,
+                time_limit: quiz_set.time_limit_for(time_multiplier),
+                elapsed,
+            }));
+        }
+
+        self.claim_identity(&quiz_set, key.1).await;
+        self.state
+            .attempt_sessions
+            .remove(&key)
+            .expect("Failed to remove attempt session");
+        self.record_answer_times(&session).await;
+        let (attempt, _answered) = self.grade_session(&quiz_set, session, now, attempt_no);
+        let (score, fixed_score, receipt) = (attempt.score, attempt.fixed_score, attempt.receipt);
+
+        let rank = self.record_attempt(&quiz_set, attempt).await;
+        let visible = quiz_set.results_visible(now);
+        Ok(QuizResponse::AnswersSubmitted {
+            score: visible.then_some(score),
+            precise_score: visible.then(|| parameters.format_score(fixed_score)),
+            rank: rank.filter(|_| visible),
+            receipt,
+        })
+    }
+
+    /// 按会话中已保存的答案生成答卷，返回答卷和已作答的题数
+    fn grade_session(
+        &mut self,
+        quiz_set: &QuizSet,
+        session: AttemptSession,
+        completed_at: Timestamp,
+        attempt_no: u32,
+    ) -> (UserAttempt, u32) {
+        // 只对已作答的问题评分，题库模式下只包含抽到的问题
+        let questions = quiz_set.drawn_questions(&session.question_order);
+        let answers: Vec<Option<Answer>> = questions
+            .iter()
+            .map(|question| session.answers[question.id as usize].clone())
+            .collect();
+        let question_results = score_answered(&questions, &answers, quiz_set.scoring);
+        let fixed_score = total_score(&question_results);
+        let score = self
+            .runtime
+            .application_parameters()
+            .display_score(fixed_score);
+        let answered = answers.iter().filter(|a| a.is_some()).count() as u32;
+        let attempt = UserAttempt {
+            quiz_id: session.quiz_id,
+            user: session.user,
+            nick_name: session.nick_name,
+            answers: answers
+                .into_iter()
+                .map(|answer| answer.unwrap_or_else(Answer::blank))
+                .collect(),
+            question_order: session.question_order,
+            score,
+            fixed_score,
+            question_results,
+            time_taken: completed_at.delta_since(session.started_at).as_micros() / 1000,
+            completed_at,
+            attempt_no,
+            receipt: self.new_receipt(session.quiz_id, session.user, attempt_no),
+            answer_changes: questions
+                .iter()
+                .map(|question| session.answer_changes[question.id as usize])
+                .collect(),
+            passed: quiz_set.passed(score),
+        };
+        (attempt, answered)
+    }
+
+    /// 记录答卷：考试模式下等待监考人确认，否则直接生效并返回排行榜名次
+    async fn record_attempt(&mut self, quiz_set: &QuizSet, attempt: UserAttempt) -> Option<u32> {
+        let key = (attempt.quiz_id, attempt.user, attempt.attempt_no);
+        self.track_participant(key.0, key.1).await;
+        let _ = self.state.attempt_receipts.insert(&attempt.receipt, key);
+        self.record_fingerprints(&attempt);
+        self.emit_event(AppEvent::AnswerSubmitted(AnswerSubmittedEvent {
+            quiz_id: attempt.quiz_id,
+            user: attempt.user,
+            attempt_no: attempt.attempt_no,
+            receipt: attempt.receipt,
+            submitted_at: attempt.completed_at,
+        }));
+        if quiz_set.proctor.is_some() {
+            let _ = self
+                .state
+                .pending_attempts
+                .insert(&(attempt.quiz_id, attempt.user), attempt);
+            return None;
+        }
+        Some(self.finalize_attempt(quiz_set, attempt).await)
+    }
+
+    /// 调整问题的累计作答结果；人工评分改变对错时只调整答对数
+    async fn update_question_tally(
+        &mut self,
+        quiz_id: u64,
+        question_id: u32,
+        answered: i64,
+        correct: i64,
+        skipped: i64,
+    ) {
+        let key = (quiz_id, question_id);
+        let mut tally = self
+            .state
+            .question_tallies
+            .get(&key)
+            .await
+            .expect("Failed to retrieve question tally")
+            .unwrap_or_default();
+        tally.answered = tally.answered.saturating_add_signed(answered);
+        tally.correct = tally.correct.saturating_add_signed(correct);
+        tally.skipped = tally.skipped.saturating_add_signed(skipped);
+        let _ = self.state.question_tallies.insert(&key, tally);
+    }
+
+    /// 登记在Quiz上留有 (QuizId, User) 记录的用户，按Quiz读取和清理时只访问这些用户的键
+    async fn track_participant(&mut self, quiz_id: u64, user: AccountOwner) {
+        self.state
+            .quiz_participants
+            .load_entry_mut(&quiz_id)
+            .await
+            .expect("Failed to retrieve quiz participants")
+            .insert(&user)
+            .expect("Failed to record quiz participant");
+    }
+
+    /// 记录答卷中文本答案的指纹，供创建者检查相似答卷
+    fn record_fingerprints(&mut self, attempt: &UserAttempt) {
+        for (result, answer) in attempt.question_results.iter().zip(&attempt.answers) {
+            let Answer::Text(text) = answer else {
+                continue;
+            };
+            let fingerprint = fingerprint(text);
+            if fingerprint.is_empty() {
+                continue;
+            }
+            let key = (
+                attempt.quiz_id,
+                result.question_id,
+                attempt.user,
+                attempt.attempt_no,
+            );
+            let _ = self.state.answer_fingerprints.insert(&key, fingerprint);
+        }
+    }
+
+    async fn load_quiz(&self, quiz_id: u64) -> QuizSet {
+        self.state
+            .quiz_sets
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve quiz from storage")
+            .expect("QuizSet not found")
+    }
+
+    /// 读取Quiz并检查当前处于测验时间范围内
+    async fn load_active_quiz(&mut self, quiz_id: u64, now: Timestamp) -> QuizSet {
+        let signer = self.signer();
+        let quiz_set = self.load_quiz(quiz_id).await;
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(quiz_set.has_started(now), "Quiz has not started yet");
+        assert!(now <= quiz_set.end_time, "Quiz has ended");
+        assert!(
+            !quiz_set.is_embargoed(),
+            "Quiz content has not been revealed yet"
+        );
+        assert!(
+            now >= quiz_set.submittable_from(signer),
+            "This account's soft launch wave has not opened yet"
+        );
+        assert!(
+            self.state.can_access_quiz(&quiz_set, Some(&signer)).await,
+            "Only organization members can take this quiz"
+        );
+        quiz_set
+    }
+
+    /// 用户下一次答题的序号：答卷等待监考确认或答题次数已用完时拒绝
+    async fn next_attempt_no(&self, quiz_set: &QuizSet, user: AccountOwner) -> u32 {
+        assert!(
+            !self
+                .state
+                .pending_attempts
+                .contains_key(&(quiz_set.id, user))
+                .await
+                .unwrap(),
+            "Previous attempt is awaiting proctor confirmation"
+        );
+        let attempts = self
+            .state
+            .user_quiz_attempts(quiz_set.id, &user)
+            .await
+            .expect("Failed to read attempts");
+        let extra_attempts = self
+            .state
+            .extra_attempts
+            .get(&(quiz_set.id, user))
+            .await
+            .expect("Failed to retrieve extra attempts")
+            .unwrap_or_default();
+        assert!(
+            attempts.len() < quiz_set.max_attempts.saturating_add(extra_attempts) as usize,
+            "User has no attempts left for this quiz"
+        );
+        attempts.last().map_or(1, |attempt| attempt.attempt_no + 1)
+    }
+
+    async fn confirm_attempt(&mut self, params: ConfirmAttemptParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        let proctor = quiz_set
+            .proctor
+            .expect("Quiz does not require proctor confirmation");
+        let signer = self.signer();
+        assert_eq!(
+            signer, proctor,
+            "Only the quiz proctor can confirm attempts"
+        );
+
+        let key = (params.quiz_id, params.user);
+        let attempt = self
+            .state
+            .pending_attempts
+            .get(&key)
+            .await
+            .expect("Failed to retrieve pending attempt from storage")
+            .expect("Pending attempt not found");
+        self.state
+            .pending_attempts
+            .remove(&key)
+            .expect("Failed to remove pending attempt");
+
+        if params.approved {
+            self.finalize_attempt(&quiz_set, attempt).await;
+        } else {
+            self.state
+                .attempt_receipts
+                .remove(&attempt.receipt)
+                .expect("Failed to remove attempt receipt");
+        }
+    }
+
+    /// 使答卷生效：存储答题记录、记录参与并更新排行榜，返回排行榜名次
+    async fn finalize_attempt(&mut self, quiz_set: &QuizSet, attempt: UserAttempt) -> u32 {
+        let quiz_id = attempt.quiz_id;
+        let user = attempt.user;
+        let nick_name = attempt.nick_name.clone();
+        let (score, completed_at) = (attempt.score, attempt.completed_at);
+        let previous_best = self
+            .state
+            .user_quiz_attempts(quiz_id, &user)
+            .await
+            .expect("Failed to read attempts")
+            .iter()
+            .map(|previous| previous.fixed_score)
+            .max()
+            .unwrap_or_default();
+        let gain = (attempt.fixed_score - previous_best).max(0);
+
+        // 存储答题记录并登记到Quiz的答卷索引
+        let mut index = self
+            .state
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve quiz attempt index")
+            .unwrap_or_default();
+        index.push((user, attempt.attempt_no));
+        let _ = self.state.quiz_attempt_index.insert(&quiz_id, index);
+        let mut user_index = self
+            .state
+            .user_attempt_index
+            .get(&user)
+            .await
+            .expect("Failed to retrieve user attempt index")
+            .unwrap_or_default();
+        user_index.push((quiz_id, attempt.attempt_no));
+        let _ = self.state.user_attempt_index.insert(&user, user_index);
+        let _ = self
+            .state
+            .user_attempts
+            .insert(&(quiz_id, user, attempt.attempt_no), attempt.clone());
+        for result in &attempt.question_results {
+            let correct = i64::from(result.correct);
+            let skipped = i64::from(result.skipped);
+            self.update_question_tally(quiz_id, result.question_id, 1, correct, skipped)
+                .await;
+        }
+        // 记录答题事件
+        self.state.quiz_events.push(attempt);
+
+        // 记录用户参与
+        let mut participations = self
+            .state
+            .user_participations
+            .get(&user)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        let first_in_quiz = !participations.contains(&quiz_id);
+        if first_in_quiz {
+            participations.push(quiz_id);
+            let _ = self.state.user_participations.insert(&user, participations);
+        }
+
+        // 练习模式不计入连续天数和赛季积分
+        if !quiz_set.practice {
+            self.award_season_points(user, score, completed_at).await;
+            // 隐藏结果的Quiz不计入，以免提前泄露分数
+            if !quiz_set.hide_results {
+                self.award_ranking_points(user, nick_name.clone(), gain, completed_at)
+                    .await;
+            }
+            self.mint_certificate(quiz_set, user, score, completed_at)
+                .await;
+        }
+
+        // 更新排行榜
+        let rank = self
+            .update_leaderboard(quiz_set, user, nick_name, true)
+            .await;
+        if !quiz_set.practice {
+            self.update_user_stats(user, |stats| {
+                stats.record_attempt(first_in_quiz, score, rank)
+            })
+            .await;
+        }
+        rank
+    }
+
+    /// 更新用户档案中的累计统计
+    async fn update_user_stats(&mut self, user: AccountOwner, update: impl FnOnce(&mut UserStats)) {
+        let mut profile = self
+            .state
+            .user_profiles
+            .get(&user)
+            .await
+            .expect("Failed to retrieve user profile")
+            .unwrap_or_default();
+        update(&mut profile.stats);
+        let _ = self.state.user_profiles.insert(&user, profile);
+    }
+
+    async fn reveal_content(&mut self, params: RevealContentParams) {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can reveal the quiz content"
+        );
+        let embargo = quiz_set
+            .embargo
+            .clone()
+            .expect("Quiz content is not embargoed");
+        assert!(
+            !quiz_set.content_revealed,
+            "Quiz content has already been revealed"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(
+            quiz_set.has_started(now),
+            "Quiz content can only be revealed once the quiz has started"
+        );
+        assert_eq!(
+            content_commitment(&params.questions, &params.salt),
+            embargo.commitment,
+            "Revealed content does not match the commitment"
+        );
+        assert_eq!(
+            params.questions.len(),
+            embargo.question_count as usize,
+            "Revealed question count does not match the embargo"
+        );
+        if let Some(violation) = validate_questions(&params.questions).into_iter().next() {
+            panic!("{}", violation.message);
+        }
+        self.check_question_media(&params.questions);
+
+        quiz_set.questions = params
+            .questions
+            .into_iter()
+            .enumerate()
+            .map(|(i, q)| Question::new(i as u32, q))
+            .collect();
+        quiz_set.content_revealed = true;
+        self.index_question_fingerprints(&quiz_set).await;
+        let _ = self.state.quiz_sets.insert(&params.quiz_id, quiz_set);
+        self.emit_event(AppEvent::ContentRevealed(ContentRevealedEvent {
+            quiz_id: params.quiz_id,
+            question_count: embargo.question_count,
+        }));
+    }
+
+    async fn reveal_answer_key(&mut self, params: RevealAnswerKeyParams) {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can reveal the answer-key salt"
+        );
+        assert!(
+            now > quiz_set.end_time,
+            "The answer-key salt can only be revealed after the quiz has ended"
+        );
+        assert!(
+            quiz_set.answer_key_salt.is_none(),
+            "The answer-key salt has already been revealed"
+        );
+        let checksum = quiz_set
+            .answer_key_hash
+            .expect("Quiz has no answer-key checksum");
+        assert_eq!(
+            answer_key_hash(&quiz_set.questions, &params.salt),
+            checksum,
+            "Salt does not match the answer-key checksum"
+        );
+        quiz_set.answer_key_salt = Some(params.salt);
+        let _ = self.state.quiz_sets.insert(&params.quiz_id, quiz_set);
+    }
+
+    async fn reveal_results(&mut self, params: RevealResultsParams) {
+        let now = self.runtime.system_time();
+        let mut quiz_set = self.load_quiz(params.quiz_id).await;
+
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can reveal results"
+        );
+        assert!(now > quiz_set.end_time, "Quiz has not ended yet");
+
+        quiz_set.results_revealed = true;
+        let _ = self.state.quiz_sets.insert(&params.quiz_id, quiz_set);
+    }
+
+    /// 已认证的签名者，作为用户身份
+    fn signer(&mut self) -> AccountOwner {
+        self.runtime
+            .authenticated_signer()
+            .expect("Failed to get authenticated signer: no user authenticated")
+    }
+
+    /// 设置了权限主体的Quiz由该主体授权，否则签名者必须是创建账户
+    fn is_quiz_creator(&mut self, quiz_set: &QuizSet) -> bool {
+        match &quiz_set.owner {
+            Some(owner) => self.is_authorized(owner),
+            None => self.runtime.authenticated_signer() == Some(quiz_set.creator),
+        }
+    }
+
+    /// 创建者和所属组织的管理员可以删除或取消Quiz
+    async fn can_moderate_quiz(&mut self, quiz_set: &QuizSet) -> bool {
+        if self.is_quiz_creator(quiz_set) {
+            return true;
+        }
+        match (&quiz_set.org, self.runtime.authenticated_signer()) {
+            (Some(org), Some(signer)) => self.state.is_org_admin(org, &signer).await,
+            _ => false,
+        }
+    }
+
+    /// 当前签名者是否可代表该主体：账户需为签名者本身，链需为当前链且签名者是其所有者之一
+    fn is_authorized(&mut self, principal: &Principal) -> bool {
+        let Some(signer) = self.runtime.authenticated_signer() else {
+            return false;
+        };
+        match principal {
+            Principal::Owner(owner) => *owner == signer,
+            Principal::Chain(chain_id) => {
+                *chain_id == self.runtime.chain_id()
+                    && self.runtime.chain_ownership().verify_owner(&signer)
+            }
+        }
+    }
+
+    /// 返回当前签名者所代表的管理员主体
+    async fn acting_admin(&mut self) -> Principal {
+        let admins = self
+            .state
+            .admins
+            .indices()
+            .await
+            .expect("Failed to read admins");
+        admins
+            .into_iter()
+            .find(|admin| self.is_authorized(admin))
+            .expect("Only admins can perform this operation")
+    }
+
+    async fn register_org(&mut self, params: RegisterOrgParams) {
+        validate_org_slug(&params.slug).unwrap_or_else(|violation| panic!("{}", violation.message));
+        if let Some(violation) = validate_metadata(&params.branding).first() {
+            panic!("{}", violation.message);
+        }
+        assert!(
+            !self.state.orgs.contains_key(&params.slug).await.unwrap(),
+            "Organization already exists"
+        );
+
+        let organization = Organization {
+            slug: params.slug.clone(),
+            name: params.name,
+            owner: self.signer(),
+            branding: params
+                .branding
+                .into_iter()
+                .map(|entry| (entry.key, entry.value))
+                .collect(),
+            settings: OrgSettings::default(),
+            created_at: self.runtime.system_time(),
+        };
+        let _ = self.state.orgs.insert(&params.slug, organization);
+    }
+
+    /// 读取组织并校验签名者在组织中至少具有指定角色
+    async fn load_managed_org(&mut self, slug: &str, role: OrgRole) -> Organization {
+        let signer = self.signer();
+        let organization = self
+            .state
+            .orgs
+            .get(slug)
+            .await
+            .expect("Failed to retrieve organization from storage")
+            .expect("Organization not found");
+        let signer_role = self.state.org_role(slug, &signer).await;
+        match role {
+            OrgRole::Owner => assert_eq!(
+                signer_role,
+                Some(OrgRole::Owner),
+                "Only the organization owner can manage its admins"
+            ),
+            _ => assert!(
+                signer_role >= Some(role),
+                "Only organization admins can manage it"
+            ),
+        }
+        organization
+    }
+
+    async fn add_org_member(&mut self, params: OrgMemberParams) {
+        self.load_managed_org(&params.org, OrgRole::Admin).await;
+        self.state
+            .org_members
+            .insert(&(params.org, params.member))
+            .expect("Failed to add organization member");
+    }
+
+    async fn remove_org_member(&mut self, params: OrgMemberParams) {
+        self.load_managed_org(&params.org, OrgRole::Admin).await;
+        self.state
+            .org_members
+            .remove(&(params.org, params.member))
+            .expect("Failed to remove organization member");
+    }
+
+    async fn set_org_branding(&mut self, params: SetOrgBrandingParams) {
+        let mut organization = self.load_managed_org(&params.org, OrgRole::Admin).await;
+        if let Some(violation) = validate_metadata(&params.branding).first() {
+            panic!("{}", violation.message);
+        }
+        organization.branding = params
+            .branding
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect();
+        let _ = self.state.orgs.insert(&params.org, organization);
+    }
+
+    async fn set_org_settings(&mut self, params: SetOrgSettingsParams) {
+        let mut organization = self.load_managed_org(&params.org, OrgRole::Admin).await;
+        organization.settings = params.settings;
+        let _ = self.state.orgs.insert(&params.org, organization);
+    }
+
+    async fn add_org_admin(&mut self, params: OrgMemberParams) {
+        let organization = self.load_managed_org(&params.org, OrgRole::Owner).await;
+        assert_ne!(
+            organization.owner, params.member,
+            "The organization owner is already an admin"
+        );
+        self.state
+            .org_admins
+            .insert(&(params.org, params.member))
+            .expect("Failed to add organization admin");
+    }
+
+    async fn remove_org_admin(&mut self, params: OrgMemberParams) {
+        self.load_managed_org(&params.org, OrgRole::Owner).await;
+        self.state
+            .org_admins
+            .remove(&(params.org, params.member))
+            .expect("Failed to remove organization admin");
+    }
+
+    async fn add_admin(&mut self, admin: Principal) {
+        self.acting_admin().await;
+        self.state
+            .admins
+            .insert(&admin)
+            .expect("Failed to add admin");
+    }
+
+    async fn add_blackout_window(&mut self, params: AddBlackoutWindowParams) {
+        self.acting_admin().await;
+        let window = validate_blackout_window(&params, self.runtime.system_time())
+            .unwrap_or_else(|violations| panic!("{}", violations[0].message));
+        let window_id = *self.state.next_blackout_id.get();
+        let _ = self.state.blackout_windows.insert(&window_id, window);
+        let next_id = window_id
+            .checked_add(1)
+            .expect("Blackout window ID overflow");
+        self.state.next_blackout_id.set(next_id);
+    }
+
+    async fn remove_blackout_window(&mut self, window_id: u64) {
+        self.acting_admin().await;
+        self.state
+            .blackout_windows
+            .remove(&window_id)
+            .expect("Failed to remove blackout window");
+    }
+
+    /// 向应用事件流发布事件并记录下一个序号
+    fn emit_event(&mut self, event: AppEvent) {
+        let index = self
+            .runtime
+            .emit(StreamName::from(EVENT_STREAM_NAME), &event);
+        self.state.next_event_index.set(index + 1);
+        log_event!(
+            Level::Info,
+            CONTRACT_TARGET,
+            "event emitted",
+            kind = variant_name(&event),
+            index = index,
+        );
+    }
+
+    /// 问题附带的媒体须符合时长上限，媒体和文字稿的数据 blob 须已发布
+    fn check_question_media(&mut self, questions: &[QuestionParams]) {
+        let parameters = self.runtime.application_parameters();
+        if let Some(violation) = validate_question_media(questions, &parameters).first() {
+            panic!("{}", violation.message);
+        }
+        for media in questions
+            .iter()
+            .filter_map(|question| question.media.as_ref())
+        {
+            self.runtime.assert_data_blob_exists(media.blob);
+            if let Some(transcript) = media.transcript {
+                self.runtime.assert_data_blob_exists(transcript);
+            }
+        }
+    }
+
+    /// 定时开始的Quiz不能安排在平台禁排时段内开始
+    async fn check_blackouts(&self, start_time: Timestamp) {
+        let windows: Vec<_> = self
+            .state
+            .blackout_window_list()
+            .await
+            .expect("Failed to read blackout windows")
+            .into_iter()
+            .map(|(_, window)| window)
+            .collect();
+        if let Some(violation) = validate_blackouts(start_time, &windows).first() {
+            panic!("{}", violation.message);
+        }
+    }
+
+    async fn propose_admin_action(&mut self, action: AdminAction) -> u64 {
+        let proposer = self.acting_admin().await;
+        let proposal_id = *self.state.next_proposal_id.get();
+        let proposal = AdminProposal {
+            id: proposal_id,
+            action,
+            confirmations: vec![proposer],
+            executed: false,
+            created_at: self.runtime.system_time(),
+        };
+        let next_id = proposal_id.checked_add(1).expect("Proposal ID overflow");
+        self.state.next_proposal_id.set(next_id);
+        self.apply_confirmations(proposal).await;
+        proposal_id
+    }
+
+    async fn confirm_admin_action(&mut self, proposal_id: u64) {
+        let admin = self.acting_admin().await;
+        let mut proposal = self
+            .state
+            .admin_proposals
+            .get(&proposal_id)
+            .await
+            .expect("Failed to retrieve proposal from storage")
+            .expect("Proposal not found");
+        assert!(!proposal.executed, "Proposal has already been executed");
+        assert!(
+            !proposal.confirmations.contains(&admin),
+            "Admin has already confirmed this proposal"
+        );
+        proposal.confirmations.push(admin);
+        self.apply_confirmations(proposal).await;
+    }
+
+    /// 确认数达到阈值时执行提案，并保存提案状态
+    async fn apply_confirmations(&mut self, mut proposal: AdminProposal) {
+        let threshold = self
+            .runtime
+            .application_parameters()
+            .admin_confirmation_threshold as usize;
+        if proposal.confirmations.len() >= threshold {
+            self.execute_admin_action(&proposal.action, threshold).await;
+            proposal.executed = true;
+        }
+        let proposal_id = proposal.id;
+        let _ = self.state.admin_proposals.insert(&proposal_id, proposal);
+    }
+
+    async fn execute_admin_action(&mut self, action: &AdminAction, threshold: usize) {
+        match action {
+            AdminAction::RemoveQuiz(quiz_id) => {
+                let quiz_set = self.load_quiz(*quiz_id).await;
+                self.remove_quiz(&quiz_set).await;
+            }
+            AdminAction::RemoveAdmin(admin) => {
+                assert!(
+                    self.state.admins.contains(admin).await.unwrap(),
+                    "Admin not found"
+                );
+                // 剩余管理员不足阈值时将无法再执行任何破坏性操作
+                let remaining = self.state.admins.count().await.unwrap() - 1;
+                assert!(
+                    remaining >= threshold,
+                    "Removing this admin would leave fewer admins than the confirmation threshold"
+                );
+                self.state
+                    .admins
+                    .remove(admin)
+                    .expect("Failed to remove admin");
+            }
+        }
+    }
+
+    async fn set_quiz_metadata(&mut self, params: SetQuizMetadataParams) {
+        let mut quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can update metadata"
+        );
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        if let Some(violation) = validate_metadata(&params.metadata).first() {
+            panic!("{}", violation.message);
+        }
+
+        quiz_set.metadata = params
+            .metadata
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect();
+        let _ = self.state.quiz_sets.insert(&params.quiz_id, quiz_set);
+    }
+
+    async fn rename_tag(&mut self, params: RenameTagParams) {
+        self.merge_tags(MergeTagsParams {
+            sources: vec![params.from],
+            target: params.to,
+        })
+        .await;
+    }
+
+    /// 把签名者创建的Quiz上的源标签替换为目标标签，其他创建者的Quiz不受影响
+    async fn merge_tags(&mut self, params: MergeTagsParams) {
+        assert!(!params.sources.is_empty(), "No source tags given");
+        let mut quiz_ids = Vec::new();
+        for source in &params.sources {
+            quiz_ids.extend(
+                self.state
+                    .tag_index
+                    .get(source)
+                    .await
+                    .expect("Failed to read tag index")
+                    .unwrap_or_default(),
+            );
+        }
+        quiz_ids.sort_unstable();
+        quiz_ids.dedup();
+
+        for quiz_id in quiz_ids {
+            let quiz_set = self.load_quiz(quiz_id).await;
+            if !self.is_quiz_creator(&quiz_set) {
+                continue;
+            }
+            let mut tags = Vec::new();
+            for tag in &quiz_set.tags {
+                let tag = if params.sources.contains(tag) {
+                    &params.target
+                } else {
+                    tag
+                };
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            self.retag_quiz(quiz_set, tags).await;
+        }
+    }
+
+    async fn apply_tag(&mut self, params: ApplyTagParams) {
+        for quiz_id in params.quiz_ids {
+            let quiz_set = self.load_quiz(quiz_id).await;
+            assert!(
+                self.is_quiz_creator(&quiz_set),
+                "Only the quiz creator can tag quiz {quiz_id}"
+            );
+            if quiz_set.tags.contains(&params.tag) {
+                continue;
+            }
+            let mut tags = quiz_set.tags.clone();
+            tags.push(params.tag.clone());
+            self.retag_quiz(quiz_set, tags).await;
+        }
+    }
+
+    /// 替换Quiz的标签并同步标签索引
+    async fn retag_quiz(&mut self, mut quiz_set: QuizSet, tags: Vec<String>) {
+        if let Some(violation) = validate_tags(&tags).first() {
+            panic!("{}", violation.message);
+        }
+        for tag in &quiz_set.tags {
+            unindex_quiz(&mut self.state.tag_index, tag, quiz_set.id).await;
+        }
+        for tag in &tags {
+            index_quiz(&mut self.state.tag_index, tag, quiz_set.id).await;
+        }
+        quiz_set.tags = tags;
+        let quiz_id = quiz_set.id;
+        let _ = self.state.quiz_sets.insert(&quiz_id, quiz_set);
+    }
+
+    async fn set_attempt_note(&mut self, params: SetAttemptNoteParams) {
+        let key = (params.quiz_id, self.signer());
+        let has_attempt = !self
+            .state
+            .user_quiz_attempts(key.0, &key.1)
+            .await
+            .expect("Failed to read attempts")
+            .is_empty()
+            || self
+                .state
+                .pending_attempts
+                .contains_key(&key)
+                .await
+                .unwrap();
+        assert!(has_attempt, "Attempt not found");
+        validate_attempt_note(&params.note)
+            .unwrap_or_else(|violation| panic!("{}", violation.message));
+
+        if params.note.is_empty() {
+            self.state
+                .attempt_notes
+                .remove(&key)
+                .expect("Failed to remove attempt note");
+        } else {
+            let _ = self.state.attempt_notes.insert(&key, params.note);
+            self.track_participant(key.0, key.1).await;
+        }
+    }
+
+    /// 向用户的收件箱添加一条通知
+    async fn notify(
+        &mut self,
+        user: AccountOwner,
+        kind: NotificationKind,
+        quiz_id: u64,
+        message: String,
+    ) {
+        let notification_id = *self.state.next_notification_id.get();
+        let notification = Notification {
+            kind,
+            quiz_id,
+            message,
+            created_at: self.runtime.system_time(),
+            read: false,
+        };
+        let _ = self
+            .state
+            .notifications
+            .load_entry_mut(&user)
+            .await
+            .expect("Failed to retrieve notifications")
+            .insert(&notification_id, notification);
+        let next_id = notification_id
+            .checked_add(1)
+            .expect("Notification ID overflow");
+        self.state.next_notification_id.set(next_id);
+    }
+
+    /// 通知获奖者，currency 说明奖励的币种
+    async fn notify_payouts(&mut self, quiz_id: u64, payouts: &[PrizePayout], currency: &str) {
+        for payout in payouts {
+            self.notify(
+                payout.user,
+                NotificationKind::PrizeAwarded,
+                quiz_id,
+                format!(
+                    "You won {} {currency} for finishing #{} in quiz {quiz_id}",
+                    payout.amount, payout.rank
+                ),
+            )
+            .await;
+        }
+    }
+
+    /// 把签名者的通知标记为已读，未指定ID时标记全部
+    async fn mark_notifications_read(&mut self, params: MarkNotificationsReadParams) {
+        let signer = self.signer();
+        let notifications = self
+            .state
+            .notifications
+            .load_entry_mut(&signer)
+            .await
+            .expect("Failed to retrieve notifications");
+        let ids = if params.ids.is_empty() {
+            notifications
+                .indices()
+                .await
+                .expect("Failed to read notifications")
+        } else {
+            params.ids
+        };
+        for notification_id in ids {
+            let notification = notifications
+                .get_mut(&notification_id)
+                .await
+                .expect("Failed to read notification");
+            if let Some(notification) = notification {
+                notification.read = true;
+            }
+        }
+    }
+
+    /// 与复习一样，提交过答卷或Quiz结束后才能收藏问题
+    async fn set_bookmark(&mut self, params: SetBookmarkParams) {
+        let now = self.runtime.system_time();
+        let user = self.signer();
+        let key = (user, params.quiz_id, params.question_id);
+        if !params.bookmarked {
+            self.state
+                .bookmarks
+                .remove(&key)
+                .expect("Failed to remove bookmark");
+            self.unindex_bookmark(user, params.quiz_id, params.question_id)
+                .await;
+            return;
+        }
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            (params.question_id as usize) < quiz_set.questions.len(),
+            "Question not found"
+        );
+        let reviewable = self
+            .state
+            .reviewable_questions(&quiz_set, &user, now)
+            .await
+            .expect("Failed to read attempts")
+            .expect("Questions can be bookmarked once no attempts are left or the quiz has ended");
+        assert!(
+            reviewable.contains(&params.question_id),
+            "Question was not drawn in any of the user's attempts"
+        );
+        let _ = self.state.bookmarks.insert(&key, now);
+        self.track_participant(params.quiz_id, user).await;
+        let mut index = self
+            .state
+            .user_bookmark_index
+            .get(&user)
+            .await
+            .expect("Failed to retrieve bookmark index")
+            .unwrap_or_default();
+        if !index.contains(&(params.quiz_id, params.question_id)) {
+            index.push((params.quiz_id, params.question_id));
+            let _ = self.state.user_bookmark_index.insert(&user, index);
+        }
+    }
+
+    /// 从用户的收藏索引中移除一个问题
+    async fn unindex_bookmark(&mut self, user: AccountOwner, quiz_id: u64, question_id: u32) {
+        let mut index = self
+            .state
+            .user_bookmark_index
+            .get(&user)
+            .await
+            .expect("Failed to retrieve bookmark index")
+            .unwrap_or_default();
+        index.retain(|entry| *entry != (quiz_id, question_id));
+        if index.is_empty() {
+            self.state
+                .user_bookmark_index
+                .remove(&user)
+                .expect("Failed to remove bookmark index");
+        } else {
+            let _ = self.state.user_bookmark_index.insert(&user, index);
+        }
+    }
+
+    async fn appeal_question(&mut self, params: AppealQuestionParams) {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(now > quiz_set.end_time, "Quiz has not ended yet");
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        assert!(
+            (params.question_id as usize) < quiz_set.questions.len(),
+            "Question not found"
+        );
+        let voter = self.signer();
+        let participated = !self
+            .state
+            .user_quiz_attempts(params.quiz_id, &voter)
+            .await
+            .expect("Failed to read attempts")
+            .is_empty();
+        assert!(participated, "Only participants can appeal questions");
+
+        let key = (params.quiz_id, params.question_id);
+        let mut appeal = self
+            .state
+            .question_appeals
+            .get(&key)
+            .await
+            .expect("Failed to retrieve appeal from storage")
+            .unwrap_or(QuestionAppeal {
+                voters: Vec::new(),
+                status: DisputeStatus::Voting,
+            });
+        assert!(
+            matches!(
+                appeal.status,
+                DisputeStatus::Voting | DisputeStatus::RegradeOpen
+            ),
+            "Appeal has already been resolved"
+        );
+        assert!(
+            !appeal.voters.contains(&voter),
+            "User has already appealed this question"
+        );
+        appeal.voters.push(voter);
+
+        // 达到法定票数时开启重新评分案件
+        let votes = appeal.voters.len() as u32;
+        if appeal.status == DisputeStatus::Voting
+            && votes >= self.runtime.application_parameters().appeal_quorum
+        {
+            appeal.status = DisputeStatus::RegradeOpen;
+            self.emit_event(AppEvent::RegradeCaseOpened(RegradeCaseOpenedEvent {
+                quiz_id: params.quiz_id,
+                question_id: params.question_id,
+                votes,
+            }));
+        }
+        let _ = self.state.question_appeals.insert(&key, appeal);
+    }
+
+    async fn resolve_regrade_case(&mut self, params: ResolveRegradeCaseParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can resolve regrade cases"
+        );
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+
+        let key = (params.quiz_id, params.question_id);
+        let mut appeal = self
+            .state
+            .question_appeals
+            .get(&key)
+            .await
+            .expect("Failed to retrieve appeal from storage")
+            .expect("Appeal not found");
+        assert_eq!(
+            appeal.status,
+            DisputeStatus::RegradeOpen,
+            "No open regrade case for this question"
+        );
+        appeal.status = if params.upheld {
+            DisputeStatus::Upheld
+        } else {
+            DisputeStatus::Dismissed
+        };
+        let _ = self.state.question_appeals.insert(&key, appeal);
+
+        if params.upheld {
+            // 重新评分前先冻结原始榜单，重新评分后的结果作为新的修订版
+            if !self.is_leaderboard_frozen(params.quiz_id).await {
+                self.snapshot_leaderboard(params.quiz_id).await;
+            }
+            self.regrade_question(&quiz_set, params.question_id).await;
+            self.snapshot_leaderboard(params.quiz_id).await;
+        }
+    }
+
+    /// 申诉成立的问题对所有答卷给满分，并更新排行榜
+    async fn regrade_question(&mut self, quiz_set: &QuizSet, question_id: u32) {
+        let index = question_id as usize;
+        let question = quiz_set.questions.get(index).expect("Question not found");
+        let full_points = fixed_points(question.points);
+
+        let attempts = self
+            .state
+            .quiz_attempts(quiz_set.id)
+            .await
+            .expect("Failed to read attempts");
+        let pending = self
+            .state
+            .quiz_pending_attempts(quiz_set.id)
+            .await
+            .expect("Failed to read pending attempts");
+
+        let parameters = self.runtime.application_parameters();
+        let regrade = |attempt: &mut UserAttempt| {
+            // 题库模式下未抽到该问题的答卷不受影响
+            if let Some(result) = attempt
+                .question_results
+                .iter_mut()
+                .find(|result| result.question_id == question_id)
+            {
+                result.points_awarded = full_points;
+            }
+            attempt.fixed_score = total_score(&attempt.question_results);
+            attempt.score = parameters.display_score(attempt.fixed_score);
+            attempt.passed = quiz_set.passed(attempt.score);
+        };
+        let mut users = Vec::new();
+        for mut attempt in attempts {
+            regrade(&mut attempt);
+            let key = (attempt.quiz_id, attempt.user, attempt.attempt_no);
+            if !users.iter().any(|(user, _)| *user == attempt.user) {
+                users.push((attempt.user, attempt.nick_name.clone()));
+            }
+            let _ = self.state.user_attempts.insert(&key, attempt);
+        }
+        // 重新评分不是名次竞争，不发送被超过的通知
+        for (user, nick_name) in users {
+            self.update_leaderboard(quiz_set, user, nick_name, false)
+                .await;
+        }
+        for mut attempt in pending {
+            regrade(&mut attempt);
+            let key = (attempt.quiz_id, attempt.user);
+            let _ = self.state.pending_attempts.insert(&key, attempt);
+        }
+    }
+
+    /// 创建者按评分标准给一份答卷中的问答题评分，并更新答卷得分和排行榜
+    async fn grade_essay(&mut self, params: GradeEssayParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can grade essay questions"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        let question = quiz_set
+            .questions
+            .get(params.question_id as usize)
+            .expect("Question not found");
+        let points =
+            rubric_score(question, &params.levels).unwrap_or_else(|error| panic!("{error}"));
+
+        // 等待监考确认的答卷也可以先评分
+        let key = (params.quiz_id, params.user, params.attempt_no);
+        let (mut attempt, pending) = match self
+            .state
+            .user_attempts
+            .get(&key)
+            .await
+            .expect("Failed to retrieve attempt from storage")
+        {
+            Some(attempt) => (attempt, false),
+            None => {
+                let attempt = self
+                    .state
+                    .pending_attempts
+                    .get(&(params.quiz_id, params.user))
+                    .await
+                    .expect("Failed to retrieve pending attempt from storage")
+                    .filter(|attempt| attempt.attempt_no == params.attempt_no)
+                    .expect("Attempt not found");
+                (attempt, true)
+            }
+        };
+        let result = attempt
+            .question_results
+            .iter_mut()
+            .find(|result| result.question_id == params.question_id)
+            .expect("Question was not drawn for this attempt");
+        let was_correct = result.correct;
+        result.points_awarded = points;
+        result.correct = points == fixed_points(question.points);
+        result.rubric_levels = params.levels;
+        // 待确认的答卷尚未计入统计
+        if !pending && result.correct != was_correct {
+            let correct = if result.correct { 1 } else { -1 };
+            self.update_question_tally(params.quiz_id, params.question_id, 0, correct, 0)
+                .await;
+        }
+
+        let parameters = self.runtime.application_parameters();
+        attempt.fixed_score = total_score(&attempt.question_results);
+        attempt.score = parameters.display_score(attempt.fixed_score);
+        attempt.passed = quiz_set.passed(attempt.score);
+        if pending {
+            let _ = self
+                .state
+                .pending_attempts
+                .insert(&(params.quiz_id, params.user), attempt);
+        } else {
+            let nick_name = attempt.nick_name.clone();
+            let _ = self.state.user_attempts.insert(&key, attempt);
+            self.update_leaderboard(&quiz_set, params.user, nick_name, true)
+                .await;
+        }
+    }
+
+    /// 测验结束后冻结原始排行榜
+    async fn freeze_leaderboard(&mut self, quiz_id: u64) {
+        let quiz_set = self.load_quiz(quiz_id).await;
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(
+            self.runtime.system_time() > quiz_set.end_time,
+            "Quiz has not ended yet"
+        );
+        assert!(
+            !self.is_leaderboard_frozen(quiz_id).await,
+            "Leaderboard is already frozen"
+        );
+        self.snapshot_leaderboard(quiz_id).await;
+    }
+
+    /// 创建者为遇到意外（如答题中途断电）的用户额外增加一次答题机会，并记录原因
+    async fn grant_extra_attempt(&mut self, params: GrantExtraAttemptParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can grant extra attempts"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        assert!(
+            !params.reason.trim().is_empty(),
+            "A reason is required to grant an extra attempt"
+        );
+
+        let key = (params.quiz_id, params.user);
+        let extra_attempts = self
+            .state
+            .extra_attempts
+            .get(&key)
+            .await
+            .expect("Failed to retrieve extra attempts")
+            .unwrap_or_default();
+        let _ = self.state.extra_attempts.insert(&key, extra_attempts + 1);
+        self.track_participant(key.0, key.1).await;
+        let granted_by = self.signer();
+        let granted_at = self.runtime.system_time();
+        self.emit_event(AppEvent::ExtraAttemptGranted(ExtraAttemptGrantedEvent {
+            quiz_id: params.quiz_id,
+            user: params.user,
+            granted_by,
+            reason: params.reason,
+            granted_at,
+        }));
+    }
+
+    async fn grant_accommodation(&mut self, params: GrantAccommodationParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can grant accommodations"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        validate_time_multiplier(params.multiplier)
+            .unwrap_or_else(|violation| panic!("{}", violation.message));
+
+        let key = (params.quiz_id, params.user);
+        if params.multiplier == 100 {
+            self.state
+                .accommodations
+                .remove(&key)
+                .expect("Failed to remove accommodation");
+        } else {
+            self.state
+                .accommodations
+                .insert(&key, params.multiplier)
+                .expect("Failed to store accommodation");
+            self.track_participant(key.0, key.1).await;
+        }
+    }
+
+    /// 为尚未开始的Quiz生成限时预览令牌（仅创建者）
+    async fn create_preview_token(&mut self, params: CreatePreviewTokenParams) -> CryptoHash {
+        let now = self.runtime.system_time();
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can create preview tokens"
+        );
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(
+            !quiz_set.has_started(now),
+            "Preview tokens are only available before the quiz starts"
+        );
+        if let Some(violation) = validate_preview_token(&params).into_iter().next() {
+            panic!("{}", violation.message);
+        }
+        let token = preview_token(params.quiz_id, &params.label, self.runtime.block_height());
+        assert!(
+            !self
+                .state
+                .preview_tokens
+                .contains_key(&token)
+                .await
+                .expect("Failed to read preview tokens"),
+            "A preview token with this label was already created in this block"
+        );
+        let preview = PreviewToken {
+            quiz_id: params.quiz_id,
+            label: params.label,
+            created_at: now,
+            expires_at: now.saturating_add(TimeDelta::from_secs(params.valid_secs)),
+        };
+        let _ = self.state.preview_tokens.insert(&token, preview);
+        let mut tokens = self
+            .state
+            .quiz_preview_tokens
+            .get(&params.quiz_id)
+            .await
+            .expect("Failed to retrieve preview tokens")
+            .unwrap_or_default();
+        tokens.push(token);
+        let _ = self
+            .state
+            .quiz_preview_tokens
+            .insert(&params.quiz_id, tokens);
+        token
+    }
+
+    /// 撤销预览令牌（仅创建者）
+    async fn revoke_preview_token(&mut self, token: CryptoHash) {
+        let preview = self
+            .state
+            .preview_tokens
+            .get(&token)
+            .await
+            .expect("Failed to retrieve preview token")
+            .expect("Preview token not found");
+        let quiz_set = self.load_quiz(preview.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can revoke preview tokens"
+        );
+        self.state
+            .preview_tokens
+            .remove(&token)
+            .expect("Failed to remove preview token");
+        let mut tokens = self
+            .state
+            .quiz_preview_tokens
+            .get(&preview.quiz_id)
+            .await
+            .expect("Failed to retrieve preview tokens")
+            .unwrap_or_default();
+        tokens.retain(|existing| *existing != token);
+        if tokens.is_empty() {
+            self.state
+                .quiz_preview_tokens
+                .remove(&preview.quiz_id)
+                .expect("Failed to remove preview tokens");
+        } else {
+            let _ = self
+                .state
+                .quiz_preview_tokens
+                .insert(&preview.quiz_id, tokens);
+        }
+    }
+
+    /// 保存公告、通知浏览过或开始过答题的用户，并在事件流中发出公告
+    async fn post_announcement(&mut self, params: PostAnnouncementParams) {
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(
+            self.is_quiz_creator(&quiz_set),
+            "Only the quiz creator can post announcements"
+        );
+        validate_announcement(&params.text)
+            .unwrap_or_else(|violation| panic!("{}", violation.message));
+
+        let mut announcements = self
+            .state
+            .announcements
+            .get(&params.quiz_id)
+            .await
+            .expect("Failed to retrieve announcements")
+            .unwrap_or_default();
+        assert!(
+            announcements.len() < MAX_ANNOUNCEMENTS_PER_QUIZ,
+            "Too many announcements (maximum {MAX_ANNOUNCEMENTS_PER_QUIZ})"
+        );
+        let posted_at = self.runtime.system_time();
+        announcements.push(CreatorAnnouncement {
+            text: params.text.clone(),
+            posted_at,
+        });
+        let _ = self
+            .state
+            .announcements
+            .insert(&params.quiz_id, announcements);
+
+        // 优先通知开始过答题的用户，再由浏览者补足，总数有上限；其余用户查看 quizAnnouncements
+        let starters = QuizState::quiz_users(
+            &self.state.quiz_starters,
+            params.quiz_id,
+            MAX_AUDIENCE_NOTIFICATIONS,
+        )
+        .await
+        .expect("Failed to read quiz starters");
+        let viewers = QuizState::quiz_users(
+            &self.state.quiz_viewers,
+            params.quiz_id,
+            MAX_AUDIENCE_NOTIFICATIONS,
+        )
+        .await
+        .expect("Failed to read quiz viewers");
+        let mut notified = BTreeSet::from([quiz_set.creator]);
+        let recipients: Vec<AccountOwner> = starters
+            .into_iter()
+            .chain(viewers)
+            .filter(|user| notified.insert(*user))
+            .take(MAX_AUDIENCE_NOTIFICATIONS)
+            .collect();
+        for recipient in recipients {
+            self.notify(
+                recipient,
+                NotificationKind::Announcement,
+                params.quiz_id,
+                params.text.clone(),
+            )
+            .await;
+        }
+        self.emit_event(AppEvent::AnnouncementPosted(AnnouncementPostedEvent {
+            quiz_id: params.quiz_id,
+            text: params.text,
+            posted_at,
+        }));
+    }
+
+    /// 用户在该Quiz的答题时限倍数（百分比），未获得延长时为100
+    async fn time_multiplier(&self, quiz_id: u64, user: AccountOwner) -> u32 {
+        self.state
+            .accommodations
+            .get(&(quiz_id, user))
+            .await
+            .expect("Failed to retrieve accommodation")
+            .unwrap_or(100)
+    }
+
+    /// 按奖池比例向最终排行榜靠前的用户发放奖金，未分配的部分退回给出资者
+    async fn distribute_prizes(&mut self, quiz_id: u64) {
+        let quiz_set = self.load_quiz(quiz_id).await;
+        assert!(quiz_set.finalized, "Quiz has not been finalized yet");
+        let mut pool = self
+            .state
+            .prize_pools
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve prize pool")
+            .expect("Quiz has no prize pool");
+        assert!(
+            pool.distribution.is_none(),
+            "Prizes have already been distributed"
+        );
+        let entries = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+            .and_then(|mut snapshots| snapshots.pop())
+            .map(|snapshot| snapshot.entries)
+            .unwrap_or_default();
+
+        // 已收取的参赛费计入奖池
+        pool.entry_fees = self
+            .take_entry_fees(quiz_id)
+            .await
+            .into_iter()
+            .fold(Amount::ZERO, |total, (_user, fee)| {
+                total.saturating_add(fee)
+            });
+        let total = pool.amount.saturating_add(pool.entry_fees);
+        let mut remaining = total;
+        let mut payouts = Vec::new();
+        // 设置了领取期限时奖金留在托管中等待获奖者领取
+        let claim_deadline = self
+            .runtime
+            .application_parameters()
+            .prize_claim_window_secs
+            .map(|secs| {
+                self.runtime
+                    .system_time()
+                    .saturating_add(TimeDelta::from_secs(secs))
+            });
+        for (index, (split, entry)) in pool.splits.iter().zip(&entries).enumerate() {
+            let amount = total.saturating_mul(u128::from(*split)).saturating_div(100);
+            match claim_deadline {
+                Some(claim_deadline) if amount > Amount::ZERO => {
+                    let unclaimed = UnclaimedPrize {
+                        rank: index as u32 + 1,
+                        amount,
+                        claim_deadline,
+                    };
+                    let _ = self
+                        .state
+                        .unclaimed_prizes
+                        .insert(&(quiz_id, entry.user), unclaimed);
+                }
+                _ => self.pay_from_escrow(entry.user, amount),
+            }
+            remaining = remaining.saturating_sub(amount);
+            payouts.push(PrizePayout {
+                rank: index as u32 + 1,
+                user: entry.user,
+                amount,
+            });
+        }
+        if remaining > Amount::ZERO {
+            self.pay_from_escrow(pool.funder, remaining);
+        }
+        for prize in &mut pool.token_prizes {
+            let mut remaining = prize.amount;
+            for (index, (split, entry)) in prize.splits.iter().zip(&entries).enumerate() {
+                let amount = prize
+                    .amount
+                    .saturating_mul(u128::from(*split))
+                    .saturating_div(100);
+                if amount > Amount::ZERO {
+                    self.transfer_token(prize.token, entry.user, amount);
+                }
+                remaining = remaining.saturating_sub(amount);
+                prize.payouts.push(PrizePayout {
+                    rank: index as u32 + 1,
+                    user: entry.user,
+                    amount,
+                });
+            }
+            if remaining > Amount::ZERO {
+                self.transfer_token(prize.token, prize.sponsor, remaining);
+            }
+            prize.refunded = remaining;
+        }
+        pool.distribution = Some(PrizeDistribution {
+            payouts: payouts.clone(),
+            refunded: remaining,
+            distributed_at: self.runtime.system_time(),
+            block_height: self.runtime.block_height(),
+        });
+        let token_prizes = pool.token_prizes.clone();
+        let _ = self.state.prize_pools.insert(&quiz_id, pool);
+        self.notify_payouts(quiz_id, &payouts, "native tokens")
+            .await;
+        for prize in &token_prizes {
+            let currency = format!("tokens of application {}", prize.token);
+            self.notify_payouts(quiz_id, &prize.payouts, &currency)
+                .await;
+        }
+        self.emit_event(AppEvent::PrizesDistributed(PrizesDistributedEvent {
+            quiz_id,
+            payouts,
+            refunded: remaining,
+            token_prizes,
+        }));
+    }
+
+    /// 获奖者在领取期限内领取奖金
+    async fn claim_prize(&mut self, quiz_id: u64) {
+        let user = self.signer();
+        let key = (quiz_id, user);
+        let unclaimed = self
+            .state
+            .unclaimed_prizes
+            .get(&key)
+            .await
+            .expect("Failed to retrieve unclaimed prize")
+            .expect("No unclaimed prize for this account");
+        assert!(
+            self.runtime.system_time() <= unclaimed.claim_deadline,
+            "The claim window has closed"
+        );
+        self.pay_from_escrow(user, unclaimed.amount);
+        self.state
+            .unclaimed_prizes
+            .remove(&key)
+            .expect("Failed to remove unclaimed prize");
+    }
+
+    /// 把领取期限已过的奖金转给金库（未设置时退回出资者），并为每位原获奖者留下清扫记录
+    async fn sweep_unclaimed_prizes(&mut self, quiz_id: u64) {
+        let now = self.runtime.system_time();
+        let mut expired = Vec::new();
+        for user in self
+            .state
+            .participants_of(quiz_id)
+            .await
+            .expect("Failed to read quiz participants")
+        {
+            let unclaimed = self
+                .state
+                .unclaimed_prizes
+                .get(&(quiz_id, user))
+                .await
+                .expect("Failed to read unclaimed prizes");
+            if let Some(unclaimed) = unclaimed.filter(|unclaimed| now > unclaimed.claim_deadline) {
+                expired.push((user, unclaimed));
+            }
+        }
+        assert!(!expired.is_empty(), "No expired unclaimed prizes to sweep");
+        let recipient = match self.runtime.application_parameters().treasury {
+            Some(treasury) => treasury,
+            None => {
+                self.state
+                    .prize_pools
+                    .get(&quiz_id)
+                    .await
+                    .expect("Failed to retrieve prize pool")
+                    .expect("Quiz has no prize pool")
+                    .funder
+            }
+        };
+        for (user, unclaimed) in expired {
+            self.pay_from_escrow(recipient, unclaimed.amount);
+            self.state
+                .unclaimed_prizes
+                .remove(&(quiz_id, user))
+                .expect("Failed to remove unclaimed prize");
+            let sweep = PrizeSweep {
+                rank: unclaimed.rank,
+                amount: unclaimed.amount,
+                recipient,
+                swept_at: now,
+            };
+            let _ = self.state.prize_sweeps.insert(&(quiz_id, user), sweep);
+            self.emit_event(AppEvent::PrizeSwept(PrizeSweptEvent {
+                quiz_id,
+                user,
+                rank: unclaimed.rank,
+                amount: unclaimed.amount,
+                recipient,
+            }));
+        }
+    }
+
+    /// 赞助者在奖池上追加代币奖金；同一赞助者追加同一代币时累加金额
+    async fn add_token_prize(&mut self, params: AddTokenPrizeParams) {
+        let sponsor = self.signer();
+        let quiz_set = self.load_quiz(params.quiz_id).await;
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(!quiz_set.finalized, "Quiz has been finalized");
+        assert!(
+            params.amount > Amount::ZERO,
+            "Token prize must be greater than zero"
+        );
+        let mut pool = self
+            .state
+            .prize_pools
+            .get(&params.quiz_id)
+            .await
+            .expect("Failed to retrieve prize pool")
+            .expect("Token prizes can only be added on top of a prize pool");
+        assert!(
+            pool.distribution.is_none(),
+            "Prizes have already been distributed"
+        );
+        assert!(
+            params.splits.is_empty() || valid_prize_splits(&params.splits),
+            "Prize splits must be positive percentages adding up to 100"
+        );
+
+        // 代币从赞助者的账户转入本应用的账户
+        let operation = FungibleOperation::Transfer {
+            owner: sponsor,
+            amount: params.amount,
+            target_account: fungible::Account {
+                chain_id: self.runtime.chain_id(),
+                owner: AccountOwner::from(self.runtime.application_id()),
+            },
+        };
+        self.runtime.call_application(
+            true,
+            params.token.with_abi::<FungibleTokenAbi>(),
+            &operation,
+        );
+
+        match pool
+            .token_prizes
+            .iter_mut()
+            .find(|prize| prize.token == params.token && prize.sponsor == sponsor)
+        {
+            Some(prize) => {
+                prize.amount = prize.amount.saturating_add(params.amount);
+                if !params.splits.is_empty() {
+                    prize.splits = params.splits;
+                }
+            }
+            None => {
+                assert!(
+                    !params.splits.is_empty(),
+                    "Prize splits are required for a new token prize"
+                );
+                pool.token_prizes.push(TokenPrize {
+                    token: params.token,
+                    sponsor,
+                    amount: params.amount,
+                    splits: params.splits,
+                    payouts: Vec::new(),
+                    refunded: Amount::ZERO,
+                });
+            }
+        }
+        let _ = self.state.prize_pools.insert(&params.quiz_id, pool);
+    }
+
+    /// 用户首次开始答题时收取参赛费，转入应用账户托管
+    async fn collect_entry_fee(&mut self, quiz_set: &QuizSet, user: AccountOwner) {
+        let Some(fee) = quiz_set.entry_fee else {
+            return;
+        };
+        let key = (quiz_set.id, user);
+        if self
+            .state
+            .escrow
+            .contains_key(&key)
+            .await
+            .expect("Failed to read escrow")
+        {
+            return;
+        }
+        let escrow = self.escrow_account();
+        self.runtime.transfer(user, escrow, fee);
+        let _ = self.state.escrow.insert(&key, fee);
+    }
+
+    /// 取出Quiz托管的全部参赛费记录
+    async fn take_entry_fees(&mut self, quiz_id: u64) -> Vec<(AccountOwner, Amount)> {
+        let mut fees = Vec::new();
+        for user in self
+            .state
+            .participants_of(quiz_id)
+            .await
+            .expect("Failed to read quiz participants")
+        {
+            let key = (quiz_id, user);
+            if let Some(fee) = self
+                .state
+                .escrow
+                .get(&key)
+                .await
+                .expect("Failed to read escrow")
+            {
+                fees.push((user, fee));
+                self.state
+                    .escrow
+                    .remove(&key)
+                    .expect("Failed to remove escrow entry");
+            }
+        }
+        fees
+    }
+
+    /// 应用托管资金的账户
+    fn escrow_account(&mut self) -> Account {
+        let owner = AccountOwner::from(self.runtime.application_id());
+        self.chain_account(owner)
+    }
+
+    /// 本链上属于 owner 的账户
+    fn chain_account(&mut self, owner: AccountOwner) -> Account {
+        Account {
+            chain_id: self.runtime.chain_id(),
+            owner,
+        }
+    }
+
+    /// 从应用托管账户向本链上的用户转账
+    fn pay_from_escrow(&mut self, owner: AccountOwner, amount: Amount) {
+        let escrow = AccountOwner::from(self.runtime.application_id());
+        let destination = self.chain_account(owner);
+        self.runtime.transfer(escrow, destination, amount);
+    }
+
+    /// 测验结束后最终确定结果：按已保存的答案提交进行中的会话，冻结排行榜并锁定Quiz
+    async fn finalize_quiz(&mut self, quiz_id: u64) {
+        let mut quiz_set = self.load_quiz(quiz_id).await;
+        assert!(!quiz_set.cancelled, "Quiz has been cancelled");
+        assert!(!quiz_set.finalized, "Quiz has already been finalized");
+        assert!(
+            self.runtime.system_time() > quiz_set.end_time,
+            "Quiz has not ended yet"
+        );
+
+        // 测验结束后所有进行中的会话都按放弃处理
+        self.expire_abandoned_attempts(ExpireAbandonedAttemptsParams { quiz_id })
+            .await;
+        let pending = self
+            .state
+            .quiz_pending_attempts(quiz_id)
+            .await
+            .expect("Failed to read pending attempts");
+        assert!(
+            pending.is_empty(),
+            "Attempts are still awaiting proctor confirmation"
+        );
+        for question in &quiz_set.questions {
+            let appeal = self
+                .state
+                .question_appeals
+                .get(&(quiz_id, question.id))
+                .await
+                .expect("Failed to read question appeals");
+            assert!(
+                appeal.is_none_or(|appeal| appeal.status != DisputeStatus::RegradeOpen),
+                "Regrade cases are still open"
+            );
+        }
+
+        // 已冻结的排行榜沿用最新修订版，重新评分会自动生成新的修订版
+        if !self.is_leaderboard_frozen(quiz_id).await {
+            self.snapshot_leaderboard(quiz_id).await;
+        }
+        let snapshot = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+            .and_then(|mut snapshots| snapshots.pop())
+            .expect("Leaderboard snapshot not found");
+        // 奖项名次同分同时先加赛，加赛结束后按加赛成绩重排再结算
+        let playoff = self
+            .state
+            .playoffs
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve playoff");
+        let snapshot = match playoff {
+            Some(playoff) => self.apply_playoff(quiz_id, &playoff, snapshot).await,
+            None => {
+                let places = self.prize_places(&quiz_set).await;
+                let tied_users = prize_ties(&snapshot.entries, places);
+                if !tied_users.is_empty() && self.start_playoff(&quiz_set, tied_users).await {
+                    return;
+                }
+                snapshot
+            }
+        };
+
+        let token_rewards = if quiz_set.practice {
+            Vec::new()
+        } else {
+            self.transfer_token_rewards(&snapshot.entries)
+        };
+        quiz_set.finalized = true;
+        let members_only = quiz_set.members_only;
+        let _ = self.state.quiz_sets.insert(&quiz_id, quiz_set);
+        let finalization = Finalization {
+            finalized_at: self.runtime.system_time(),
+            block_height: self.runtime.block_height(),
+            revision: snapshot.revision,
+            standings_hash: standings_hash(quiz_id, snapshot.revision, &snapshot.entries),
+            token_rewards: token_rewards.clone(),
+        };
+        if !members_only {
+            self.broadcast(Message::QuizFinalized {
+                quiz_id,
+                revision: finalization.revision,
+                standings_hash: finalization.standings_hash,
+            })
+            .await;
+        }
+        let _ = self.state.finalizations.insert(&quiz_id, finalization);
+        self.notify_payouts(quiz_id, &token_rewards, "reward tokens")
+            .await;
+        self.emit_event(AppEvent::QuizFinalized(QuizFinalizedEvent {
+            quiz_id,
+            revision: snapshot.revision,
+            top_entries: snapshot
+                .entries
+                .into_iter()
+                .take(FINAL_TOP_ENTRIES)
+                .collect(),
+            token_rewards,
+        }));
+    }
+
+    /// 最终确定时发放奖池奖金或奖励代币的名次数，练习卷和加赛没有奖项
+    async fn prize_places(&mut self, quiz_set: &QuizSet) -> usize {
+        if quiz_set.practice {
+            return 0;
+        }
+        let parameters = self.runtime.application_parameters();
+        let mut places = if parameters.reward_token.is_some() {
+            parameters.reward_token_amounts.len()
+        } else {
+            0
+        };
+        if let Some(pool) = self
+            .state
+            .prize_pools
+            .get(&quiz_set.id)
+            .await
+            .expect("Failed to retrieve prize pool")
+        {
+            places = places.max(pool.splits.len());
+            for prize in &pool.token_prizes {
+                places = places.max(prize.splits.len());
+            }
+        }
+        places
+    }
+
+    /// 从创建者往期公开Quiz中抽题，为同分同时的答题者创建立即开始、每人限答一次的加赛；
+    /// 这些题目的答案可能已可复习，加赛比的是对往期内容的掌握而非保密题目。
+    /// 未启用加赛或没有可用题目时返回 false，按原排行榜顺序结算
+    async fn start_playoff(&mut self, quiz_set: &QuizSet, tied_users: Vec<AccountOwner>) -> bool {
+        let parameters = self.runtime.application_parameters();
+        if parameters.playoff_question_count == 0 {
+            return false;
+        }
+        let now = self.runtime.system_time();
+        let pool: Vec<_> = self
+            .state
+            .practice_pool(&quiz_set.creator, &[], None, now)
+            .await
+            .expect("Failed to collect playoff questions")
+            .into_iter()
+            .filter(|entry| entry.quiz_id != quiz_set.id)
+            .collect();
+        if pool.is_empty() {
+            return false;
+        }
+
+        let playoff_quiz_id = *self.state.next_quiz_id.get();
+        let questions = sample_questions(
+            pool,
+            parameters.playoff_question_count as usize,
+            playoff_quiz_id,
+            quiz_set.creator,
+            self.runtime.block_height(),
+        );
+        let playoff_quiz = QuizSet {
+            id: playoff_quiz_id,
+            title: format!("{} playoff", quiz_set.title),
+            description: String::new(),
+            creator: quiz_set.creator,
+            creator_name: quiz_set.creator_name.clone(),
+            time_limit: PLAYOFF_SECS_PER_QUESTION * questions.len() as u64,
+            questions,
+            start_time: now,
+            end_time: now.saturating_add(TimeDelta::from_secs(parameters.playoff_window_secs)),
+            created_at: now,
+            practice: true,
+            playoff_of: Some(quiz_set.id),
+            hide_results: false,
+            reveal_delay: None,
+            results_revealed: false,
+            proctor: None,
+            owner: None,
+            metadata: Vec::new(),
+            start_mode: QuizStartMode::Scheduled,
+            scoring: quiz_set.scoring,
+            allow_dont_know: false,
+            org: None,
+            members_only: false,
+            shuffle_questions: false,
+            questions_per_attempt: None,
+            embargo: None,
+            max_attempts: 1,
+            attempt_policy: AttemptPolicy::default(),
+            attestation_app: None,
+            entry_fee: None,
+            category: String::new(),
+            tags: Vec::new(),
+            pass_score: None,
+            soft_launch: None,
+            answer_key_hash: None,
+            answer_key_salt: None,
+            content_revealed: false,
+            is_started: false,
+            cancelled: false,
+            finalized: false,
+        };
+        self.store_new_quiz(playoff_quiz).await;
+
+        for user in &tied_users {
+            self.notify(
+                *user,
+                NotificationKind::Playoff,
+                quiz_set.id,
+                format!(
+                    "You are tied for a prize place; take playoff quiz {playoff_quiz_id} before it closes"
+                ),
+            )
+            .await;
+        }
+        self.emit_event(AppEvent::PlayoffStarted(PlayoffStartedEvent {
+            quiz_id: quiz_set.id,
+            playoff_quiz_id,
+            tied_users: tied_users.clone(),
+        }));
+        let playoff = Playoff {
+            playoff_quiz_id,
+            tied_users,
+            created_at: now,
+        };
+        let _ = self.state.playoffs.insert(&quiz_set.id, playoff);
+        true
+    }
+
+    /// 加赛结束后按加赛成绩重排原排行榜中的同分者，名次有变化时生成新的修订版
+    async fn apply_playoff(
+        &mut self,
+        quiz_id: u64,
+        playoff: &Playoff,
+        snapshot: LeaderboardSnapshot,
+    ) -> LeaderboardSnapshot {
+        let playoff_quiz = self.load_quiz(playoff.playoff_quiz_id).await;
+        assert!(
+            self.runtime.system_time() > playoff_quiz.end_time,
+            "Playoff round has not ended yet"
+        );
+        let playoff_entries = self
+            .state
+            .leaderboard
+            .get(&playoff.playoff_quiz_id)
+            .await
+            .expect("Failed to retrieve leaderboard")
+            .unwrap_or_default();
+        let standings = playoff_standings(&snapshot.entries, &playoff_entries);
+        if standings
+            .iter()
+            .map(|entry| entry.user)
+            .eq(snapshot.entries.iter().map(|entry| entry.user))
+        {
+            return snapshot;
+        }
+        let _ = self.state.leaderboard.insert(&quiz_id, standings);
+        self.snapshot_leaderboard(quiz_id).await;
+        self.state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+            .and_then(|mut snapshots| snapshots.pop())
+            .expect("Leaderboard snapshot not found")
+    }
+
+    /// 调用参数中配置的代币应用，把奖励代币从本应用的账户转给最终排名前几的用户
+    fn transfer_token_rewards(&mut self, entries: &[LeaderboardEntry]) -> Vec<PrizePayout> {
+        let parameters = self.runtime.application_parameters();
+        let Some(reward_token) = parameters.reward_token else {
+            return Vec::new();
+        };
+        let mut rewards = Vec::new();
+        for (index, (entry, &amount)) in entries
+            .iter()
+            .zip(&parameters.reward_token_amounts)
+            .enumerate()
+        {
+            if amount == Amount::ZERO {
+                continue;
+            }
+            self.transfer_token(reward_token, entry.user, amount);
+            rewards.push(PrizePayout {
+                rank: index as u32 + 1,
+                user: entry.user,
+                amount,
+            });
+        }
+        rewards
+    }
+
+    /// 调用代币应用，把代币从本应用的账户转给本链上的用户
+    fn transfer_token(&mut self, token: ApplicationId, owner: AccountOwner, amount: Amount) {
+        let operation = FungibleOperation::Transfer {
+            owner: AccountOwner::from(self.runtime.application_id()),
+            amount,
+            target_account: fungible::Account {
+                chain_id: self.runtime.chain_id(),
+                owner,
+            },
+        };
+        self.runtime
+            .call_application(true, token.with_abi::<FungibleTokenAbi>(), &operation);
+    }
+
+    async fn is_leaderboard_frozen(&self, quiz_id: u64) -> bool {
+        self.state
+            .leaderboard_snapshots
+            .contains_key(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+    }
+
+    /// 把当前排行榜追加为新的修订版，返回修订号
+    async fn snapshot_leaderboard(&mut self, quiz_id: u64) -> u32 {
+        let entries = self
+            .state
+            .leaderboard
+            .get(&quiz_id)
+            .await
+            .expect("Failed to retrieve leaderboard")
+            .unwrap_or_default();
+        let mut snapshots = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await
+            .expect("Failed to read leaderboard snapshots")
+            .unwrap_or_default();
+        let revision = snapshots.len() as u32 + 1;
+        let now = self.runtime.system_time();
+        // 新修订版的前三名取代上一修订版颁发的奖杯
+        if let Some(previous) = snapshots.last() {
+            self.revoke_trophies(quiz_id, &previous.entries).await;
+        }
+        self.award_trophies(quiz_id, &entries, now).await;
+        snapshots.push(LeaderboardSnapshot {
+            revision,
+            frozen_at: now,
+            entries,
+        });
+        let _ = self.state.leaderboard_snapshots.insert(&quiz_id, snapshots);
+        self.emit_event(AppEvent::LeaderboardFrozen(LeaderboardFrozenEvent {
+            quiz_id,
+            revision,
+        }));
+        revision
+    }
+
+    /// 把排行榜前三名写入对应用户的档案
+    async fn award_trophies(&mut self, quiz_id: u64, entries: &[LeaderboardEntry], now: Timestamp) {
+        for (index, entry) in entries.iter().take(PODIUM_SIZE).enumerate() {
+            let mut profile = self
+                .state
+                .user_profiles
+                .get(&entry.user)
+                .await
+                .expect("Failed to retrieve user profile")
+                .unwrap_or_default();
+            profile.trophies.push(Trophy {
+                quiz_id,
+                place: index as u32 + 1,
+                score: entry.score,
+                awarded_at: now,
+            });
+            let _ = self.state.user_profiles.insert(&entry.user, profile);
+        }
+    }
+
+    /// 记录用户的每日连续完成天数，并按连续天数的加成累计赛季积分
+    async fn award_season_points(
+        &mut self,
+        user: AccountOwner,
+        score: u32,
+        completed_at: Timestamp,
+    ) {
+        let parameters = self.runtime.application_parameters();
+        let mut profile = self
+            .state
+            .user_profiles
+            .get(&user)
+            .await
+            .expect("Failed to retrieve user profile")
+            .unwrap_or_default();
+        let streak = profile.streak.record_completion(completed_at);
+        let _ = self.state.user_profiles.insert(&user, profile);
+
+        let points = u64::from(score) * u64::from(parameters.streak_bonus(streak)) / 100;
+        let key = (parameters.season_at(completed_at), user);
+        let total = self
+            .state
+            .season_points
+            .get(&key)
+            .await
+            .expect("Failed to retrieve season points")
+            .unwrap_or_default();
+        let _ = self
+            .state
+            .season_points
+            .insert(&key, total.saturating_add(points));
+    }
+
+    /// 按答卷相对该Quiz此前最好成绩的提升累计全局衰减积分
+    async fn award_ranking_points(
+        &mut self,
+        user: AccountOwner,
+        nick_name: String,
+        gain: FixedScore,
+        completed_at: Timestamp,
+    ) {
+        let half_life_days = self.runtime.application_parameters().ranking_half_life_days;
+        let mut ranking = self
+            .state
+            .decayed_ranking
+            .get(&user)
+            .await
+            .expect("Failed to retrieve decayed ranking")
+            .unwrap_or_default();
+        ranking.add(gain, completed_at, half_life_days);
+        ranking.nick_name = nick_name;
+        let _ = self.state.decayed_ranking.insert(&user, ranking);
+    }
+
+    /// 用该Quiz计入排行榜的最新成绩替换之前计入的成绩，更新用户的累计积分
+    async fn update_global_standing(
+        &mut self,
+        quiz_id: u64,
+        user: AccountOwner,
+        nick_name: &str,
+        fixed_score: FixedScore,
+    ) {
+        let previous = self
+            .state
+            .global_contributions
+            .get(&(quiz_id, user))
+            .await
+            .expect("Failed to retrieve global contribution");
+        let mut standing = self
+            .state
+            .global_standings
+            .get(&user)
+            .await
+            .expect("Failed to retrieve global standing")
+            .unwrap_or_default();
+        if previous.is_none() {
+            standing.quizzes_completed += 1;
+        }
+        standing.points = standing
+            .points
+            .saturating_sub(previous.unwrap_or_default())
+            .saturating_add(fixed_score);
+        standing.nick_name = nick_name.to_string();
+        standing.updated_at = self.runtime.system_time();
+        let _ = self.state.global_standings.insert(&user, standing);
+        let _ = self
+            .state
+            .global_contributions
+            .insert(&(quiz_id, user), fixed_score);
+    }
+
+    /// 移除该Quiz计入累计排行榜的成绩，返回各用户被移除的成绩
+    async fn take_global_contributions(&mut self, quiz_id: u64) -> Vec<(AccountOwner, FixedScore)> {
+        let mut contributions = Vec::new();
+        for user in self
+            .state
+            .participants_of(quiz_id)
+            .await
+            .expect("Failed to read quiz participants")
+        {
+            let key = (quiz_id, user);
+            if let Some(points) = self
+                .state
+                .global_contributions
+                .get(&key)
+                .await
+                .expect("Failed to read global contributions")
+            {
+                contributions.push((user, points));
+                self.state
+                    .global_contributions
+                    .remove(&key)
+                    .expect("Failed to remove global contribution");
+            }
+        }
+        contributions
+    }
+
+    /// 从排行榜前三名的档案中移除该Quiz的奖杯
+    async fn revoke_trophies(&mut self, quiz_id: u64, entries: &[LeaderboardEntry]) {
+        for entry in entries.iter().take(PODIUM_SIZE) {
+            let Some(mut profile) = self
+                .state
+                .user_profiles
+                .get(&entry.user)
+                .await
+                .expect("Failed to retrieve user profile")
+            else {
+                continue;
+            };
+            profile.trophies.retain(|trophy| trophy.quiz_id != quiz_id);
+            let _ = self.state.user_profiles.insert(&entry.user, profile);
+        }
+    }
+
+    /// 登记唯一昵称并维护昵称索引，昵称已被他人登记时返回 NicknameTaken
+    async fn set_nickname(&mut self, params: SetNicknameParams) -> QuizResult<()> {
+        let user = self.signer();
+        let nick_name = params.nick_name;
+        if !nick_name.is_empty() {
+            validate_nickname(&nick_name)
+                .unwrap_or_else(|violation| panic!("{}", violation.message));
+            if let Some(owner) = self
+                .state
+                .nicknames
+                .get(&nickname_key(&nick_name))
+                .await
+                .expect("Failed to retrieve nickname")
+            {
+                if owner != user {
+                    return Err(QuizError::NicknameTaken(NicknameTakenError {
+                        nick_name,
+                        owner,
+                    }));
+                }
+            }
+        }
+        let mut profile = self
+            .state
+            .user_profiles
+            .get(&user)
+            .await
+            .expect("Failed to retrieve user profile")
+            .unwrap_or_default();
+        if let Some(previous) = profile.nick_name.take() {
+            self.state
+                .nicknames
+                .remove(&nickname_key(&previous))
+                .expect("Failed to release nickname");
+        }
+        if !nick_name.is_empty() {
+            let _ = self.state.nicknames.insert(&nickname_key(&nick_name), user);
+            profile.nick_name = Some(nick_name);
+        }
+        let _ = self.state.user_profiles.insert(&user, profile);
+        Ok(())
+    }
+
+    fn set_privacy(&mut self, params: SetPrivacyParams) {
+        let user = self.signer();
+        if params.private {
+            self.state
+                .private_users
+                .insert(&user)
+                .expect("Failed to update privacy setting");
+        } else {
+            self.state
+                .private_users
+                .remove(&user)
+                .expect("Failed to update privacy setting");
+        }
+    }
+
+    /// 按Quiz的多次答题策略更新用户在排行榜上的分数，返回名次；
+    /// notify_overtaken 为真时通知排行榜前列中被该用户超过的答题者
+    async fn update_leaderboard(
+        &mut self,
+        quiz_set: &QuizSet,
+        user: AccountOwner,
+        nick_name: String,
+        notify_overtaken: bool,
+    ) -> u32 {
+        let quiz_id = quiz_set.id;
+        let attempts = self
+            .state
+            .user_quiz_attempts(quiz_id, &user)
+            .await
+            .expect("Failed to read attempts");
+        let fixed_score = policy_score(&attempts, quiz_set.attempt_policy).unwrap_or_default();
+        let score = self
+            .runtime
+            .application_parameters()
+            .display_score(fixed_score);
+        let (time_taken, completed_at) = policy_attempt(&attempts, quiz_set.attempt_policy)
+            .map(|attempt| (attempt.time_taken, attempt.completed_at))
+            .unwrap_or_default();
+        // 练习卷和隐藏结果的Quiz不计入累计排行榜
+        if !quiz_set.practice && !quiz_set.hide_results {
+            self.update_global_standing(quiz_id, user, &nick_name, fixed_score)
+                .await;
+        }
+
+        let mut entries = self
+            .state
+            .leaderboard
+            .get(&quiz_id)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+
+        // 查找用户是否已有条目
+        let existing_index = entries.iter().position(|entry| entry.user == user);
+        let previous_index = existing_index.unwrap_or(entries.len());
 
         if let Some(index) = existing_index {
             // 更新现有条目
             entries[index].score = score;
+            entries[index].nick_name = nick_name;
+            entries[index].time_taken = time_taken;
+            entries[index].completed_at = completed_at;
         } else {
             // 添加新条目
             entries.push(LeaderboardEntry {
                 user,
+                nick_name,
                 score,
-                time_taken: 0, // 这里可以从attempt中获取time_taken
+                time_taken,
+                completed_at,
             });
         }
 
-        // 按分数排序（从高到低）
-        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        // 按分数、用时和提交时间排序
+        entries.sort_by(leaderboard_order);
+        let rank = entries
+            .iter()
+            .position(|entry| entry.user == user)
+            .expect("Leaderboard entry was just inserted") as u32
+            + 1;
+
+        // 其他条目的相对顺序不变，被超过的正是新旧名次之间的答题者，只通知排行榜前列的
+        let overtaken: Vec<AccountOwner> = if notify_overtaken {
+            let new_index = rank as usize - 1;
+            let end = previous_index.min(OVERTAKEN_NOTIFY_RANKS);
+            entries
+                .get(new_index + 1..=end)
+                .unwrap_or_default()
+                .iter()
+                .map(|entry| entry.user)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for other in overtaken {
+            self.notify(
+                other,
+                NotificationKind::Overtaken,
+                quiz_id,
+                format!(
+                    "You were overtaken on the leaderboard of \"{}\"",
+                    quiz_set.title
+                ),
+            )
+            .await;
+        }
 
         // 保存更新后的排行榜
         let _ = self.state.leaderboard.insert(&quiz_id, entries);
+        rank
+    }
+}
+
+/// 把Quiz按ID升序登记到倒排索引（搜索词、标签或分类）的一个键下
+async fn index_quiz(index: &mut MapView<String, Vec<u64>>, key: &str, quiz_id: u64) {
+    let mut quiz_ids = index
+        .get(key)
+        .await
+        .expect("Failed to retrieve quiz index")
+        .unwrap_or_default();
+    if let Err(position) = quiz_ids.binary_search(&quiz_id) {
+        quiz_ids.insert(position, quiz_id);
+        let _ = index.insert(key, quiz_ids);
+    }
+}
+
+/// 从倒排索引的一个键下移除Quiz，列表为空时删除该键
+async fn unindex_quiz(index: &mut MapView<String, Vec<u64>>, key: &str, quiz_id: u64) {
+    let Some(mut quiz_ids) = index.get(key).await.expect("Failed to retrieve quiz index") else {
+        return;
+    };
+    quiz_ids.retain(|id| *id != quiz_id);
+    if quiz_ids.is_empty() {
+        index
+            .remove(key)
+            .expect("Failed to remove quiz index entry");
+    } else {
+        let _ = index.insert(key, quiz_ids);
+    }
+}
+
+/// 把按答题者看到的顺序提交的答案恢复为原始问题顺序
+fn restore_question_order(answers: Vec<Answer>, question_order: &[u32]) -> Vec<Answer> {
+    let mut ordered: Vec<(u32, Answer)> = question_order.iter().copied().zip(answers).collect();
+    ordered.sort_by_key(|(question_id, _)| *question_id);
+    ordered.into_iter().map(|(_, answer)| answer).collect()
+}
+
+/// Quiz未开启“不会”选项时拒绝 DontKnow 答案
+fn check_dont_know(quiz_set: &QuizSet, answers: &[Answer]) {
+    assert!(
+        quiz_set.allow_dont_know
+            || !answers
+                .iter()
+                .any(|answer| matches!(answer, Answer::DontKnow(_))),
+        "This quiz does not offer an \"I don't know\" answer"
+    );
+}
+
+/// 把按问题ID提交的答案排回原始问题顺序，问题须属于本次抽到的问题且不能重复，未提交的问题为空答案
+fn answers_by_question_id(answers: Vec<QuestionAnswer>, question_order: &[u32]) -> Vec<Answer> {
+    let mut question_ids = question_order.to_vec();
+    question_ids.sort_unstable();
+    let mut ordered: Vec<Option<Answer>> = vec![None; question_ids.len()];
+    for QuestionAnswer {
+        question_id,
+        answer,
+    } in answers
+    {
+        let index = question_ids
+            .binary_search(&question_id)
+            .unwrap_or_else(|_| panic!("Question {question_id} is not part of this attempt"));
+        assert!(
+            ordered[index].replace(answer).is_none(),
+            "Duplicate answer for question {question_id}"
+        );
     }
+    ordered
+        .into_iter()
+        .map(|answer| answer.unwrap_or_else(Answer::blank))
+        .collect()
+}
+
+/// 构造 QuizLocked 错误
+fn quiz_locked(quiz_id: u64, reason: &str) -> QuizError {
+    QuizError::QuizLocked(QuizLockedError {
+        quiz_id,
+        reason: reason.to_string(),
+    })
 }
@@ -3,61 +3,1690 @@
 
 /*! ABI of the Quiz Application */
 
-use async_graphql::{InputObject, SimpleObject};
+use std::fmt;
+
+use async_graphql::{Enum, InputObject, OneofObject, OutputType, SimpleObject, Union};
 use linera_sdk::graphql::GraphQLMutationRoot;
-use linera_sdk::linera_base_types::{ContractAbi, ServiceAbi};
+use linera_sdk::linera_base_types::{
+    AccountOwner, Amount, ApplicationId, BcsHashable, ChainId, ContractAbi, CryptoHash,
+    DataBlobHash, ServiceAbi, Timestamp,
+};
 use serde::{Deserialize, Serialize};
 
-pub mod state;
+use crate::logging::LogLevel;
+use crate::scoring::FixedScore;
+
+#[cfg(feature = "client")]
+pub mod client;
+pub mod logging;
+pub mod packing;
+pub mod scoring;
+pub mod similarity;
+pub mod state;
+pub mod validation;
+pub mod views;
+
+pub struct QuizAbi;
+
+/// 毫秒时间戳转换为 Timestamp（微秒），供以毫秒计时的客户端使用
+pub fn timestamp_from_millis(millis: u64) -> Timestamp {
+    Timestamp::from(millis.saturating_mul(1000))
+}
+
+/// Timestamp 对应的毫秒时间戳
+pub fn timestamp_millis(time: Timestamp) -> u64 {
+    time.micros() / 1000
+}
+
+/// 创建Quiz集合的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct CreateQuizParams {
+    pub title: String,
+    pub description: String,
+    pub questions: Vec<QuestionParams>,
+    pub time_limit: u64, // 秒，0 表示不限时
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// 创建者显示名，身份以签名账户为准
+    pub nick_name: String,
+    /// 练习模式：任何时候都可以预览评分
+    #[graphql(default)]
+    pub practice: bool,
+    /// 在公布前隐藏所有分数和排行榜
+    #[graphql(default)]
+    pub hide_results: bool,
+    /// 结束后自动公布结果的延迟（秒），为空时需创建者手动公布
+    pub reveal_delay: Option<u64>,
+    /// 考试模式：答卷需经该监考账户确认后才生效
+    pub proctor: Option<AccountOwner>,
+    /// 创建者权限的持有者（账户或多所有者链），为空时仅限创建账户
+    pub owner: Option<Principal>,
+    /// 自定义元数据（主题色、封面图片哈希、外部ID等）
+    #[graphql(default)]
+    pub metadata: Vec<MetadataEntry>,
+    /// 开始方式：按开始时间自动开始，或由创建者手动开始
+    #[graphql(default)]
+    pub start_mode: QuizStartMode,
+    /// 评分方式，默认完全匹配
+    #[graphql(default)]
+    pub scoring: ScoringStrategy,
+    /// 允许以 `{ dontKnow: true }` 明确表示不会，记为跳过且不扣分
+    #[graphql(default)]
+    pub allow_dont_know: bool,
+    /// 所属组织，创建者须为该组织成员
+    pub org: Option<String>,
+    /// 为 true 时只有组织成员可以查看和参加，为空时使用组织的默认可见性
+    pub members_only: Option<bool>,
+    /// 为 true 时每位答题者的问题顺序随机打乱
+    #[graphql(default)]
+    pub shuffle_questions: bool,
+    /// 题库模式：每次答题从全部问题中随机抽取的题数，须先 StartAttempt
+    pub questions_per_attempt: Option<u32>,
+    /// 内容封存：创建时只提交问题的承诺哈希（此时 questions 须为空），开始后再公开
+    pub embargo: Option<ContentEmbargo>,
+    /// 答案校验和：`state::answer_key_commitment(questions, salt)` 的结果，
+    /// salt 须为创建者保密的高熵随机串，Quiz结束后用 RevealAnswerKey 公开
+    pub answer_key_hash: Option<CryptoHash>,
+    /// 每位答题者最多可以答题的次数
+    #[graphql(default = 1)]
+    pub max_attempts: u32,
+    /// 多次答题时计入排行榜的成绩
+    #[graphql(default)]
+    pub attempt_policy: AttemptPolicy,
+    /// 身份认证应用：设置后答题者须有已验证身份，且同一身份只能用一个账户参加
+    pub attestation_app: Option<ApplicationId>,
+    /// 奖池：创建时从创建者账户转入应用账户托管的原生代币
+    pub prize_pool: Option<Amount>,
+    /// 奖池按最终排名分配的百分比，如 [50, 30, 20]，合计须为100
+    #[graphql(default)]
+    pub prize_splits: Vec<u32>,
+    /// 参赛费：答题者首次开始答题时转入应用账户托管，发奖时计入奖池，取消时退还
+    pub entry_fee: Option<Amount>,
+    /// 主分类，为空表示未分类
+    #[graphql(default)]
+    pub category: String,
+    /// 分类标签
+    #[graphql(default)]
+    pub tags: Vec<String>,
+    /// 及格分：展示分数达到该值的答卷视为通过，可查询证书并获得证书NFT
+    pub pass_score: Option<u32>,
+    /// 分批开放：开始后按确定性分组逐批允许用户答题
+    pub soft_launch: Option<SoftLaunch>,
+}
+
+/// 分批开放的设置，每个用户按Quiz和账户的哈希确定性地分到一个批次
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "SoftLaunchInput")]
+pub struct SoftLaunch {
+    /// 每批开放的用户百分比
+    pub percent_per_wave: u32,
+    /// 相邻两批的间隔（秒）
+    pub wave_secs: u64,
+}
+
+/// 多次答题时计入排行榜的成绩
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AttemptPolicy {
+    /// 最高分
+    #[default]
+    Best,
+    /// 最近一次答题的分数
+    Latest,
+    /// 所有答题的平均分
+    Average,
+}
+
+/// 封存问题内容的承诺
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
+pub struct ContentEmbargo {
+    /// `state::content_commitment(questions, salt)` 的结果
+    pub commitment: CryptoHash,
+    /// 封存的问题数，公开前用于显示占位问题
+    pub question_count: u32,
+}
+
+/// 修改Quiz的参数，未提供的字段保持不变
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct UpdateQuizParams {
+    pub quiz_id: u64,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub questions: Option<Vec<QuestionParams>>,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub scoring: Option<ScoringStrategy>,
+    /// 新的答案校验和；修改问题时未提供则清除原校验和
+    pub answer_key_hash: Option<CryptoHash>,
+}
+
+/// Quiz所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuizStatus {
+    /// 手动开始方式，等待创建者开始
+    Draft,
+    /// 定时开始，尚未到开始时间
+    Upcoming,
+    /// 处于答题时间内
+    Active,
+    /// 已过结束时间
+    Ended,
+    /// 已取消
+    Cancelled,
+}
+
+/// quiz_sets 的组合筛选条件，各条件同时满足
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct QuizFilter {
+    pub status: Option<QuizStatus>,
+    pub mode: Option<QuizStartMode>,
+    pub creator: Option<AccountOwner>,
+    /// 标题包含的子串，不区分大小写
+    pub title_contains: Option<String>,
+    /// 创建时间不早于该时间
+    pub created_after: Option<Timestamp>,
+    /// 创建时间早于该时间
+    pub created_before: Option<Timestamp>,
+    /// 最少的不同答题者数
+    pub min_participants: Option<u64>,
+}
+
+/// Quiz开始方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuizStartMode {
+    /// 到达开始时间后自动开始
+    #[default]
+    Scheduled,
+    /// 创建者执行 StartQuiz 后开始
+    Manual,
+}
+
+/// 每个问题的评分方式，GraphQL中以JSON标量表示，例如 `"partialCredit"`、`{"negativeMarking": {"penalty": 500}}`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScoringStrategy {
+    /// 所选答案与正确选项完全一致才得分
+    #[default]
+    ExactMatch,
+    /// 多选题按选对的选项数减去选错的选项数给部分分，不低于0
+    PartialCredit,
+    /// 完全一致才得分，答错扣除 penalty（千分之一分），未作答不扣分
+    // 客户端解析响应时会把键名转成 snake_case
+    #[serde(alias = "negative_marking")]
+    NegativeMarking { penalty: u32 },
+}
+
+async_graphql::scalar!(ScoringStrategy);
+
+/// Quiz自定义元数据条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "MetadataEntryInput")]
+pub struct MetadataEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// 替换Quiz自定义元数据的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetQuizMetadataParams {
+    pub quiz_id: u64,
+    pub metadata: Vec<MetadataEntry>,
+}
+
+/// 在创建者的所有Quiz上重命名标签的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct RenameTagParams {
+    pub from: String,
+    pub to: String,
+}
+
+/// 在创建者的所有Quiz上把多个标签合并为一个的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct MergeTagsParams {
+    pub sources: Vec<String>,
+    pub target: String,
+}
+
+/// 为创建者的多个Quiz添加同一标签的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct ApplyTagParams {
+    pub tag: String,
+    pub quiz_ids: Vec<u64>,
+}
+
+/// 注册组织的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct RegisterOrgParams {
+    /// 组织命名空间标识，只能包含小写字母、数字和连字符
+    pub slug: String,
+    pub name: String,
+    #[graphql(default)]
+    pub branding: Vec<MetadataEntry>,
+}
+
+/// 添加或移除组织成员的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct OrgMemberParams {
+    pub org: String,
+    pub member: AccountOwner,
+}
+
+/// 替换组织品牌元数据的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetOrgBrandingParams {
+    pub org: String,
+    pub branding: Vec<MetadataEntry>,
+}
+
+/// 组织内的角色，权限依次递增
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrgRole {
+    /// 可以在组织内创建Quiz，并查看和参加仅成员可见的Quiz
+    Member,
+    /// 还可以管理成员、品牌和设置，删除或取消组织内的Quiz，并查看组织统计
+    Admin,
+    /// 还可以任免组织管理员
+    Owner,
+}
+
+/// 组织对其内部Quiz的默认值和限制
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject,
+)]
+#[graphql(input_name = "OrgSettingsInput")]
+pub struct OrgSettings {
+    /// 创建时未指定 members_only 的Quiz是否默认仅成员可见
+    pub default_members_only: bool,
+    /// 每个Quiz的最大问题数
+    pub max_questions: Option<u32>,
+    /// 答题时限上限（秒），设置后组织内的Quiz不能不限时
+    pub max_time_limit: Option<u64>,
+}
+
+/// 替换组织设置的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetOrgSettingsParams {
+    pub org: String,
+    pub settings: OrgSettings,
+}
+
+/// 问题参数
+#[derive(Debug, Serialize, Deserialize, Clone, SimpleObject, InputObject)]
+#[graphql(input_name = "QuestionParamsInput")]
+pub struct QuestionParams {
+    pub text: String,
+    pub options: Vec<String>,
+    pub correct_options: Vec<u32>,
+    pub points: u32,
+    /// 问题类型，默认选择题
+    #[graphql(default)]
+    pub kind: QuestionKind,
+    /// 答案解析，答题者提交后或测验结束后可见
+    pub explanation: Option<String>,
+    /// 听力、视听理解题附带的音视频
+    pub media: Option<QuestionMedia>,
+    /// 读屏软件朗读的提示，如公式或图表的文字说明
+    pub screen_reader_hint: Option<String>,
+}
+
+/// 问题附带媒体的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+/// 问题附带的音视频，内容以数据 blob 发布，创建Quiz时须已存在
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject, InputObject)]
+#[graphql(input_name = "QuestionMediaInput")]
+pub struct QuestionMedia {
+    pub kind: MediaKind,
+    /// 媒体内容所在的数据 blob
+    pub blob: DataBlobHash,
+    /// 播放时长（秒），不得超过应用参数中该类型的上限
+    pub duration_secs: u32,
+    /// 文字稿所在的数据 blob
+    pub transcript: Option<DataBlobHash>,
+    /// 媒体的替代文本，供读屏软件朗读
+    pub alt_text: Option<String>,
+}
+
+/// 问题类型及判分所需的数据，GraphQL中以JSON标量表示，例如 `"trueFalse"`、`{"fillInBlank": {"answers": ["Paris"]}}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuestionKind {
+    /// 选择题，按 correct_options 判分
+    #[default]
+    MultipleChoice,
+    /// 判断题，选项固定为 True / False，correct_options 为其中之一
+    TrueFalse,
+    /// 填空题，与任一可接受答案相同（忽略大小写和首尾空白）即为正确
+    // 客户端解析响应时会把键名转成 snake_case
+    #[serde(alias = "fill_in_blank")]
+    FillInBlank { answers: Vec<String> },
+    /// 数值题，与 answer 相差不超过 tolerance 即为正确，均为十进制字符串
+    Numeric { answer: String, tolerance: String },
+    /// 问答题，以文本作答，由创建者按评分标准人工评分，评分前记0分
+    Essay { rubric: Vec<RubricCriterion> },
+}
+
+/// 问答题的一条评分标准
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RubricCriterion {
+    pub name: String,
+    /// 该标准在问题分值中所占的权重
+    pub weight: u32,
+    /// 各等级的描述，从最低（0分）到最高（满分）
+    pub levels: Vec<String>,
+}
+
+async_graphql::scalar!(QuestionKind);
+
+impl QuestionKind {
+    /// 不含答案的问题类型
+    pub fn question_type(&self) -> QuestionType {
+        match self {
+            QuestionKind::MultipleChoice => QuestionType::MultipleChoice,
+            QuestionKind::TrueFalse => QuestionType::TrueFalse,
+            QuestionKind::FillInBlank { .. } => QuestionType::FillInBlank,
+            QuestionKind::Numeric { .. } => QuestionType::Numeric,
+            QuestionKind::Essay { .. } => QuestionType::Essay,
+        }
+    }
+}
+
+/// 向答题者展示的问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuestionType {
+    MultipleChoice,
+    TrueFalse,
+    FillInBlank,
+    Numeric,
+    Essay,
+}
+
+/// 一道题的答案：选择题和判断题为选项索引，填空题为文本，数值题为十进制字符串；
+/// Quiz允许时可以用 DontKnow（值固定为 true）表示不会，适用于任何问题类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, OneofObject)]
+#[serde(rename_all = "camelCase")]
+pub enum Answer {
+    Choices(Vec<u32>),
+    Text(String),
+    Number(String),
+    DontKnow(bool),
+}
+
+impl Answer {
+    /// 未作答的空答案
+    pub fn blank() -> Self {
+        Answer::Choices(Vec::new())
+    }
+
+    /// 是否为空答案
+    pub fn is_blank(&self) -> bool {
+        match self {
+            Answer::Choices(choices) => choices.is_empty(),
+            Answer::Text(text) | Answer::Number(text) => text.trim().is_empty(),
+            Answer::DontKnow(_) => false,
+        }
+    }
+
+    /// 是否跳过了该问题：空答案或明确表示不会
+    pub fn is_skip(&self) -> bool {
+        self.is_blank() || matches!(self, Answer::DontKnow(_))
+    }
+}
+
+/// 答案视图，与 Answer 一致只有一个字段非空
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AnswerView {
+    pub choices: Option<Vec<u32>>,
+    pub text: Option<String>,
+    pub number: Option<String>,
+    pub dont_know: Option<bool>,
+}
+
+/// 答题会话中已保存的答案
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SavedAnswer {
+    pub question_id: u32,
+    pub answer: AnswerView,
+}
+
+impl From<Answer> for AnswerView {
+    fn from(answer: Answer) -> Self {
+        let mut view = AnswerView {
+            choices: None,
+            text: None,
+            number: None,
+            dont_know: None,
+        };
+        match answer {
+            Answer::Choices(choices) => view.choices = Some(choices),
+            Answer::Text(text) => view.text = Some(text),
+            Answer::Number(number) => view.number = Some(number),
+            Answer::DontKnow(dont_know) => view.dont_know = Some(dont_know),
+        }
+        view
+    }
+}
+
+/// 按问题ID提交的一道题的答案
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
+pub struct QuestionAnswer {
+    pub question_id: u32, // 原始问题ID
+    pub answer: Answer,
+}
+
+/// 提交答案的参数
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
+pub struct SubmitAnswersParams {
+    pub quiz_id: u64,
+    /// 按问题ID提交的答案，未提交的问题按空答案计分
+    #[graphql(default)]
+    #[serde(default)]
+    pub answers: Vec<QuestionAnswer>,
+    /// 兼容旧客户端：按答题者看到的问题顺序的答案，须覆盖全部问题，不能与 answers 同时使用
+    #[graphql(default)]
+    #[serde(default)]
+    pub positional_answers: Vec<Answer>,
+    /// 大型Quiz的紧凑编码答案（见 packing 模块），不能与其他答案字段同时使用
+    #[graphql(default)]
+    #[serde(default)]
+    pub packed_answers: Vec<u8>,
+    /// 答题者显示名，身份以签名账户为准
+    pub nick_name: String,
+}
+
+/// 公开封存问题内容的参数，须与创建时的承诺哈希一致
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct RevealContentParams {
+    pub quiz_id: u64,
+    pub questions: Vec<QuestionParams>,
+    pub salt: String,
+}
+
+/// 公开答案校验和的盐的参数，须与创建时提交的校验和一致
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct RevealAnswerKeyParams {
+    pub quiz_id: u64,
+    pub salt: String,
+}
+
+/// 公布结果的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct RevealResultsParams {
+    pub quiz_id: u64,
+}
+
+/// 监考人确认答卷的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct ConfirmAttemptParams {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    /// 为 false 时驳回该答卷
+    pub approved: bool,
+}
+
+/// 为指定用户额外增加一次答题机会的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct GrantExtraAttemptParams {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    /// 记录在应用事件中的原因，如答题中途断电
+    pub reason: String,
+}
+
+/// 发布创建者公告的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct PostAnnouncementParams {
+    pub quiz_id: u64,
+    pub text: String,
+}
+
+/// 为尚未开始的Quiz生成审阅预览令牌的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct CreatePreviewTokenParams {
+    pub quiz_id: u64,
+    /// 审阅者标签，如审稿人姓名，同一区块内同一Quiz的标签不能重复
+    pub label: String,
+    /// 令牌有效时长（秒）
+    pub valid_secs: u64,
+}
+
+/// 预览令牌及其有效期，只返回给创建者
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PreviewTokenView {
+    pub token: CryptoHash,
+    pub quiz_id: u64,
+    pub label: String,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+/// 创建者向Quiz参与者发布的公告，如题目更正或时间调整
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct CreatorAnnouncement {
+    pub text: String,
+    pub posted_at: Timestamp,
+}
+
+/// 为指定用户延长答题时限的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct GrantAccommodationParams {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    /// 答题时限的倍数（百分比），如 150 表示 1.5 倍，100 表示取消延长
+    pub multiplier: u32,
+}
+
+/// 设置答题私人笔记的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetAttemptNoteParams {
+    pub quiz_id: u64,
+    /// 笔记内容，为空时删除笔记
+    pub note: String,
+}
+
+/// 收藏或取消收藏问题的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetBookmarkParams {
+    pub quiz_id: u64,
+    pub question_id: u32,
+    /// 为 false 时取消收藏
+    pub bookmarked: bool,
+}
+
+/// 把通知标记为已读的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct MarkNotificationsReadParams {
+    /// 要标记的通知ID，为空时标记签名者的全部通知
+    #[graphql(default)]
+    pub ids: Vec<u64>,
+}
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum NotificationKind {
+    /// 在排行榜上被其他答题者超过
+    Overtaken,
+    /// 浏览过的Quiz已由创建者开始
+    QuizStarting,
+    /// 获得奖池奖金或奖励代币
+    PrizeAwarded,
+    /// 浏览过或参加过的Quiz有创建者公告
+    Announcement,
+    /// 参加的Quiz奖项名次同分同时，需要参加加赛
+    Playoff,
+}
+
+/// 用户通知视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct NotificationView {
+    pub id: u64,
+    pub kind: NotificationKind,
+    pub quiz_id: u64,
+    pub message: String,
+    pub created_at: Timestamp,
+    pub read: bool,
+}
+
+/// 问题难度，按历史答卷的答对率划分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuestionDifficulty {
+    /// 答对率不低于70%
+    Easy,
+    /// 答对率在40%到70%之间
+    Medium,
+    /// 答对率低于40%
+    Hard,
+}
+
+impl QuestionDifficulty {
+    /// 答对次数和作答次数对应的难度
+    pub fn from_results(correct: u64, total: u64) -> Self {
+        let percent = correct * 100 / total.max(1);
+        if percent >= 70 {
+            QuestionDifficulty::Easy
+        } else if percent >= 40 {
+            QuestionDifficulty::Medium
+        } else {
+            QuestionDifficulty::Hard
+        }
+    }
+}
+
+/// 从创建者往期Quiz中抽题组成练习卷的参数，练习卷归签名者所有
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct BuildPracticeExamParams {
+    /// 往期Quiz的创建者
+    pub creator: AccountOwner,
+    /// 只从带有其中任一标签的Quiz中抽题，为空时不限
+    #[graphql(default)]
+    pub tags: Vec<String>,
+    /// 只抽取该难度的问题，为空时不限
+    pub difficulty: Option<QuestionDifficulty>,
+    /// 抽取的问题数
+    pub question_count: u32,
+    pub title: String,
+    /// 组卷者显示名
+    pub nick_name: String,
+    #[graphql(default)]
+    pub time_limit: u64, // 秒，0 表示不限时
+    /// 练习卷的结束时间，创建后立即开始
+    pub end_time: Timestamp,
+}
+
+/// 练习卷的候选问题
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PracticePoolQuestion {
+    pub quiz_id: u64,
+    pub question_id: u32,
+    pub text: String,
+    /// 尚无答卷时为空
+    pub difficulty: Option<QuestionDifficulty>,
+}
+
+/// 登记个人昵称的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetNicknameParams {
+    /// 昵称（不区分大小写地全局唯一），为空时释放已登记的昵称
+    pub nick_name: String,
+}
+
+/// 设置答题隐私的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SetPrivacyParams {
+    /// 为 true 时在公开排行榜中匿名显示，且他人无法查询其答题记录
+    pub private: bool,
+}
+
+/// 开始答题会话的参数，题库模式下在此时抽题
+#[derive(Debug, Clone, Serialize, Deserialize, InputObject)]
+pub struct StartAttemptParams {
+    pub quiz_id: u64,
+    /// 答题者显示名，身份以签名账户为准
+    pub nick_name: String,
+}
+
+/// 逐题保存答案的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct SaveAnswerParams {
+    pub quiz_id: u64,
+    pub question_id: u32,
+    pub answer: Answer,
+    /// 答题者显示名，身份以签名账户为准
+    pub nick_name: String,
+}
+
+/// 答题会话心跳的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct HeartbeatParams {
+    pub quiz_id: u64,
+}
+
+/// 按已保存的答案交卷的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct FinalizeAttemptParams {
+    pub quiz_id: u64,
+}
+
+/// 清理超时未活动答题会话的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct ExpireAbandonedAttemptsParams {
+    pub quiz_id: u64,
+}
+
+/// 对问题发起申诉投票的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct AppealQuestionParams {
+    pub quiz_id: u64,
+    pub question_id: u32,
+}
+
+/// 创建者按评分标准给问答题评分的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct GradeEssayParams {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub attempt_no: u32,
+    pub question_id: u32,
+    /// 每条评分标准选中的等级，按评分标准顺序，0为最低
+    pub levels: Vec<u32>,
+}
+
+/// 创建者处理重新评分案件的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct ResolveRegradeCaseParams {
+    pub quiz_id: u64,
+    pub question_id: u32,
+    /// 为 true 时认定问题有误
+    pub upheld: bool,
+}
+
+/// 权限主体：单个账户，或一条链（该链的任一所有者均可代表其操作）
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, OneofObject)]
+#[serde(rename_all = "camelCase")]
+pub enum Principal {
+    Owner(AccountOwner),
+    Chain(ChainId),
+}
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Principal::Owner(owner) => write!(f, "owner:{owner}"),
+            Principal::Chain(chain_id) => write!(f, "chain:{chain_id}"),
+        }
+    }
+}
+
+/// 需要多名管理员确认才能执行的破坏性管理操作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, OneofObject)]
+#[serde(rename_all = "camelCase")]
+pub enum AdminAction {
+    /// 删除指定Quiz
+    RemoveQuiz(u64),
+    /// 移除管理员
+    RemoveAdmin(Principal),
+}
+
+impl fmt::Display for AdminAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminAction::RemoveQuiz(quiz_id) => write!(f, "RemoveQuiz({quiz_id})"),
+            AdminAction::RemoveAdmin(admin) => write!(f, "RemoveAdmin({admin})"),
+        }
+    }
+}
+
+/// 添加平台禁排时段的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct AddBlackoutWindowParams {
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// 禁排原因，如系统维护或重大活动
+    pub reason: String,
+}
+
+/// 平台禁排时段，[start_time, end_time) 内不能安排Quiz开始
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlackoutWindow {
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub reason: String,
+}
+
+impl BlackoutWindow {
+    /// 时间点是否落在禁排时段内
+    pub fn contains(&self, time: Timestamp) -> bool {
+        self.start_time <= time && time < self.end_time
+    }
+}
+
+/// 平台禁排时段视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct BlackoutWindowView {
+    pub id: u64,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub reason: String,
+}
+
+/// 管理操作提案视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct AdminProposalView {
+    pub id: u64,
+    pub action: String,
+    pub confirmations: Vec<String>,
+    pub executed: bool,
+    pub created_at: Timestamp,
+}
+
+/// 排行榜条目，用时和完成时间取自按多次答题策略计入成绩的答卷
+#[derive(Debug, Serialize, Deserialize, SimpleObject, Clone)]
+pub struct LeaderboardEntry {
+    pub user: AccountOwner,
+    pub nick_name: String,
+    pub score: u32,
+    pub time_taken: u64, // 毫秒
+    pub completed_at: Timestamp,
+}
+
+/// 应用支持的操作
+#[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
+pub enum Operation {
+    /// 创建新的Quiz集合
+    CreateQuiz(Box<CreateQuizParams>),
+    /// 手动开始Quiz（仅创建者，且Quiz须为手动开始方式）
+    StartQuiz { quiz_id: u64 },
+    /// 在开始前删除Quiz（创建者或所属组织的管理员）
+    DeleteQuiz { quiz_id: u64 },
+    /// 在开始后取消Quiz，保留已取消的记录并清理答题数据（创建者或所属组织的管理员）
+    CancelQuiz { quiz_id: u64 },
+    /// 测验结束后冻结排行榜，保存原始榜单（任何人都可调用）
+    FreezeLeaderboard { quiz_id: u64 },
+    /// 测验结束后最终确定结果并锁定Quiz（任何人都可调用）
+    FinalizeQuiz { quiz_id: u64 },
+    /// 在答题次数上限之外为指定用户增加一次答题机会（仅创建者）
+    GrantExtraAttempt(GrantExtraAttemptParams),
+    /// 按倍数延长指定用户在该Quiz的答题时限（仅创建者）
+    GrantAccommodation(GrantAccommodationParams),
+    /// 发布Quiz公告并通知部分参加过或浏览过该Quiz的用户（仅创建者）
+    PostAnnouncement(PostAnnouncementParams),
+    /// 为尚未开始的Quiz生成限时预览令牌，供审阅者查看和试答（仅创建者）
+    CreatePreviewToken(CreatePreviewTokenParams),
+    /// 撤销预览令牌（仅创建者）
+    RevokePreviewToken { token: CryptoHash },
+    /// 归档并删除签名者创建的、在给定时间前结束且已最终确定或已取消的Quiz；
+    /// 每次最多处理一批，剩余的可再次调用
+    PruneQuizzes { ended_before: Timestamp },
+    /// 设置签名者的Quiz保留天数，为空时取消保留策略
+    SetRetentionPolicy { retention_days: Option<u32> },
+    /// 按创建者的保留策略归档并删除其过期的Quiz（任何人都可调用）
+    ApplyRetentionPolicy { creator: AccountOwner },
+    /// 结果最终确定后按奖池比例向最终排名靠前的用户发放奖金（任何人都可调用）
+    DistributePrizes { quiz_id: u64 },
+    /// 赞助者在已有奖池上追加代币奖金，从签名者的代币账户转入本应用账户托管
+    AddTokenPrize(AddTokenPrizeParams),
+    /// 获奖者在领取期限内领取自己的奖池奖金
+    ClaimPrize { quiz_id: u64 },
+    /// 把领取期限已过的奖金清扫给金库或出资者（任何人都可调用）
+    SweepUnclaimedPrizes { quiz_id: u64 },
+    /// 记录一次Quiz浏览，已认证的浏览者同时计入不同浏览者数
+    RecordQuizView { quiz_id: u64 },
+    /// 在开始前且无人答题时修改Quiz（仅创建者）
+    UpdateQuiz(UpdateQuizParams),
+    /// 提交Quiz答案
+    SubmitAnswers(SubmitAnswersParams),
+    /// 设置答题隐私
+    SetPrivacy(SetPrivacyParams),
+    /// 在档案中登记唯一昵称
+    SetNickname(SetNicknameParams),
+    /// 从创建者往期Quiz中抽题，创建归签名者所有的练习卷
+    BuildPracticeExam(BuildPracticeExamParams),
+    /// 为自己的答题记录添加私人笔记
+    SetAttemptNote(SetAttemptNoteParams),
+    /// 复习时收藏或取消收藏问题
+    SetBookmark(SetBookmarkParams),
+    /// 把签名者的通知标记为已读
+    MarkNotificationsRead(MarkNotificationsReadParams),
+    /// 测验开始后公开封存的问题内容（仅创建者）
+    RevealContent(RevealContentParams),
+    /// 测验结束后公开答案校验和的盐，合约校验其与答案相符（仅创建者）
+    RevealAnswerKey(RevealAnswerKeyParams),
+    /// 公布隐藏的测验结果（仅创建者）
+    RevealResults(RevealResultsParams),
+    /// 监考人确认或驳回待确认的答卷
+    ConfirmAttempt(ConfirmAttemptParams),
+    /// 开始答题会话并记录开始时间，题库模式下为答题者抽取问题
+    StartAttempt(StartAttemptParams),
+    /// 从自己的链向Quiz所在的链发起答题会话，开始时间以所在链处理消息的时间为准
+    RemoteStartAttempt {
+        host_chain_id: ChainId,
+        params: StartAttemptParams,
+    },
+    /// 从自己的链向Quiz所在的链提交答案，由所在链评分并更新排行榜
+    RemoteSubmitAnswers {
+        host_chain_id: ChainId,
+        params: SubmitAnswersParams,
+    },
+    /// 让本链订阅Quiz所在的链的新Quiz和最终结果公告
+    Subscribe { host_chain_id: ChainId },
+    /// 取消本链的订阅
+    Unsubscribe { host_chain_id: ChainId },
+    /// 逐题保存答案（开始或继续答题会话）
+    SaveAnswer(SaveAnswerParams),
+    /// 答题会话心跳，刷新最后活动时间
+    Heartbeat(HeartbeatParams),
+    /// 按答题会话中逐题保存的答案交卷并评分
+    FinalizeAttempt(FinalizeAttemptParams),
+    /// 自动提交超时未活动的答题会话（任何人都可调用）
+    ExpireAbandonedAttempts(ExpireAbandonedAttemptsParams),
+    /// 替换Quiz自定义元数据（仅创建者）
+    SetQuizMetadata(SetQuizMetadataParams),
+    /// 在签名者创建的所有Quiz上重命名标签
+    RenameTag(RenameTagParams),
+    /// 在签名者创建的所有Quiz上合并标签
+    MergeTags(MergeTagsParams),
+    /// 为签名者创建的多个Quiz添加标签
+    ApplyTag(ApplyTagParams),
+    /// 测验结束后，参与者投票认为某个问题有误
+    AppealQuestion(AppealQuestionParams),
+    /// 创建者处理重新评分案件
+    ResolveRegradeCase(ResolveRegradeCaseParams),
+    /// 创建者按评分标准给问答题评分，可重复评分
+    GradeEssay(GradeEssayParams),
+    /// 注册组织，签名者成为组织所有者
+    RegisterOrg(RegisterOrgParams),
+    /// 添加组织成员（组织管理员）
+    AddOrgMember(OrgMemberParams),
+    /// 移除组织成员（组织管理员）
+    RemoveOrgMember(OrgMemberParams),
+    /// 替换组织品牌元数据（组织管理员）
+    SetOrgBranding(SetOrgBrandingParams),
+    /// 替换组织设置（组织管理员）
+    SetOrgSettings(SetOrgSettingsParams),
+    /// 任命组织管理员（仅组织所有者）
+    AddOrgAdmin(OrgMemberParams),
+    /// 撤销组织管理员（仅组织所有者）
+    RemoveOrgAdmin(OrgMemberParams),
+    /// 添加管理员（任一管理员即可）
+    AddAdmin(Principal),
+    /// 添加平台禁排时段，期间不能安排Quiz开始（任一管理员即可）
+    AddBlackoutWindow(AddBlackoutWindowParams),
+    /// 移除平台禁排时段（任一管理员即可）
+    RemoveBlackoutWindow(u64),
+    /// 发起破坏性管理操作提案，发起人计为第一次确认
+    ProposeAdminAction(AdminAction),
+    /// 确认管理操作提案，确认数达到阈值后自动执行
+    ConfirmAdminAction(u64),
+}
+
+/// 跨链消息，答题者身份以消息携带的签名者为准
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// 在Quiz所在的链上开始答题会话
+    RemoteStartAttempt { params: StartAttemptParams },
+    /// 在Quiz所在的链上提交答案，按Quiz所在的链处理消息的时间评分和计时
+    RemoteSubmission { params: SubmitAnswersParams },
+    /// 订阅Quiz所在的链的新Quiz和最终结果公告
+    Subscribe,
+    /// 取消订阅
+    Unsubscribe,
+    /// 向订阅链公告新创建的Quiz，订阅时也用于同步现有目录
+    QuizCreated(QuizAnnouncement),
+    /// 向订阅链公告Quiz标题或时间的变更
+    QuizUpdated(QuizAnnouncement),
+    /// 通知订阅链Quiz已取消或删除，从目录中移除
+    QuizWithdrawn { quiz_id: u64 },
+    /// 向订阅链公告Quiz的最终结果
+    QuizFinalized {
+        quiz_id: u64,
+        revision: u32,
+        standings_hash: CryptoHash,
+    },
+}
+
+/// 新Quiz公告的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizAnnouncement {
+    pub quiz_id: u64,
+    pub title: String,
+    pub creator: AccountOwner,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+}
+
+/// 通过订阅得知的其他链上的Quiz
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct RemoteQuizView {
+    pub host_chain_id: ChainId,
+    pub quiz_id: u64,
+    pub title: String,
+    pub creator: AccountOwner,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// 本链最近一次收到该Quiz公告的时间
+    pub refreshed_at: Timestamp,
+    /// 最终结果的排行榜哈希，尚未最终确定时为空
+    pub standings_hash: Option<CryptoHash>,
+}
+
+/// 答题会话因超时未活动被自动提交
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AttemptAbandonedEvent {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub score: u32,
+    pub answered: u32,
+}
+
+/// 封存的问题内容已公开
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ContentRevealedEvent {
+    pub quiz_id: u64,
+    pub question_count: u32,
+}
+
+/// 创建了新的Quiz
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct QuizCreatedEvent {
+    pub quiz_id: u64,
+    pub creator: AccountOwner,
+    pub title: String,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+}
+
+/// 提交了一份答卷，不含分数，隐藏成绩的Quiz不会因此提前公开结果
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AnswerSubmittedEvent {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub attempt_no: u32,
+    pub receipt: CryptoHash,
+    pub submitted_at: Timestamp,
+}
+
+/// 创建者手动开始了Quiz
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct QuizStartedEvent {
+    pub quiz_id: u64,
+    pub started_at: Timestamp,
+}
+
+/// Quiz被删除或取消，其答题记录和排行榜已被清理
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct QuizCancelledEvent {
+    pub quiz_id: u64,
+    /// 为 true 时Quiz本身也已被删除
+    pub deleted: bool,
+}
+
+/// 问题申诉达到法定票数，为创建者开启重新评分案件
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RegradeCaseOpenedEvent {
+    pub quiz_id: u64,
+    pub question_id: u32,
+    pub votes: u32,
+}
+
+/// 排行榜生成了新的冻结修订版
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardFrozenEvent {
+    pub quiz_id: u64,
+    pub revision: u32,
+}
+
+/// 创建者为用户额外增加了一次答题机会
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ExtraAttemptGrantedEvent {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub granted_by: AccountOwner,
+    pub reason: String,
+    pub granted_at: Timestamp,
+}
+
+/// 创建者发布了Quiz公告
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AnnouncementPostedEvent {
+    pub quiz_id: u64,
+    pub text: String,
+    pub posted_at: Timestamp,
+}
+
+/// 逾期未领的奖金被清扫给出资者或金库
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PrizeSweptEvent {
+    pub quiz_id: u64,
+    /// 原本有权领取的获奖者
+    pub user: AccountOwner,
+    pub rank: u32,
+    pub amount: Amount,
+    pub recipient: AccountOwner,
+}
+
+/// 奖池奖金已发放
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PrizesDistributedEvent {
+    pub quiz_id: u64,
+    pub payouts: Vec<PrizePayout>,
+    /// 未分配出去（获奖人数不足或取整余数）退回给出资者的金额
+    pub refunded: Amount,
+    /// 各代币奖金的发放记录
+    pub token_prizes: Vec<TokenPrize>,
+}
+
+/// 追加代币奖金的参数
+#[derive(Debug, Serialize, Deserialize, InputObject)]
+pub struct AddTokenPrizeParams {
+    pub quiz_id: u64,
+    /// 代币应用（`FungibleTokenAbi`）
+    pub token: ApplicationId,
+    pub amount: Amount,
+    /// 该代币按最终排名分配的百分比，追加到同一赞助者的同一代币时可为空以沿用原比例
+    #[graphql(default)]
+    pub splits: Vec<u32>,
+}
+
+/// 奖池中一位赞助者提供的一种代币奖金
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct TokenPrize {
+    pub token: ApplicationId,
+    pub sponsor: AccountOwner,
+    pub amount: Amount,
+    pub splits: Vec<u32>,
+    /// 已发放时为实际发放记录，否则为空
+    pub payouts: Vec<PrizePayout>,
+    /// 未分配出去退回给赞助者的金额
+    pub refunded: Amount,
+}
+
+/// 发放给一位获奖者的奖金
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PrizePayout {
+    pub rank: u32,
+    pub user: AccountOwner,
+    pub amount: Amount,
+}
 
-pub struct QuizAbi;
+/// Quiz奖池视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PrizePoolView {
+    pub quiz_id: u64,
+    pub funder: AccountOwner,
+    pub amount: Amount,
+    /// 已收取的参赛费，发奖时计入奖池
+    pub entry_fees: Amount,
+    pub splits: Vec<u32>,
+    /// 已发放时为实际发放记录，否则为空
+    pub payouts: Vec<PrizePayout>,
+    pub distributed: bool,
+    /// 赞助者追加的各代币奖金余额
+    pub token_prizes: Vec<TokenPrize>,
+    /// 尚未领取的奖金
+    pub unclaimed: Vec<UnclaimedPrizeView>,
+    /// 逾期被清扫的奖金记录
+    pub sweeps: Vec<PrizeSweepView>,
+}
 
-/// 创建Quiz集合的参数
-#[derive(Debug, Serialize, Deserialize, InputObject)]
-pub struct CreateQuizParams {
+/// 待领取的奖池奖金视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct UnclaimedPrizeView {
+    pub user: AccountOwner,
+    pub rank: u32,
+    pub amount: Amount,
+    pub claim_deadline: Timestamp,
+}
+
+/// 逾期奖金的清扫记录视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PrizeSweepView {
+    /// 原本有权领取的获奖者
+    pub user: AccountOwner,
+    pub rank: u32,
+    pub amount: Amount,
+    pub recipient: AccountOwner,
+    pub swept_at: Timestamp,
+}
+
+/// Quiz最终结果的可验证摘要，汇总判断结果是否公正所需的数据
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct FinalizationView {
+    pub quiz_id: u64,
+    pub finalized_at: Timestamp,
+    pub block_height: u64,
+    pub revision: u32,
+    /// 最终排行榜修订版的哈希，见 `state::standings_hash`
+    pub standings_hash: CryptoHash,
+    /// 奖池发放记录，没有奖池或尚未发放时为空
+    pub prize_distribution: Option<PrizeDistributionView>,
+    /// 最终确定时转出的奖励代币
+    pub token_rewards: Vec<PrizePayout>,
+    /// 排行榜各修订版的冻结时间
+    pub revisions: Vec<LeaderboardRevision>,
+    /// 问题申诉及重新评分记录
+    pub regrades: Vec<RegradeRecord>,
+}
+
+/// 已归档Quiz的摘要，答卷压缩为参与统计和最终排行榜
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct ArchivedQuizView {
+    pub quiz_id: u64,
     pub title: String,
-    pub description: String,
-    pub questions: Vec<QuestionParams>,
-    pub time_limit: u64,    // 秒
-    pub start_time: String, // 毫秒时间戳字符串
-    pub end_time: String,   // 毫秒时间戳字符串
-    pub nick_name: String,
+    pub creator: AccountOwner,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub cancelled: bool,
+    pub participants: u32,
+    pub attempts: u32,
+    /// 每份答卷的平均得分，没有答卷时为空
+    pub average_score: Option<u32>,
+    /// 最终排行榜，每位答题者一条
+    pub standings: Vec<LeaderboardEntry>,
+    /// 最终排行榜的哈希，未最终确定的Quiz为空
+    pub standings_hash: Option<CryptoHash>,
+    pub archived_at: Timestamp,
 }
 
-/// 问题参数
-#[derive(Debug, Serialize, Deserialize, Clone, SimpleObject, InputObject)]
-#[graphql(input_name = "QuestionParamsInput")]
-pub struct QuestionParams {
-    pub text: String,
-    pub options: Vec<String>,
-    pub correct_options: Vec<u32>,
-    pub points: u32,
+/// 奖项名次同分同时的答题者之间的加赛
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PlayoffView {
+    pub quiz_id: u64,
+    /// 限同分者参加的加赛Quiz，结束后原Quiz才能最终确定
+    pub playoff_quiz_id: u64,
+    pub tied_users: Vec<AccountOwner>,
+    pub created_at: Timestamp,
 }
 
-/// 提交答案的参数
-#[derive(Debug, Serialize, Deserialize, InputObject)]
-pub struct SubmitAnswersParams {
+/// 奖池发放记录
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PrizeDistributionView {
+    pub payouts: Vec<PrizePayout>,
+    /// 退回给出资者的金额
+    pub refunded: Amount,
+    pub distributed_at: Timestamp,
+    pub block_height: u64,
+}
+
+/// 排行榜修订版
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardRevision {
+    pub revision: u32,
+    pub frozen_at: Timestamp,
+}
+
+/// 标签及使用该标签的Quiz数
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct TagCount {
+    pub tag: String,
+    pub quiz_count: u32,
+}
+
+/// 分类及该分类下的Quiz数
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct CategoryCount {
+    pub category: String,
+    pub quiz_count: u32,
+}
+
+/// 问题的申诉及处理结果
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct RegradeRecord {
+    pub question_id: u32,
+    pub votes: u32,
+    pub status: DisputeStatus,
+}
+
+/// Quiz结果已最终确定
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct QuizFinalizedEvent {
+    pub quiz_id: u64,
+    /// 最终结果所在的排行榜修订版
+    pub revision: u32,
+    /// 最终排行榜的前几名
+    pub top_entries: Vec<LeaderboardEntry>,
+    /// 转给前几名的奖励代币
+    pub token_rewards: Vec<PrizePayout>,
+}
+
+/// 奖项名次出现同分同时，已为同分者创建加赛
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PlayoffStartedEvent {
+    pub quiz_id: u64,
+    pub playoff_quiz_id: u64,
+    pub tied_users: Vec<AccountOwner>,
+}
+
+/// 应用事件流的名称
+pub const EVENT_STREAM_NAME: &[u8] = b"quiz_events";
+
+/// 应用事件，作为 `EventValue` 发布到 `quiz_events` 事件流，外部索引器可直接订阅
+#[derive(Debug, Clone, Serialize, Deserialize, Union)]
+pub enum AppEvent {
+    QuizCreated(QuizCreatedEvent),
+    AnswerSubmitted(AnswerSubmittedEvent),
+    AttemptAbandoned(AttemptAbandonedEvent),
+    RegradeCaseOpened(RegradeCaseOpenedEvent),
+    QuizStarted(QuizStartedEvent),
+    QuizCancelled(QuizCancelledEvent),
+    LeaderboardFrozen(LeaderboardFrozenEvent),
+    ContentRevealed(ContentRevealedEvent),
+    QuizFinalized(QuizFinalizedEvent),
+    ExtraAttemptGranted(ExtraAttemptGrantedEvent),
+    PrizesDistributed(PrizesDistributedEvent),
+    PrizeSwept(PrizeSweptEvent),
+    AnnouncementPosted(AnnouncementPostedEvent),
+    PlayoffStarted(PlayoffStartedEvent),
+}
+
+/// 问题申诉状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DisputeStatus {
+    /// 正在收集申诉票
+    Voting,
+    /// 已达到法定票数，等待创建者处理
+    RegradeOpen,
+    /// 创建者认定问题有误
+    Upheld,
+    /// 创建者驳回申诉
+    Dismissed,
+}
+
+/// 指向某个Quiz中的问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct QuestionRef {
     pub quiz_id: u64,
-    pub answers: Vec<Vec<u32>>, // 每个问题的答案选项索引列表，支持多选
-    pub time_taken: u64,        // 毫秒
+    pub question_id: u32,
+}
+
+/// 与创建者已有问题重复的问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct DuplicateQuestion {
+    pub question_id: u32,
+    pub existing: Vec<QuestionRef>,
+}
+
+/// 相似度报告中一对相似的文本答案
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SimilarityPair {
+    pub question_id: u32,
+    pub user_a: AccountOwner,
+    pub attempt_a: u32,
+    pub user_b: AccountOwner,
+    pub attempt_b: u32,
+    /// 估算的相似度（百分比）
+    pub similarity: u32,
+}
+
+/// 单个问题的作答结果视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct QuestionResultView {
+    pub question_id: u32,
+    pub correct: bool,
+    /// 未作答、留空或明确表示不会，与答错区分
+    pub skipped: bool,
+    /// 按应用展示精度格式化的得分
+    pub points_awarded: String,
+    /// 逐题保存时已保存的答案被修改的次数
+    pub answer_changes: u32,
+    /// 已评分问答题的各项评分标准得分，其他问题为空
+    pub rubric: Vec<RubricScore>,
+}
+
+/// 问答题一条评分标准的评分结果
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct RubricScore {
+    pub criterion: String,
+    pub weight: u32,
+    /// 选中的等级，0为最低
+    pub level: u32,
+    pub level_count: u32,
+    /// 选中等级的描述
+    pub descriptor: String,
+}
+
+/// 答卷的逐题得分明细
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct AttemptDetail {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub attempt_no: u32,
+    pub score: u32,
+    pub precise_score: String,
+    pub results: Vec<QuestionResultView>,
+}
+
+/// 通过Quiz的证书记录，可凭提交回执和链ID在链上核对
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct CertificateView {
+    pub quiz_id: u64,
+    pub quiz_title: String,
+    pub user: AccountOwner,
+    pub attempt_no: u32,
+    pub score: u32,
+    pub pass_score: u32,
+    pub completed_at: Timestamp,
+    /// Quiz所在的链
+    pub chain_id: ChainId,
+    pub receipt: CryptoHash,
+}
+
+/// 冻结排行榜中的一个名次
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardSnapshotEntry {
+    pub rank: u32,
+    /// 私密用户匿名显示时为空
+    pub user: Option<AccountOwner>,
     pub nick_name: String,
+    pub score: u32,
 }
 
-/// 排行榜条目
-#[derive(Debug, Serialize, Deserialize, SimpleObject, Clone)]
-pub struct LeaderboardEntry {
-    pub user: String,
+/// 冻结的排行榜：修订版1为原始榜单，之后每次重新评分追加一个修订版
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardSnapshotView {
+    pub quiz_id: u64,
+    pub revision: u32,
+    pub frozen_at: Timestamp,
+    pub entries: Vec<LeaderboardSnapshotEntry>,
+}
+
+/// 用户在Quiz排行榜上的名次
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardRank {
+    pub quiz_id: u64,
+    pub rank: u32,
+    /// 排行榜上的总人数
+    pub total: u32,
+    pub entry: UserAttemptView,
+}
+
+/// 用户名次上下各 radius 名的排行榜片段
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardWindow {
+    pub quiz_id: u64,
+    pub rank: u32,
+    /// 排行榜上的总人数
+    pub total: u32,
+    /// entries 中第一条的名次
+    pub first_rank: u32,
+    pub entries: Vec<UserAttemptView>,
+}
+
+/// 创建者内容中相同指纹的一组问题
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct DuplicateQuestionGroup {
+    pub fingerprint: String,
+    pub questions: Vec<QuestionRef>,
+}
+
+/// 用户档案中的奖杯
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct TrophyView {
+    pub quiz_id: u64,
+    /// Quiz已被删除时为空
+    pub quiz_title: Option<String>,
+    /// 名次，1到3
+    pub place: u32,
     pub score: u32,
-    pub time_taken: u64,
+    pub awarded_at: Timestamp,
 }
 
-/// 应用支持的操作
-#[derive(Debug, Serialize, Deserialize, GraphQLMutationRoot)]
-pub enum Operation {
-    /// 创建新的Quiz集合
-    CreateQuiz(CreateQuizParams),
-    /// 提交Quiz答案
-    SubmitAnswers(SubmitAnswersParams),
+/// 用户档案视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct UserView {
+    pub user: AccountOwner,
+    /// 登记的唯一昵称
+    pub nick_name: Option<String>,
+    /// 排行榜前三名奖杯，私密用户只对本人可见
+    pub trophies: Vec<TrophyView>,
+}
+
+/// 用户的累计答题和创建统计，练习卷不计入
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct UserStatsView {
+    pub user: AccountOwner,
+    /// 有已评分答卷的不同Quiz数
+    pub quizzes_taken: u32,
+    pub quizzes_created: u32,
+    pub graded_attempts: u32,
+    /// 各次答卷得分之和
+    pub total_points: u64,
+    /// 每份答卷的平均得分，没有答卷时为空
+    pub average_score: Option<u32>,
+    /// 提交答卷时在Quiz排行榜上取得的最好名次
+    pub best_rank: Option<u32>,
+}
+
+/// 用户每日完成Quiz的连续天数和当前赛季积分
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct UserStreakView {
+    pub user: AccountOwner,
+    /// 截至今天或昨天仍未中断的连续天数，已中断时为0
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub last_completed_day: Option<u64>, // 自Unix纪元起的天数
+    /// 当前连续天数对应的赛季积分加成倍数，如 "1.25"
+    pub bonus_multiplier: String,
+    pub season: u64,
+    pub season_points: u64,
+}
+
+/// 问题统计
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct QuestionStats {
+    pub question_id: u32,
+    pub appeal_votes: u32,
+    /// 无人申诉时为空
+    pub dispute_status: Option<DisputeStatus>,
+    /// 逐题保存答案时记录的用时样本数
+    pub answer_time_samples: u32,
+    /// 答题用时中位数（毫秒），没有样本时为空
+    pub median_answer_time: Option<u64>,
+    /// 抽到该问题的生效答卷数
+    pub answered: u64,
+    /// 答对的答卷数
+    pub correct: u64,
+    /// 跳过的答卷数，不计为答错
+    pub skipped: u64,
+    /// 答对率（百分比），没有答卷时为空
+    pub accuracy: Option<u32>,
+    /// 按答对率划分的难度，没有答卷时为空
+    pub difficulty: Option<QuestionDifficulty>,
+}
+
+/// Quiz统计
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct QuizStats {
+    pub quiz_id: u64,
+    pub time_limit: u64, // 秒，0 表示不限时
+    /// 按逐题用时中位数估算的建议答题时限（秒），没有用时样本时为空
+    pub recommended_time_limit: Option<u64>,
+    /// 浏览次数
+    pub views: u64,
+    /// 不同的已认证浏览者数
+    pub unique_viewers: u64,
+    /// 开始过答题的不同用户数
+    pub starters: u64,
+    /// 至少有一份生效答卷的不同用户数
+    pub submitters: u64,
+}
+
+/// 用户在分批开放中被分到的批次及答题时间窗口
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SubmissionWindow {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    /// 用户所在批次，从0开始；未分批开放时为0
+    pub wave: u32,
+    pub wave_count: u32,
+    pub opens_at: Timestamp, // 手动开始的Quiz在开始前为预定开始时间
+    pub closes_at: Timestamp,
+    /// 该用户的答题时限（秒），已计入延长答题时间，0 表示不限时
+    pub time_limit: u64,
+    /// 当前是否可以答题
+    pub open: bool,
+}
+
+/// 应用事件流的位置，索引器据此从节点读取事件
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct EventStreamView {
+    pub chain_id: ChainId,
+    pub application_id: ApplicationId,
+    pub stream_name: String,
+    /// 下一个事件的序号，即已发布的事件数
+    pub next_index: u32,
+    /// 从客户端给出的 since_index 起尚未读取的事件数
+    pub pending: u32,
+}
+
+/// Quiz已开始或已有答题记录，不能再修改
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct QuizLockedError {
+    pub quiz_id: u64,
+    pub reason: String,
+}
+
+/// 昵称已被其他用户登记
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct NicknameTakenError {
+    pub nick_name: String,
+    pub owner: AccountOwner,
+}
+
+/// 提交时已超过答题时限和宽限时长，会话已按保存的答案记为放弃的答卷
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct TimeLimitExceededError {
+    pub quiz_id: u64,
+    pub time_limit: u64, // 秒
+    pub elapsed: u64,    // 毫秒
+}
+
+/// 合约操作返回的业务错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
+pub enum QuizError {
+    QuizLocked(QuizLockedError),
+    TimeLimitExceeded(TimeLimitExceededError),
+    NicknameTaken(NicknameTakenError),
+}
+
+impl fmt::Display for QuizError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuizError::QuizLocked(error) => {
+                write!(f, "Quiz {} is locked: {}", error.quiz_id, error.reason)
+            }
+            QuizError::TimeLimitExceeded(error) => write!(
+                f,
+                "Quiz {} time limit of {}s exceeded after {}ms",
+                error.quiz_id, error.time_limit, error.elapsed
+            ),
+            QuizError::NicknameTaken(error) => {
+                write!(f, "Nickname {} is already taken", error.nick_name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QuizError {}
+
+/// 合约操作的结果
+pub type QuizResult<T> = Result<T, QuizError>;
+
+/// 合约操作的返回值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuizResponse {
+    /// 操作已执行，无额外返回数据
+    Ok,
+    /// 新创建的Quiz，附带与创建者已有问题重复的问题
+    QuizCreated {
+        quiz_id: u64,
+        duplicate_questions: Vec<DuplicateQuestion>,
+    },
+    /// 答卷已提交；结果隐藏时不返回分数和名次，等待监考确认时不返回名次
+    AnswersSubmitted {
+        score: Option<u32>,
+        precise_score: Option<String>,
+        rank: Option<u32>,
+        receipt: CryptoHash,
+    },
+    /// 新建的管理操作提案
+    ProposalCreated { proposal_id: u64 },
+    /// 答题会话已开始，附带答题者看到的问题顺序（原始问题ID）
+    AttemptStarted { question_order: Vec<u32> },
+    /// 新生成的预览令牌
+    PreviewTokenCreated { token: CryptoHash },
 }
 
 /// 应用支持的查询
@@ -68,28 +1697,58 @@ pub enum Query {
     /// 获取Quiz集合详情
     GetQuizSet(u64),
     /// 获取用户的Quiz尝试记录
-    GetUserAttempts(String),
+    GetUserAttempts(AccountOwner),
     /// 获取Quiz排行榜
     GetLeaderboard,
     /// 获取单个Quiz的排行榜
     GetQuizLeaderboard(u64),
     /// 获取用户参与的测验集合
-    GetUserParticipations(String),
+    GetUserParticipations(AccountOwner),
     /// 获取用户创建的测验集合
-    GetUserCreatedQuizzes(String),
+    GetUserCreatedQuizzes(AccountOwner),
     /// 获取用户参与的测验集合详情
-    GetUserParticipatedQuizzes(String),
+    GetUserParticipatedQuizzes(AccountOwner),
 }
 
 /// 用户答题尝试视图
 #[derive(Debug, Serialize, Deserialize, SimpleObject)]
 pub struct UserAttemptView {
     pub quiz_id: u64,
-    pub user: String,
-    pub answers: Vec<Vec<u32>>,
+    /// 私密用户在公开排行榜中匿名显示时为空
+    pub user: Option<AccountOwner>,
+    pub nick_name: String,
+    pub answers: Vec<AnswerView>,
     pub score: u32,
+    /// 按应用展示精度格式化的精确分数
+    pub precise_score: String,
+    /// 每个问题的精确得分，结果隐藏或聚合的排行榜条目为空
+    pub question_scores: Vec<String>,
     pub time_taken: u64,
-    pub completed_at: String, // 微秒时间戳字符串
+    pub completed_at: Timestamp,
+    /// 结果尚未公布，分数被隐藏
+    pub results_hidden: bool,
+    /// 提交回执ID，聚合的排行榜条目为空
+    pub receipt: Option<CryptoHash>,
+    /// 答题者看到的问题顺序（原始问题ID），聚合的排行榜条目为空
+    pub question_order: Vec<u32>,
+    /// 该用户在此Quiz上的第几次答题，聚合的排行榜条目为0
+    pub attempt_no: u32,
+    /// 是否达到及格分，Quiz未设置及格分、结果隐藏或聚合的排行榜条目为空
+    pub passed: Option<bool>,
+}
+
+/// 跨Quiz累计排行榜的条目，按累计积分、再按计入的Quiz数排名
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct GlobalStandingView {
+    pub rank: u32,
+    /// 私密用户在公开排行榜中匿名显示时为空
+    pub user: Option<AccountOwner>,
+    pub nick_name: String,
+    pub points: u32,
+    /// 按应用展示精度格式化的精确积分
+    pub precise_points: String,
+    pub quizzes_completed: u32,
+    pub updated_at: Timestamp,
 }
 
 /// 测验尝试记录
@@ -97,6 +1756,8 @@ pub struct UserAttemptView {
 pub struct QuizAttempt {
     pub quiz_id: u64,
     pub attempt: UserAttemptView,
+    /// 答题者的私人笔记，仅答题者本人可见
+    pub note: Option<String>,
 }
 
 /// Quiz集合视图
@@ -105,11 +1766,79 @@ pub struct QuizSetView {
     pub id: u64,
     pub title: String,
     pub description: String,
-    pub creator: String,
+    pub creator: AccountOwner,
+    pub creator_name: String,
     pub questions: Vec<QuestionView>,
-    pub start_time: String, // 微秒时间戳字符串
-    pub end_time: String,   // 微秒时间戳字符串
-    pub created_at: String, // 微秒时间戳字符串
+    pub start_mode: QuizStartMode,
+    pub scoring: ScoringStrategy,
+    pub allow_dont_know: bool,
+    pub is_started: bool,
+    /// 按当前时间计算的Quiz阶段
+    pub status: QuizStatus,
+    pub cancelled: bool,
+    /// 结果已最终确定，排行榜不会再变化
+    pub finalized: bool,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub created_at: Timestamp,
+    pub practice: bool,
+    /// 加赛所属的原Quiz，仅原Quiz中同分同时的答题者可以参加
+    pub playoff_of: Option<u64>,
+    pub hide_results: bool,
+    /// 当前是否可以查看分数和排行榜
+    pub results_visible: bool,
+    pub proctor: Option<AccountOwner>,
+    pub owner: Option<String>,
+    pub metadata: Vec<MetadataEntry>,
+    pub org: Option<String>,
+    pub members_only: bool,
+    pub time_limit: u64, // 秒，0 表示不限时
+    pub shuffle_questions: bool,
+    pub questions_per_attempt: Option<u32>,
+    pub max_attempts: u32,
+    pub attempt_policy: AttemptPolicy,
+    pub attestation_app: Option<ApplicationId>,
+    pub entry_fee: Option<Amount>,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub pass_score: Option<u32>,
+    pub soft_launch: Option<SoftLaunch>,
+    /// 问题内容仍处于封存中，questions 为占位问题
+    pub embargoed: bool,
+    pub content_commitment: Option<CryptoHash>,
+    /// 创建者提交的加盐答案校验和
+    pub answer_key_hash: Option<CryptoHash>,
+    /// Quiz结束后公开的盐，已由合约校验与答案和校验和相符
+    pub answer_key_salt: Option<String>,
+}
+
+/// 组织视图
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct OrgView {
+    pub slug: String,
+    pub name: String,
+    pub owner: AccountOwner,
+    pub admins: Vec<AccountOwner>,
+    pub members: Vec<AccountOwner>,
+    pub branding: Vec<MetadataEntry>,
+    pub settings: OrgSettings,
+    pub created_at: Timestamp,
+}
+
+/// 组织统计，仅组织管理员可见
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct OrgAnalytics {
+    pub org: String,
+    pub quiz_count: u64,
+    /// 当前处于答题时间内的Quiz数量
+    pub active_quizzes: u64,
+    pub cancelled_quizzes: u64,
+    /// 组织内Quiz的生效答卷数量
+    pub attempt_count: u64,
+    /// 至少提交过一份答卷的不同用户数
+    pub participant_count: u64,
+    /// 按应用展示精度格式化的平均分，没有答卷时为空
+    pub average_score: Option<String>,
 }
 
 /// 问题视图
@@ -119,6 +1848,32 @@ pub struct QuestionView {
     pub text: String,
     pub options: Vec<String>,
     pub points: u32,
+    pub kind: QuestionType,
+    pub media: Option<QuestionMedia>,
+    pub screen_reader_hint: Option<String>,
+}
+
+/// 答题后回顾的问题：包含正确答案和解析
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct QuestionReview {
+    pub question_id: u32,
+    pub text: String,
+    pub options: Vec<String>,
+    pub correct_options: Vec<u32>,
+    /// 填空题的可接受答案，或数值题的答案与容差
+    pub accepted_answers: Vec<String>,
+    /// 问答题的评分标准
+    pub rubric: Vec<RubricCriterion>,
+    pub explanation: Option<String>,
+}
+
+/// 收藏的问题及其答案解析
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct BookmarkedQuestion {
+    pub quiz_id: u64,
+    pub quiz_title: String,
+    pub bookmarked_at: Timestamp,
+    pub question: QuestionReview,
 }
 
 /// 查询响应
@@ -127,7 +1882,7 @@ pub enum QueryResponse {
     /// 所有Quiz集合
     QuizSets(Vec<QuizSetView>),
     /// Quiz集合详情
-    QuizSet(Option<QuizSetView>),
+    QuizSet(Option<Box<QuizSetView>>),
     /// 用户尝试记录列表
     UserAttempts(Vec<QuizAttempt>),
     Leaderboard(Vec<UserAttemptView>),
@@ -139,12 +1894,525 @@ pub enum QueryResponse {
     UserParticipatedQuizzes(Vec<QuizSetView>),
 }
 
+/// 查询时的链元数据，便于聚合多个部署的客户端标注和去重
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ChainMetadata {
+    pub chain_id: String,
+    pub application_id: String,
+    pub next_block_height: u64,
+    pub timestamp: Timestamp,
+}
+
+/// 供通用Linera浏览器聚合展示的应用概要
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AppSummary {
+    pub metadata: ChainMetadata,
+    pub version: String,
+    pub quiz_count: u64,
+    /// 当前处于答题时间内的Quiz数量
+    pub active_quizzes: u64,
+    /// 最近24小时内生效的答卷数量（最多回溯事件日志末尾的一万条）
+    pub attempts_last_day: u64,
+    pub admins: Vec<String>,
+}
+
+/// 附带链元数据的查询结果
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(concrete(name = "QuizSetsEnvelope", params(QuizSetView)))]
+#[graphql(concrete(name = "QuizAttemptsEnvelope", params(QuizAttempt)))]
+#[graphql(concrete(name = "LeaderboardEnvelope", params(UserAttemptView)))]
+pub struct Envelope<T: OutputType> {
+    pub metadata: ChainMetadata,
+    pub data: Vec<T>,
+}
+
+/// 游标分页信息
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct PageInfo {
+    /// 本页最后一项的不透明游标，作为下一页的 after 参数；本页为空时为空
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
+}
+
+/// 游标分页结果中的一项，cursor 可作为 after 参数从该项之后继续
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(concrete(name = "QuizSetEdge", params(QuizSetView)))]
+#[graphql(concrete(name = "QuizAttemptEdge", params(QuizAttempt)))]
+#[graphql(concrete(name = "LeaderboardEdge", params(UserAttemptView)))]
+#[graphql(concrete(name = "RemoteQuizEdge", params(RemoteQuizView)))]
+pub struct Edge<T: OutputType> {
+    pub cursor: String,
+    pub node: T,
+}
+
+/// 游标分页的查询结果，所有列表查询的 *Connection 版本共用
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+#[graphql(concrete(name = "QuizSetConnection", params(QuizSetView)))]
+#[graphql(concrete(name = "QuizAttemptConnection", params(QuizAttempt)))]
+#[graphql(concrete(name = "LeaderboardConnection", params(UserAttemptView)))]
+#[graphql(concrete(name = "RemoteQuizConnection", params(RemoteQuizView)))]
+pub struct Connection<T>
+where
+    T: OutputType,
+    Edge<T>: OutputType,
+{
+    pub edges: Vec<Edge<T>>,
+    pub page_info: PageInfo,
+}
+
+/// 评分预览结果
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct ScorePreview {
+    pub quiz_id: u64,
+    pub score: u32,
+    pub precise_score: String,
+    pub max_score: u32,
+}
+
+/// 列表排序方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum SortOrder {
+    /// 按ID升序（最早创建的在前）
+    #[default]
+    Ascending,
+    /// 按ID降序（最新创建的在前）
+    Descending,
+}
+
+/// Quiz列表的排序字段，相同时按Quiz ID排序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum QuizSortField {
+    /// Quiz ID，即创建顺序
+    #[default]
+    Id,
+    /// 开始时间
+    StartTime,
+    /// 结束时间
+    EndTime,
+    /// 标题，不区分大小写
+    Title,
+}
+
+/// 答题记录的排序字段，相同时按Quiz ID和答题次序排序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum AttemptSortField {
+    /// Quiz ID
+    #[default]
+    QuizId,
+    /// 完成时间
+    CompletedAt,
+    /// 得分
+    Score,
+    /// 用时
+    TimeTaken,
+}
+
+/// 分数展示时的舍入策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum RoundingPolicy {
+    /// 向下取整
+    Floor,
+    /// 四舍五入
+    #[default]
+    HalfUp,
+    /// 向上取整
+    Ceil,
+}
+
+/// Quiz排行榜的排名方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LeaderboardKind {
+    /// 按最高分排名
+    #[default]
+    Score,
+    /// 只列出满分答卷，按用时排名
+    Speed,
+    /// 按答对的题数排名
+    Accuracy,
+    /// 按最好成绩相对首次答题的提升排名
+    MostImproved,
+}
+
+/// 应用参数，由运营方在创建应用时设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuizParameters {
+    /// 列表查询未指定 limit 时的默认每页条数
+    pub default_page_size: u32,
+    /// 列表查询允许的最大每页条数
+    pub max_page_size: u32,
+    /// 列表查询未指定 sort 时的默认排序方向
+    pub default_sort: SortOrder,
+    /// 答题会话超过该时长（秒）未活动即视为放弃
+    pub abandon_timeout_secs: u64,
+    /// 破坏性管理操作所需的管理员确认数
+    pub admin_confirmation_threshold: u32,
+    /// 开启重新评分案件所需的申诉票数
+    pub appeal_quorum: u32,
+    /// 每个调用方在一个限流窗口内的建议查询上限
+    pub rate_limit_queries: u32,
+    /// 限流窗口长度（秒）
+    pub rate_limit_window_secs: u64,
+    /// 展示分数时的舍入策略
+    pub score_rounding: RoundingPolicy,
+    /// 精确分数展示的小数位数（最多3位）
+    pub score_precision: u32,
+    /// 超过答题时限后仍接受提交的宽限时长（秒），用于抵消出块延迟
+    pub time_limit_grace_secs: u64,
+    /// 每个赛季的时长（秒），赛季积分按赛季分别累计
+    pub season_length_secs: u64,
+    /// 连续第1、2、3…天完成Quiz时的赛季积分加成（百分比），超出部分沿用最后一项
+    pub streak_bonus_schedule: Vec<u32>,
+    /// 奖励代币所在的同质化代币应用，奖励从本应用在其中的账户转出
+    pub reward_token: Option<ApplicationId>,
+    /// Quiz最终确定时第1、2、3…名获得的奖励代币数量
+    pub reward_token_amounts: Vec<Amount>,
+    /// 证书NFT应用，答卷达到及格分时为答题者铸造完成证书
+    pub certificate_app: Option<ApplicationId>,
+    /// 设置后奖池奖金改为由获奖者在该时长（秒）内领取，逾期未领的奖金可被清扫
+    pub prize_claim_window_secs: Option<u64>,
+    /// 逾期未领奖金的去向，未设置时退回给奖池出资者
+    pub treasury: Option<AccountOwner>,
+    /// 合约和服务的日志级别，Trace 时输出操作和查询的区间日志
+    pub log_level: LogLevel,
+    /// 全局衰减排名的半衰期（天），积分每经过一个半衰期减半
+    pub ranking_half_life_days: u32,
+    /// 问题附带音频的最大时长（秒）
+    pub max_audio_duration_secs: u32,
+    /// 问题附带视频的最大时长（秒）
+    pub max_video_duration_secs: u32,
+    /// 奖项名次同分同时时加赛的题数，0 表示不加赛、按原排行榜顺序结算
+    pub playoff_question_count: u32,
+    /// 加赛的开放时长（秒），同分者须在此期间内完成加赛
+    pub playoff_window_secs: u64,
+}
+
+impl Default for QuizParameters {
+    fn default() -> Self {
+        QuizParameters {
+            default_page_size: 100,
+            max_page_size: 500,
+            default_sort: SortOrder::Ascending,
+            abandon_timeout_secs: 600,
+            admin_confirmation_threshold: 2,
+            appeal_quorum: 3,
+            rate_limit_queries: 600,
+            rate_limit_window_secs: 60,
+            score_rounding: RoundingPolicy::HalfUp,
+            score_precision: 2,
+            time_limit_grace_secs: 5,
+            season_length_secs: 30 * 24 * 60 * 60,
+            streak_bonus_schedule: vec![100, 110, 125, 150, 200],
+            reward_token: None,
+            reward_token_amounts: Vec::new(),
+            certificate_app: None,
+            prize_claim_window_secs: None,
+            treasury: None,
+            log_level: LogLevel::Info,
+            ranking_half_life_days: 30,
+            max_audio_duration_secs: 30 * 60,
+            max_video_duration_secs: 10 * 60,
+            playoff_question_count: 3,
+            playoff_window_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+impl QuizParameters {
+    /// 按舍入策略取整后的展示分数
+    pub fn display_score(&self, score: FixedScore) -> u32 {
+        scoring::display_score(score, self.score_rounding)
+    }
+
+    /// 按展示精度格式化的精确分数
+    pub fn format_score(&self, score: FixedScore) -> String {
+        scoring::format_score(score, self.score_precision, self.score_rounding)
+    }
+
+    /// 时间点所在的赛季编号
+    pub fn season_at(&self, time: Timestamp) -> u64 {
+        time.micros() / self.season_length_secs.max(1).saturating_mul(1_000_000)
+    }
+
+    /// 该类型媒体允许的最大时长（秒）
+    pub fn max_media_duration(&self, kind: MediaKind) -> u32 {
+        match kind {
+            MediaKind::Audio => self.max_audio_duration_secs,
+            MediaKind::Video => self.max_video_duration_secs,
+        }
+    }
+
+    /// 连续 streak 天完成Quiz时的赛季积分加成（百分比），未形成连续时为100
+    pub fn streak_bonus(&self, streak: u32) -> u32 {
+        let Some(index) = (streak as usize).checked_sub(1) else {
+            return 100;
+        };
+        self.streak_bonus_schedule
+            .get(index)
+            .or(self.streak_bonus_schedule.last())
+            .copied()
+            .unwrap_or(100)
+    }
+}
+
+/// GraphQL响应 `extensions.rateLimit` 中的建议性限流信息，超出上限时不会拒绝请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitHint {
+    pub limit: u32,
+    pub remaining: u32,
+    /// 窗口重置时间
+    pub reset: Timestamp,
+}
+
+/// 个人答题记录导出文档的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExportFormat {
+    /// 紧凑JSON
+    #[default]
+    Json,
+    /// 带缩进的JSON，便于阅读
+    PrettyJson,
+}
+
+/// 个人答题记录导出文档
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttemptHistoryExport {
+    pub user: AccountOwner,
+    pub metadata: ChainMetadata,
+    pub attempts: Vec<AttemptHistoryEntry>,
+}
+
+/// 导出文档中的一次答题
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttemptHistoryEntry {
+    pub quiz_id: u64,
+    /// Quiz已被删除时为空
+    pub quiz_title: Option<String>,
+    pub attempt: UserAttemptView,
+    pub note: Option<String>,
+    /// 答卷仍在等待监考人确认
+    pub pending_confirmation: bool,
+}
+
+/// 状态导出的分区，每个分区对应一个或一组视图
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ExportSection {
+    /// 全局计数器（next_quiz_id）
+    Counters,
+    /// quiz_sets
+    Quizzes,
+    /// user_attempts
+    Attempts,
+    /// quiz_attempt_index
+    QuizAttemptIndex,
+    /// user_attempt_index
+    UserAttemptIndex,
+    /// quiz_events
+    Events,
+    /// user_participations
+    Participations,
+    /// creator_quizzes
+    CreatorQuizzes,
+    /// answer_fingerprints
+    AnswerFingerprints,
+    /// leaderboard
+    Leaderboards,
+    /// private_users
+    PrivateUsers,
+    /// pending_attempts
+    PendingAttempts,
+    /// attempt_sessions
+    AttemptSessions,
+    /// admins
+    Admins,
+    /// admin_proposals
+    AdminProposals,
+    /// attempt_notes，笔记是私人的，只导出键
+    AttemptNotes,
+    /// question_appeals
+    QuestionAppeals,
+    /// question_fingerprints
+    QuestionFingerprints,
+    /// attempt_receipts
+    AttemptReceipts,
+    /// leaderboard_snapshots
+    LeaderboardSnapshots,
+    /// orgs
+    Orgs,
+    /// org_members
+    OrgMembers,
+    /// org_admins
+    OrgAdmins,
+    /// user_profiles
+    UserProfiles,
+    /// nicknames
+    Nicknames,
+    /// quiz_identities
+    QuizIdentities,
+    /// accommodations
+    Accommodations,
+    /// extra_attempts
+    ExtraAttempts,
+    /// announcements
+    Announcements,
+    /// prize_pools
+    PrizePools,
+    /// unclaimed_prizes
+    UnclaimedPrizes,
+    /// prize_sweeps
+    PrizeSweeps,
+    /// finalizations
+    Finalizations,
+    /// playoffs
+    Playoffs,
+    /// archived_quizzes
+    ArchivedQuizzes,
+    /// preview_tokens，令牌是凭证，只导出令牌哈希（`state::preview_token_hash`）和元数据
+    PreviewTokens,
+    /// quiz_preview_tokens，同样只导出令牌哈希
+    QuizPreviewTokens,
+    /// retention_policies
+    RetentionPolicies,
+    /// tag_index
+    TagIndex,
+    /// category_index
+    CategoryIndex,
+    /// search_index
+    SearchIndex,
+    /// certificates
+    Certificates,
+    /// quiz_views
+    QuizViews,
+    /// bookmarks
+    Bookmarks,
+    /// user_bookmark_index
+    UserBookmarkIndex,
+    /// subscribers
+    Subscribers,
+    /// blackout_windows
+    BlackoutWindows,
+    /// notifications
+    Notifications,
+    /// remote_quizzes
+    RemoteQuizzes,
+    /// quiz_viewers
+    QuizViewers,
+    /// quiz_starters
+    QuizStarters,
+    /// quiz_participants
+    QuizParticipants,
+    /// escrow
+    Escrow,
+    /// question_times
+    QuestionTimes,
+    /// question_tallies
+    QuestionTallies,
+    /// season_points
+    SeasonPoints,
+    /// decayed_ranking
+    DecayedRanking,
+    /// global_standings
+    GlobalStandings,
+    /// global_contributions
+    GlobalContributions,
+}
+
+/// 状态导出的一页数据
+#[derive(Debug, Serialize, Deserialize, SimpleObject)]
+pub struct StateExportPage {
+    pub section: ExportSection,
+    pub offset: u64,
+    pub records: Vec<String>, // 规范化JSON记录，按存储顺序排列
+    pub next_offset: Option<u64>,
+    pub page_hash: String,
+}
+
+/// 计算导出页哈希时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateExportDigest {
+    pub section: ExportSection,
+    pub offset: u64,
+    pub records: Vec<String>,
+}
+
+impl BcsHashable<'_> for StateExportDigest {}
+
+impl StateExportPage {
+    /// 根据页内容构造导出页并计算其哈希
+    pub fn new(
+        section: ExportSection,
+        offset: u64,
+        records: Vec<String>,
+        next_offset: Option<u64>,
+    ) -> Self {
+        let digest = StateExportDigest {
+            section,
+            offset,
+            records,
+        };
+        let page_hash = CryptoHash::new(&digest).to_string();
+        StateExportPage {
+            section,
+            offset,
+            records: digest.records,
+            next_offset,
+            page_hash,
+        }
+    }
+}
+
 impl ContractAbi for QuizAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = QuizResult<QuizResponse>;
 }
 
 impl ServiceAbi for QuizAbi {
     type Query = async_graphql::Request;
     type QueryResponse = async_graphql::Response;
-}
\ No newline at end of file
+}
+
+/// 外部身份认证应用的ABI，Quiz通过跨应用调用查询答题者的已验证身份
+pub struct AttestationAbi;
+
+impl ContractAbi for AttestationAbi {
+    type Operation = AttestationOperation;
+    /// 已验证身份的ID，同一个人的多个账户返回相同的ID；未验证时为空
+    type Response = Option<String>;
+}
+
+/// 身份认证应用需要支持的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AttestationOperation {
+    /// 查询账户绑定的已验证身份
+    VerifiedIdentity { owner: AccountOwner },
+}
+
+/// 证书NFT应用的ABI，答卷通过时Quiz通过跨应用调用铸造完成证书
+pub struct CertificateAbi;
+
+impl ContractAbi for CertificateAbi {
+    type Operation = CertificateOperation;
+    type Response = ();
+}
+
+/// 证书NFT应用需要支持的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CertificateOperation {
+    /// 为 owner 铸造一枚NFT
+    Mint {
+        owner: AccountOwner,
+        name: String,
+        metadata: CertificateMetadata,
+    },
+}
+
+/// 完成证书NFT携带的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateMetadata {
+    pub quiz_id: u64,
+    pub score: u32,
+    pub completed_at: Timestamp,
+}
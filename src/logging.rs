@@ -0,0 +1,97 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 合约与服务共用的结构化日志
+
+SDK 的日志器只转发消息文本，因此目标和字段都写进消息里：
+`[quiz::contract] answers graded quiz_id=3 user=0x…`。级别由应用参数 `log_level` 控制，
+为 `Trace` 时还会在每个操作、消息和查询字段前后输出区间日志，
+节点为每行日志记录的时间即可用来衡量耗时。 */
+
+use serde::{Deserialize, Serialize};
+
+#[doc(hidden)]
+pub use log;
+pub use log::Level;
+
+/// 合约日志的目标
+pub const CONTRACT_TARGET: &str = "quiz::contract";
+/// 服务日志的目标
+pub const SERVICE_TARGET: &str = "quiz::service";
+
+/// 日志级别，低于该级别的日志不输出
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Off => log::LevelFilter::Off,
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// 按应用参数设置最高日志级别，须在 SDK 安装日志器之后调用
+pub fn init(level: LogLevel) {
+    log::set_max_level(level.filter());
+}
+
+/// 输出一条结构化日志：`[目标] 消息 key=value …`，级别未开启时不格式化字段
+#[macro_export]
+macro_rules! log_event {
+    ($level:expr, $target:expr, $message:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::logging::log::log_enabled!(target: $target, $level) {
+            #[allow(unused_mut)]
+            let mut line = format!("[{}] {}", $target, $message);
+            $(line.push_str(&format!(" {}={}", stringify!($key), $value));)*
+            $crate::logging::log::log!(target: $target, $level, "{line}");
+        }
+    };
+}
+
+/// 跟踪区间：创建和释放时各输出一条 Trace 日志
+pub struct Span {
+    target: &'static str,
+    name: String,
+}
+
+impl Span {
+    /// 进入区间；Trace 级别未开启时返回 None，也不会计算区间名
+    pub fn enter(target: &'static str, name: impl FnOnce() -> String) -> Option<Span> {
+        if !log::log_enabled!(target: target, Level::Trace) {
+            return None;
+        }
+        let name = name();
+        log::trace!(target: target, "[{target}] enter span={name}");
+        Some(Span { target, name })
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let target = self.target;
+        log::trace!(target: target, "[{target}] exit span={}", self.name);
+    }
+}
+
+/// 从枚举值的 Debug 输出中取出变体名，用作区间名
+pub fn variant_name(value: &impl std::fmt::Debug) -> String {
+    let debug = format!("{value:?}");
+    let end = debug
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(debug.len());
+    debug[..end].to_string()
+}
@@ -0,0 +1,273 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 大型Quiz的紧凑答案编码，客户端打包、合约解包
+
+格式：答案条数，随后每条依次为问题ID增量、答案类型和内容。问题ID按升序排列，
+第一条记录ID本身，之后记录与上一条ID之差减一；选择题答案为选项位图，
+填空题和数值题为 UTF-8 字符串，明确表示不会时内容为空。所有整数都是 LEB128 变长编码。 */
+
+use crate::{Answer, QuestionAnswer};
+
+/// 选择题答案，内容为位图字节数和位图（第 i 位为第 i 个选项）
+const TAG_CHOICES: u8 = 0;
+/// 填空题答案，内容为字节数和文本
+const TAG_TEXT: u8 = 1;
+/// 数值题答案，内容为字节数和十进制字符串
+const TAG_NUMBER: u8 = 2;
+/// 明确表示不会，内容为零字节
+const TAG_DONT_KNOW: u8 = 3;
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// 按问题ID打包答案，同一问题出现多次时返回错误
+pub fn pack_answers(answers: &[QuestionAnswer]) -> Result<Vec<u8>, String> {
+    let mut sorted: Vec<&QuestionAnswer> = answers.iter().collect();
+    sorted.sort_by_key(|entry| entry.question_id);
+    let mut out = Vec::new();
+    write_varint(&mut out, sorted.len() as u64);
+    let mut previous: Option<u32> = None;
+    for entry in sorted {
+        let delta = match previous {
+            None => entry.question_id,
+            Some(previous) if entry.question_id > previous => entry.question_id - previous - 1,
+            Some(_) => {
+                return Err(format!(
+                    "Duplicate answer for question {}",
+                    entry.question_id
+                ))
+            }
+        };
+        previous = Some(entry.question_id);
+        write_varint(&mut out, u64::from(delta));
+        match &entry.answer {
+            Answer::Choices(choices) => {
+                out.push(TAG_CHOICES);
+                let len = choices.iter().max().map_or(0, |max| *max as usize / 8 + 1);
+                let mut bitset = vec![0u8; len];
+                for choice in choices {
+                    bitset[*choice as usize / 8] |= 1 << (choice % 8);
+                }
+                write_bytes(&mut out, &bitset);
+            }
+            Answer::Text(text) => {
+                out.push(TAG_TEXT);
+                write_bytes(&mut out, text.as_bytes());
+            }
+            Answer::Number(number) => {
+                out.push(TAG_NUMBER);
+                write_bytes(&mut out, number.as_bytes());
+            }
+            Answer::DontKnow(_) => {
+                out.push(TAG_DONT_KNOW);
+                write_bytes(&mut out, &[]);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// 读取打包答案的游标
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or("Packed answers are truncated")?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Packed answers contain an overlong integer".to_string())
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = usize::try_from(self.varint()?).map_err(|_| "Packed field is too long")?;
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Packed answers are truncated")?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        String::from_utf8(self.bytes()?.to_vec())
+            .map_err(|_| "Packed answer is not valid UTF-8".to_string())
+    }
+}
+
+/// 解包答案，格式错误、问题ID溢出或有多余字节时返回错误
+pub fn unpack_answers(bytes: &[u8]) -> Result<Vec<QuestionAnswer>, String> {
+    let mut reader = Reader { bytes, position: 0 };
+    let count = reader.varint()?;
+    // 每条答案至少三个字节，据此拒绝虚报的条数
+    if count > bytes.len() as u64 / 3 {
+        return Err("Packed answer count exceeds the payload".to_string());
+    }
+    let mut answers = Vec::with_capacity(count as usize);
+    let mut previous: Option<u32> = None;
+    for _ in 0..count {
+        let delta = u32::try_from(reader.varint()?).map_err(|_| "Question ID overflow")?;
+        let question_id = match previous {
+            None => Some(delta),
+            Some(previous) => previous.checked_add(delta).and_then(|id| id.checked_add(1)),
+        }
+        .ok_or("Question ID overflow")?;
+        previous = Some(question_id);
+        let answer = match reader.byte()? {
+            TAG_CHOICES => {
+                let bitset = reader.bytes()?;
+                let mut choices = Vec::new();
+                for (index, byte) in bitset.iter().enumerate() {
+                    for bit in 0..8 {
+                        if byte & (1 << bit) != 0 {
+                            choices.push((index * 8 + bit) as u32);
+                        }
+                    }
+                }
+                Answer::Choices(choices)
+            }
+            TAG_TEXT => Answer::Text(reader.string()?),
+            TAG_NUMBER => Answer::Number(reader.string()?),
+            TAG_DONT_KNOW => {
+                if !reader.bytes()?.is_empty() {
+                    return Err("Packed \"don't know\" answer has content".to_string());
+                }
+                Answer::DontKnow(true)
+            }
+            tag => return Err(format!("Unknown packed answer type {tag}")),
+        };
+        answers.push(QuestionAnswer {
+            question_id,
+            answer,
+        });
+    }
+    if reader.position != bytes.len() {
+        return Err("Packed answers have trailing bytes".to_string());
+    }
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(question_id: u32, answer: Answer) -> QuestionAnswer {
+        QuestionAnswer {
+            question_id,
+            answer,
+        }
+    }
+
+    fn pairs(answers: &[QuestionAnswer]) -> Vec<(u32, Answer)> {
+        answers
+            .iter()
+            .map(|entry| (entry.question_id, entry.answer.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_sorts_by_question_id() {
+        let answers = vec![
+            entry(300, Answer::Number("-2.5".to_string())),
+            entry(0, Answer::Choices(vec![0, 9, 17])),
+            entry(1, Answer::Text("Paris".to_string())),
+            entry(5, Answer::DontKnow(true)),
+            entry(6, Answer::blank()),
+        ];
+        let packed = pack_answers(&answers).unwrap();
+        let mut expected = pairs(&answers);
+        expected.sort_by_key(|(question_id, _)| *question_id);
+        assert_eq!(pairs(&unpack_answers(&packed).unwrap()), expected);
+    }
+
+    #[test]
+    fn round_trip_of_no_answers() {
+        let packed = pack_answers(&[]).unwrap();
+        assert_eq!(packed, vec![0]);
+        assert!(unpack_answers(&packed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn choices_unpack_in_ascending_order_without_duplicates() {
+        let packed = pack_answers(&[entry(2, Answer::Choices(vec![8, 1, 8]))]).unwrap();
+        assert_eq!(
+            pairs(&unpack_answers(&packed).unwrap()),
+            vec![(2, Answer::Choices(vec![1, 8]))]
+        );
+    }
+
+    #[test]
+    fn pack_rejects_duplicate_questions() {
+        let answers = [entry(3, Answer::blank()), entry(3, Answer::DontKnow(true))];
+        assert!(pack_answers(&answers).is_err());
+    }
+
+    #[test]
+    fn unpack_rejects_every_truncation() {
+        let answers = [
+            entry(0, Answer::Choices(vec![2])),
+            entry(200, Answer::Text("long enough text".to_string())),
+        ];
+        let packed = pack_answers(&answers).unwrap();
+        for len in 0..packed.len() {
+            assert!(unpack_answers(&packed[..len]).is_err(), "length {len}");
+        }
+    }
+
+    #[test]
+    fn unpack_rejects_malformed_payloads() {
+        let packed = pack_answers(&[entry(0, Answer::Text("a".to_string()))]).unwrap();
+        let mut trailing = packed.clone();
+        trailing.push(0);
+        assert!(unpack_answers(&trailing).is_err());
+        // 条数超过载荷可容纳的数量
+        assert!(unpack_answers(&[5, 0, 0]).is_err());
+        // 未知答案类型
+        assert!(unpack_answers(&[1, 0, 9, 0]).is_err());
+        // 不会的答案带有内容
+        assert!(unpack_answers(&[1, 0, TAG_DONT_KNOW, 1, 0]).is_err());
+        // 非 UTF-8 文本
+        assert!(unpack_answers(&[1, 0, TAG_TEXT, 1, 0xff]).is_err());
+        // 超长的变长整数
+        assert!(unpack_answers(&[0x80; 11]).is_err());
+    }
+
+    #[test]
+    fn unpack_rejects_question_id_overflow() {
+        let mut packed = vec![2];
+        write_varint(&mut packed, u64::from(u32::MAX));
+        packed.extend([TAG_DONT_KNOW, 0]);
+        write_varint(&mut packed, 0);
+        packed.extend([TAG_DONT_KNOW, 0]);
+        assert!(unpack_answers(&packed).is_err());
+    }
+}
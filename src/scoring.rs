@@ -0,0 +1,481 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 合约与服务共用的评分逻辑 */
+
+use std::cmp::Ordering;
+
+use crate::{
+    state::{Question, QuestionResult, UserAttempt},
+    Answer, AttemptPolicy, LeaderboardEntry, QuestionKind, RoundingPolicy, ScoringStrategy,
+};
+
+/// 定点分数保留的小数位数
+pub const SCORE_DECIMALS: u32 = 3;
+/// 1分对应的定点单位数
+pub const SCORE_SCALE: FixedScore = 10i64.pow(SCORE_DECIMALS);
+
+/// 以千分之一分为单位的定点分数，允许为负以支持扣分类计分方案
+pub type FixedScore = i64;
+
+/// 把整数分值转换为定点分数
+pub fn fixed_points(points: u32) -> FixedScore {
+    FixedScore::from(points) * SCORE_SCALE
+}
+
+/// 数值题答案保留的小数位数
+pub const NUMERIC_DECIMALS: u32 = 6;
+
+/// 把十进制字符串（如 "-3.14"）解析为以 10^-NUMERIC_DECIMALS 为单位的整数
+pub fn parse_decimal(value: &str) -> Option<i128> {
+    let value = value.trim();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty())
+        || fraction.len() > NUMERIC_DECIMALS as usize
+        || !is_digits(integer)
+        || !is_digits(fraction)
+    {
+        return None;
+    }
+    let integer: i128 = if integer.is_empty() {
+        0
+    } else {
+        integer.parse().ok()?
+    };
+    let fraction: i128 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<width$}", width = NUMERIC_DECIMALS as usize)
+            .parse()
+            .ok()?
+    };
+    let magnitude = integer
+        .checked_mul(10i128.pow(NUMERIC_DECIMALS))?
+        .checked_add(fraction)?;
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+/// 填空题答案比较时忽略大小写和首尾空白
+fn normalize_text(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// 用户选择的答案是否与所有正确选项完全匹配（顺序无关）
+fn is_exact_match(question: &Question, answer: &[u32]) -> bool {
+    let mut user_answers_sorted = answer.to_vec();
+    user_answers_sorted.sort();
+    let mut correct_options_sorted = question.correct_options.clone();
+    correct_options_sorted.sort();
+    user_answers_sorted == correct_options_sorted
+}
+
+/// 按问题类型判断答案是否正确，答案类型与问题类型不符时视为错误
+pub fn is_correct(question: &Question, answer: &Answer) -> bool {
+    match (&question.kind, answer) {
+        (QuestionKind::MultipleChoice | QuestionKind::TrueFalse, Answer::Choices(choices)) => {
+            is_exact_match(question, choices)
+        }
+        (QuestionKind::FillInBlank { answers }, Answer::Text(text)) => {
+            let text = normalize_text(text);
+            answers
+                .iter()
+                .any(|accepted| normalize_text(accepted) == text)
+        }
+        (QuestionKind::Numeric { answer, tolerance }, Answer::Number(number)) => {
+            match (
+                parse_decimal(answer),
+                parse_decimal(tolerance),
+                parse_decimal(number),
+            ) {
+                (Some(answer), Some(tolerance), Some(number)) => {
+                    (number - answer).abs() <= tolerance
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// 按评分方式计算单个问题的定点得分；部分得分只适用于选择题，问答题在人工评分前记0分
+pub fn score_question(
+    question: &Question,
+    answer: &Answer,
+    strategy: ScoringStrategy,
+) -> FixedScore {
+    if matches!(question.kind, QuestionKind::Essay { .. }) {
+        return 0;
+    }
+    let full = fixed_points(question.points);
+    match (strategy, answer) {
+        (ScoringStrategy::PartialCredit, Answer::Choices(choices))
+            if question.kind == QuestionKind::MultipleChoice =>
+        {
+            if question.correct_options.is_empty() {
+                return if choices.is_empty() { full } else { 0 };
+            }
+            let mut selected = choices.to_vec();
+            selected.sort();
+            selected.dedup();
+            let correct = selected
+                .iter()
+                .filter(|option| question.correct_options.contains(option))
+                .count();
+            let wrong = selected.len() - correct;
+            let net = correct.saturating_sub(wrong) as FixedScore;
+            full * net / question.correct_options.len() as FixedScore
+        }
+        _ if is_correct(question, answer) => full,
+        (ScoringStrategy::NegativeMarking { penalty }, answer) if !answer.is_skip() => {
+            -FixedScore::from(penalty)
+        }
+        _ => 0,
+    }
+}
+
+/// 检查答案类型是否与问题类型一致，DontKnow 适用于任何问题
+pub fn check_answer_kind(question: &Question, answer: &Answer) -> Result<(), String> {
+    let matches = matches!(
+        (&question.kind, answer),
+        (_, Answer::DontKnow(_))
+            | (
+                QuestionKind::MultipleChoice | QuestionKind::TrueFalse,
+                Answer::Choices(_)
+            )
+            | (
+                QuestionKind::FillInBlank { .. } | QuestionKind::Essay { .. },
+                Answer::Text(_)
+            )
+            | (QuestionKind::Numeric { .. }, Answer::Number(_))
+    );
+    if matches {
+        Ok(())
+    } else {
+        Err(format!(
+            "Answer type does not match question {}",
+            question.id
+        ))
+    }
+}
+
+/// 按评分标准计算问答题的定点得分：每条标准按权重分配分值，并按选中等级线性给分
+pub fn rubric_score(question: &Question, levels: &[u32]) -> Result<FixedScore, String> {
+    let QuestionKind::Essay { rubric } = &question.kind else {
+        return Err(format!("Question {} is not an essay question", question.id));
+    };
+    if levels.len() != rubric.len() {
+        return Err("Level count mismatch with rubric criteria".to_string());
+    }
+    let total_weight: FixedScore = rubric
+        .iter()
+        .map(|criterion| FixedScore::from(criterion.weight))
+        .sum();
+    let full = fixed_points(question.points);
+    let mut score = 0;
+    for (criterion, level) in rubric.iter().zip(levels) {
+        let max_level = criterion.levels.len().saturating_sub(1) as FixedScore;
+        if FixedScore::from(*level) > max_level {
+            return Err(format!("Level is out of range for {}", criterion.name));
+        }
+        score += full * FixedScore::from(criterion.weight) * FixedScore::from(*level)
+            / (total_weight * max_level);
+    }
+    Ok(score)
+}
+
+/// 评分并记录单个问题的作答结果
+fn question_result(
+    question: &Question,
+    answer: &Answer,
+    strategy: ScoringStrategy,
+) -> QuestionResult {
+    let correct = is_correct(question, answer);
+    QuestionResult {
+        question_id: question.id,
+        correct,
+        // 没有正确选项的选择题留空即为答对，不算跳过
+        skipped: answer.is_skip() && !correct,
+        points_awarded: score_question(question, answer, strategy),
+        rubric_levels: Vec::new(),
+    }
+}
+
+/// 计算整份答案每个问题的结果，答案数量必须与问题数量一致
+pub fn score_answers(
+    questions: &[Question],
+    answers: &[Answer],
+    strategy: ScoringStrategy,
+) -> Result<Vec<QuestionResult>, String> {
+    if answers.len() != questions.len() {
+        return Err("Answer count mismatch with questions".to_string());
+    }
+    for (question, answer) in questions.iter().zip(answers) {
+        check_answer_kind(question, answer)?;
+    }
+    Ok(questions
+        .iter()
+        .zip(answers)
+        .map(|(question, answer)| question_result(question, answer, strategy))
+        .collect())
+}
+
+/// 计算部分作答每个问题的结果，未作答的问题记为跳过并得0分
+pub fn score_answered(
+    questions: &[Question],
+    answers: &[Option<Answer>],
+    strategy: ScoringStrategy,
+) -> Vec<QuestionResult> {
+    questions
+        .iter()
+        .zip(answers)
+        .map(|(question, answer)| match answer {
+            Some(answer) => question_result(question, answer, strategy),
+            None => QuestionResult {
+                question_id: question.id,
+                correct: false,
+                skipped: true,
+                points_awarded: 0,
+                rubric_levels: Vec::new(),
+            },
+        })
+        .collect()
+}
+
+/// 各问题得分之和
+pub fn total_score(results: &[QuestionResult]) -> FixedScore {
+    results.iter().fold(0, |total, result| {
+        total.saturating_add(result.points_awarded)
+    })
+}
+
+/// 按多次答题策略计算计入排行榜的定点分数，没有答卷时为空
+pub fn policy_score(attempts: &[UserAttempt], policy: AttemptPolicy) -> Option<FixedScore> {
+    match policy {
+        AttemptPolicy::Best => attempts.iter().map(|attempt| attempt.fixed_score).max(),
+        AttemptPolicy::Latest => attempts
+            .iter()
+            .max_by_key(|attempt| attempt.attempt_no)
+            .map(|attempt| attempt.fixed_score),
+        AttemptPolicy::Average if attempts.is_empty() => None,
+        AttemptPolicy::Average => {
+            let total = attempts.iter().fold(0, |total: FixedScore, attempt| {
+                total.saturating_add(attempt.fixed_score)
+            });
+            Some(total / attempts.len() as FixedScore)
+        }
+    }
+}
+
+/// 按多次答题策略选出提供排行榜用时和完成时间的答卷：最高分策略取最高分中用时最短、
+/// 提交最早的一份，最新和平均策略取最近一次答卷；没有答卷时为空
+pub fn policy_attempt(attempts: &[UserAttempt], policy: AttemptPolicy) -> Option<&UserAttempt> {
+    match policy {
+        AttemptPolicy::Best => attempts.iter().min_by_key(|attempt| {
+            (
+                std::cmp::Reverse(attempt.fixed_score),
+                attempt.time_taken,
+                attempt.completed_at,
+            )
+        }),
+        AttemptPolicy::Latest | AttemptPolicy::Average => {
+            attempts.iter().max_by_key(|attempt| attempt.attempt_no)
+        }
+    }
+}
+
+/// 排行榜顺序：分数高者在前，同分时用时短者在前，用时也相同时先提交者在前
+pub fn leaderboard_order(a: &LeaderboardEntry, b: &LeaderboardEntry) -> Ordering {
+    b.score
+        .cmp(&a.score)
+        .then(a.time_taken.cmp(&b.time_taken))
+        .then(a.completed_at.cmp(&b.completed_at))
+}
+
+/// 测验的满分
+pub fn max_score(questions: &[Question]) -> u32 {
+    questions
+        .iter()
+        .map(|question| question.points)
+        .fold(0u32, u32::saturating_add)
+}
+
+/// 按舍入策略把定点分数舍入到指定小数位，返回以 10^decimals 为单位的整数
+fn round_to(score: FixedScore, decimals: u32, policy: RoundingPolicy) -> i64 {
+    let step = 10i64.pow(SCORE_DECIMALS - decimals.min(SCORE_DECIMALS));
+    let quotient = score.div_euclid(step);
+    let remainder = score.rem_euclid(step);
+    let round_up = match policy {
+        RoundingPolicy::Floor => false,
+        RoundingPolicy::Ceil => remainder > 0,
+        RoundingPolicy::HalfUp => remainder * 2 >= step,
+    };
+    quotient + i64::from(round_up)
+}
+
+/// 展示用的整数分数，负分记为0
+pub fn display_score(score: FixedScore, policy: RoundingPolicy) -> u32 {
+    round_to(score, 0, policy).clamp(0, i64::from(u32::MAX)) as u32
+}
+
+/// 按精度和舍入策略把定点分数格式化为十进制字符串，例如 "2.50"
+pub fn format_score(score: FixedScore, decimals: u32, policy: RoundingPolicy) -> String {
+    let decimals = decimals.min(SCORE_DECIMALS);
+    let value = round_to(score, decimals, policy);
+    if decimals == 0 {
+        return value.to_string();
+    }
+    let unit = 10u64.pow(decimals);
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    format!(
+        "{sign}{}.{:0width$}",
+        magnitude / unit,
+        magnitude % unit,
+        width = decimals as usize
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(kind: QuestionKind, correct_options: Vec<u32>, points: u32) -> Question {
+        Question {
+            id: 0,
+            text: "Q".to_string(),
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            correct_options,
+            points,
+            kind,
+            explanation: None,
+            media: None,
+            screen_reader_hint: None,
+        }
+    }
+
+    #[test]
+    fn parse_decimal_scales_and_rejects_malformed_input() {
+        assert_eq!(parse_decimal("3"), Some(3_000_000));
+        assert_eq!(parse_decimal(" -3.14 "), Some(-3_140_000));
+        assert_eq!(parse_decimal(".5"), Some(500_000));
+        assert_eq!(parse_decimal("0.000001"), Some(1));
+        assert_eq!(parse_decimal("0.0000001"), None);
+        assert_eq!(parse_decimal(""), None);
+        assert_eq!(parse_decimal("-"), None);
+        assert_eq!(parse_decimal("1e3"), None);
+        assert_eq!(parse_decimal("1.2.3"), None);
+    }
+
+    #[test]
+    fn round_to_applies_each_policy() {
+        assert_eq!(round_to(2_500, 0, RoundingPolicy::Floor), 2);
+        assert_eq!(round_to(2_500, 0, RoundingPolicy::HalfUp), 3);
+        assert_eq!(round_to(2_499, 0, RoundingPolicy::HalfUp), 2);
+        assert_eq!(round_to(2_001, 0, RoundingPolicy::Ceil), 3);
+        assert_eq!(round_to(2_000, 0, RoundingPolicy::Ceil), 2);
+        // 负分向负无穷方向取整
+        assert_eq!(round_to(-1_500, 0, RoundingPolicy::Floor), -2);
+        assert_eq!(round_to(-1_500, 0, RoundingPolicy::HalfUp), -1);
+        assert_eq!(round_to(-1_500, 0, RoundingPolicy::Ceil), -1);
+        assert_eq!(round_to(1_234, 2, RoundingPolicy::HalfUp), 123);
+        assert_eq!(round_to(1_235, 2, RoundingPolicy::HalfUp), 124);
+        // 超过定点精度的小数位数按定点精度处理
+        assert_eq!(round_to(1_234, 9, RoundingPolicy::Floor), 1_234);
+    }
+
+    #[test]
+    fn display_score_clamps_negative_scores_to_zero() {
+        assert_eq!(display_score(-2_000, RoundingPolicy::HalfUp), 0);
+        assert_eq!(display_score(2_600, RoundingPolicy::Floor), 2);
+        assert_eq!(display_score(2_600, RoundingPolicy::HalfUp), 3);
+    }
+
+    #[test]
+    fn format_score_pads_fraction_and_keeps_sign() {
+        assert_eq!(format_score(2_500, 2, RoundingPolicy::HalfUp), "2.50");
+        assert_eq!(format_score(2_500, 0, RoundingPolicy::HalfUp), "3");
+        assert_eq!(format_score(1_005, 2, RoundingPolicy::Floor), "1.00");
+        assert_eq!(format_score(1_005, 2, RoundingPolicy::Ceil), "1.01");
+        assert_eq!(format_score(50, 3, RoundingPolicy::Floor), "0.050");
+        assert_eq!(format_score(-250, 1, RoundingPolicy::HalfUp), "-0.2");
+        assert_eq!(format_score(-1_250, 2, RoundingPolicy::Floor), "-1.25");
+        assert_eq!(format_score(7_000, 5, RoundingPolicy::Floor), "7.000");
+    }
+
+    #[test]
+    fn partial_credit_nets_wrong_choices_against_correct_ones() {
+        let question = question(QuestionKind::MultipleChoice, vec![0, 1], 3);
+        let score = |choices: Vec<u32>| {
+            score_question(
+                &question,
+                &Answer::Choices(choices),
+                ScoringStrategy::PartialCredit,
+            )
+        };
+        assert_eq!(score(vec![0, 1]), 3_000);
+        assert_eq!(score(vec![0]), 1_500);
+        assert_eq!(score(vec![0, 0]), 1_500);
+        assert_eq!(score(vec![0, 2]), 0);
+        assert_eq!(score(vec![2]), 0);
+    }
+
+    #[test]
+    fn negative_marking_penalizes_wrong_answers_but_not_skips() {
+        let question = question(QuestionKind::MultipleChoice, vec![1], 2);
+        let strategy = ScoringStrategy::NegativeMarking { penalty: 500 };
+        assert_eq!(
+            score_question(&question, &Answer::Choices(vec![1]), strategy),
+            2_000
+        );
+        assert_eq!(
+            score_question(&question, &Answer::Choices(vec![0]), strategy),
+            -500
+        );
+        assert_eq!(score_question(&question, &Answer::blank(), strategy), 0);
+        assert_eq!(
+            score_question(&question, &Answer::DontKnow(true), strategy),
+            0
+        );
+    }
+
+    #[test]
+    fn numeric_answers_match_within_tolerance() {
+        let question = question(
+            QuestionKind::Numeric {
+                answer: "3.14".to_string(),
+                tolerance: "0.01".to_string(),
+            },
+            Vec::new(),
+            1,
+        );
+        assert!(is_correct(&question, &Answer::Number("3.15".to_string())));
+        assert!(is_correct(&question, &Answer::Number("3.13".to_string())));
+        assert!(!is_correct(&question, &Answer::Number("3.16".to_string())));
+        assert!(!is_correct(&question, &Answer::Number("pi".to_string())));
+        assert!(!is_correct(&question, &Answer::Text("3.14".to_string())));
+    }
+
+    #[test]
+    fn score_answers_rejects_mismatched_answers() {
+        let questions = vec![question(QuestionKind::MultipleChoice, vec![0], 1)];
+        assert!(score_answers(&questions, &[], ScoringStrategy::ExactMatch).is_err());
+        assert!(score_answers(
+            &questions,
+            &[Answer::Text("A".to_string())],
+            ScoringStrategy::ExactMatch
+        )
+        .is_err());
+        let results = score_answers(
+            &questions,
+            &[Answer::Choices(vec![0])],
+            ScoringStrategy::ExactMatch,
+        )
+        .unwrap();
+        assert_eq!(total_score(&results), 1_000);
+    }
+}
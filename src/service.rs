@@ -1,267 +1,912 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-use async_graphql::{EmptySubscription, Request, Response, Schema};
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo,
+};
+use async_graphql::{
+    EmptySubscription, InputObject, MergedObject, OutputType, Request, Response, Schema,
+    ServerResult,
+};
 use linera_sdk::graphql::GraphQLMutationRoot;
-use linera_sdk::linera_base_types::WithServiceAbi;
-use linera_sdk::views::View;
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, TimeDelta, Timestamp, WithServiceAbi};
+use linera_sdk::views::{MapView, View};
 use linera_sdk::{Service, ServiceRuntime};
-use quiz::state::QuizState;
-use quiz::{Operation, QuestionView, QuizAttempt, QuizSetView, UserAttemptView};
-use std::sync::Arc;
+use quiz::logging::{self, Level, Span, SERVICE_TARGET};
+use quiz::scoring::{fixed_points, policy_score, FixedScore};
+use quiz::state::{CompletionStreak, Organization, QuizSet, QuizState, UserAttempt};
+use quiz::validation::validate_quiz_filter;
+use quiz::views::{attempt_view, global_standing_view, ranking_view, remote_quiz_view};
+use quiz::{
+    log_event, AttemptPolicy, AttemptSortField, BlackoutWindow, ChainMetadata, Connection, Edge,
+    GlobalStandingView, LeaderboardKind, Operation, OrgView, PageInfo, QuizAttempt, QuizFilter,
+    QuizParameters, QuizSetView, QuizSortField, RateLimitHint, RemoteQuizView, SortOrder,
+    UserAttemptView,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use attempts::AttemptQueries;
+use leaderboards::LeaderboardQueries;
+use orgs::OrgQueries;
+use platform::PlatformQueries;
+use quizzes::QuizQueries;
+use users::UserQueries;
+
+/// 未在请求 `extensions.caller` 中标识自己的调用方
+const UNKNOWN_CALLER: &str = "anonymous";
+
+/// 私密用户在公开排行榜中的显示名
+const ANONYMOUS_USER: &str = "Anonymous";
+
+#[path = "service/attempts.rs"]
+mod attempts;
+#[path = "service/leaderboards.rs"]
+mod leaderboards;
+#[path = "service/orgs.rs"]
+mod orgs;
+#[path = "service/platform.rs"]
+mod platform;
+#[path = "service/quizzes.rs"]
+mod quizzes;
+#[path = "service/users.rs"]
+mod users;
 
 linera_sdk::service!(QuizService);
 
 pub struct QuizService {
     state: Arc<QuizState>,
     runtime: Arc<ServiceRuntime<Self>>,
+    parameters: QuizParameters,
+    /// 各调用方的查询量，仅在当前服务实例内统计
+    query_windows: Mutex<HashMap<String, QueryWindow>>,
 }
 
-struct QueryRoot {
+/// 调用方在当前限流窗口内的查询量
+struct QueryWindow {
+    started_at: Timestamp,
+    count: u32,
+}
+
+/// 各查询模块共享的状态、运行时和参数
+struct QueryContext {
     state: Arc<QuizState>,
     runtime: Arc<ServiceRuntime<QuizService>>,
+    parameters: QuizParameters,
 }
 
-#[async_graphql::Object]
+/// GraphQL查询根，由各模块的查询合并而成
+#[derive(MergedObject)]
+struct QueryRoot(
+    QuizQueries,
+    OrgQueries,
+    AttemptQueries,
+    LeaderboardQueries,
+    UserQueries,
+    PlatformQueries,
+);
+
 impl QueryRoot {
-    async fn quiz_set(&self, quiz_id: u64) -> Option<QuizSetView> {
-        match self.state.quiz_sets.get(&quiz_id).await {
-            Ok(option) => option.map(|quiz| QuizSetView {
-                id: quiz.id,
-                title: quiz.title.clone(),
-                description: quiz.description.clone(),
-                creator: quiz.creator,
-                questions: quiz
-                    .questions
-                    .iter()
-                    .map(|q| QuestionView {
-                        id: q.id,
-                        text: q.text.clone(),
-                        options: q.options.clone(),
-                        points: q.points,
-                    })
-                    .collect(),
-                start_time: quiz.start_time.micros().to_string(),
-                end_time: quiz.end_time.micros().to_string(),
-                created_at: quiz.created_at.micros().to_string(),
-            }),
-            Err(_) => None,
+    fn new(context: QueryContext) -> Self {
+        let context = Arc::new(context);
+        QueryRoot(
+            QuizQueries(context.clone()),
+            OrgQueries(context.clone()),
+            AttemptQueries(context.clone()),
+            LeaderboardQueries(context.clone()),
+            UserQueries(context.clone()),
+            PlatformQueries(context.clone()),
+        )
+    }
+}
+
+/// 列表查询的分页与排序参数
+#[derive(Default, InputObject)]
+struct PageArgs {
+    offset: Option<u32>,
+    limit: Option<u32>,
+    sort: Option<SortOrder>,
+}
+
+/// 游标分页参数
+#[derive(Default, InputObject)]
+struct CursorArgs {
+    /// 上一页的 endCursor，为空时从第一页开始
+    after: Option<String>,
+    limit: Option<u32>,
+    sort: Option<SortOrder>,
+}
+
+impl CursorArgs {
+    fn page(&self) -> PageArgs {
+        PageArgs {
+            offset: None,
+            limit: self.limit,
+            sort: self.sort,
         }
     }
 
-    async fn quiz_sets(&self) -> Vec<QuizSetView> {
-        let mut quiz_sets = Vec::new();
+    fn cursor(&self) -> async_graphql::Result<Option<PageCursor>> {
+        self.after.as_deref().map(decode_cursor).transpose()
+    }
+}
+
+/// 解析后的Quiz筛选条件
+struct QuizMatcher {
+    filter: QuizFilter,
+    title: Option<String>,
+    created_after: Option<Timestamp>,
+    created_before: Option<Timestamp>,
+    now: Timestamp,
+}
+
+impl QuizMatcher {
+    fn new(filter: QuizFilter, now: Timestamp) -> async_graphql::Result<Self> {
+        let (created_after, created_before) =
+            validate_quiz_filter(&filter).map_err(|violation| violation.message)?;
+        Ok(QuizMatcher {
+            title: filter.title_contains.as_deref().map(str::to_lowercase),
+            filter,
+            created_after,
+            created_before,
+            now,
+        })
+    }
+
+    /// 只依赖Quiz本身的条件，答题人数由调用方另行检查
+    fn matches(&self, quiz: &QuizSet) -> bool {
+        let filter = &self.filter;
+        filter
+            .status
+            .is_none_or(|status| quiz.status(self.now) == status)
+            && filter.mode.is_none_or(|mode| quiz.start_mode == mode)
+            && filter.creator.is_none_or(|creator| quiz.creator == creator)
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|title| quiz.title.to_lowercase().contains(title))
+            && self
+                .created_after
+                .is_none_or(|after| quiz.created_at >= after)
+            && self
+                .created_before
+                .is_none_or(|before| quiz.created_at < before)
+    }
+}
+
+/// 列表的排序键：数值字段或文本字段
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Number(u64),
+    Text(String),
+}
+
+/// Quiz列表按指定字段排序的键，Quiz ID作为次要键使顺序稳定
+fn quiz_sort_key(sort_by: QuizSortField) -> impl Fn(&QuizSetView) -> (SortKey, u64) {
+    move |quiz| {
+        let key = match sort_by {
+            QuizSortField::Id => SortKey::Number(quiz.id),
+            QuizSortField::StartTime => SortKey::Number(quiz.start_time.micros()),
+            QuizSortField::EndTime => SortKey::Number(quiz.end_time.micros()),
+            QuizSortField::Title => SortKey::Text(quiz.title.to_lowercase()),
+        };
+        (key, quiz.id)
+    }
+}
+
+/// 答题记录按指定字段排序的键，Quiz ID和答题次序作为次要键使顺序稳定
+fn attempt_sort_key(sort_by: AttemptSortField) -> impl Fn(&QuizAttempt) -> (u64, u64, u32) {
+    move |entry| {
+        let attempt = &entry.attempt;
+        let key = match sort_by {
+            AttemptSortField::QuizId => entry.quiz_id,
+            AttemptSortField::CompletedAt => attempt.completed_at.micros(),
+            AttemptSortField::Score => u64::from(attempt.score),
+            AttemptSortField::TimeTaken => attempt.time_taken,
+        };
+        (key, entry.quiz_id, attempt.attempt_no)
+    }
+}
+
+/// 游标的内容：上一页最后一项的键及其在列表中的位置
+#[derive(Serialize, Deserialize)]
+struct PageCursor {
+    key: String,
+    position: u64,
+}
+
+impl QueryContext {
+    /// 从订阅的链收到公告的Quiz目录，可按所在链筛选，open_only 时只保留尚未结束的Quiz
+    async fn collect_remote_quizzes(
+        &self,
+        host_chain_id: Option<ChainId>,
+        open_only: bool,
+    ) -> async_graphql::Result<Vec<RemoteQuizView>> {
+        let now = self.runtime.system_time();
+        let mut quizzes = Vec::new();
+        self.state
+            .remote_quizzes
+            .for_each_index_value(|(quiz_chain_id, quiz_id), remote_quiz| {
+                let announcement = &remote_quiz.announcement;
+                if host_chain_id.is_some_and(|chain_id| chain_id != quiz_chain_id)
+                    || (open_only && now > announcement.end_time)
+                {
+                    return Ok(());
+                }
+                quizzes.push(remote_quiz_view(quiz_chain_id, quiz_id, &remote_quiz));
+                Ok(())
+            })
+            .await?;
+        Ok(quizzes)
+    }
 
+    async fn collect_quiz_sets(&self, viewer: Option<AccountOwner>) -> Vec<QuizSetView> {
+        let now = self.runtime.system_time();
+        let mut quizzes = Vec::new();
         let _ = self
             .state
             .quiz_sets
             .for_each_index_value(|_key, quiz| {
-                let quiz = quiz.into_owned();
-                let quiz_view = QuizSetView {
-                    id: quiz.id,
-                    title: quiz.title.clone(),
-                    description: quiz.description.clone(),
-                    creator: quiz.creator,
-                    questions: quiz
-                        .questions
-                        .iter()
-                        .map(|q| QuestionView {
-                            id: q.id,
-                            text: q.text.clone(),
-                            options: q.options.clone(),
-                            points: q.points,
-                        })
-                        .collect(),
-                    start_time: quiz.start_time.micros().to_string(),
-                    end_time: quiz.end_time.micros().to_string(),
-                    created_at: quiz.created_at.micros().to_string(),
-                };
-                quiz_sets.push(quiz_view);
+                quizzes.push(quiz.into_owned());
                 Ok(())
             })
             .await;
 
+        let mut quiz_sets = Vec::new();
+        for quiz in quizzes {
+            if !self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+                continue;
+            }
+            quiz_sets.push(QuizSetView::from((&quiz, now)));
+        }
+
         quiz_sets
     }
 
-    async fn user_attempts(&self, user: String) -> Vec<QuizAttempt> {
-        let mut attempts = Vec::new();
+    /// 倒排索引（标签、分类）中一个键下 viewer 可以访问的Quiz
+    async fn collect_indexed_quiz_sets(
+        &self,
+        index: &MapView<String, Vec<u64>>,
+        key: &str,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        let now = self.runtime.system_time();
+        let mut quiz_sets = Vec::new();
+        for quiz_id in index.get(key).await?.unwrap_or_default() {
+            let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await? else {
+                continue;
+            };
+            if self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+                quiz_sets.push(QuizSetView::from((&quiz, now)));
+            }
+        }
+        Ok(quiz_sets)
+    }
+
+    /// 按Quiz ID顺序读取一页可见的Quiz，返回本页及是否还有下一页。ID由 next_quiz_id
+    /// 顺序分配，按页大小批量读取，凑够所需的符合条件的Quiz后即停止；
+    /// after 为上一页最后一个Quiz的ID，min_participants 为最少的不同答题者数
+    async fn quiz_sets_page(
+        &self,
+        viewer: Option<AccountOwner>,
+        page: &PageArgs,
+        after: Option<u64>,
+        filter: impl Fn(&QuizSet) -> bool,
+        min_participants: Option<u64>,
+    ) -> async_graphql::Result<(Vec<QuizSetView>, bool)> {
+        let now = self.runtime.system_time();
+        let limit = self.page_limit(page) as usize;
+        let end = page.offset.unwrap_or(0) as usize + limit;
+        // 多读一个以判断是否还有下一页
+        let needed = end + 1;
+        let descending = page.sort.unwrap_or(self.parameters.default_sort) == SortOrder::Descending;
+        let next_quiz_id = *self.state.next_quiz_id.get();
+        let mut quiz_ids: Vec<u64> = (1..next_quiz_id)
+            .filter(|quiz_id| match after {
+                Some(after) if descending => *quiz_id < after,
+                Some(after) => *quiz_id > after,
+                None => true,
+            })
+            .collect();
+        if descending {
+            quiz_ids.reverse();
+        }
 
-        let _ = self
+        let mut quiz_sets = Vec::new();
+        for batch in quiz_ids.chunks(limit.max(1)) {
+            if quiz_sets.len() >= needed {
+                break;
+            }
+            for quiz in self
+                .state
+                .quiz_sets
+                .multi_get(batch)
+                .await?
+                .into_iter()
+                .flatten()
+            {
+                if quiz_sets.len() < needed
+                    && filter(&quiz)
+                    && self.state.can_access_quiz(&quiz, viewer.as_ref()).await
+                    && self.has_participants(quiz.id, min_participants).await?
+                {
+                    quiz_sets.push(QuizSetView::from((&quiz, now)));
+                }
+            }
+        }
+        let has_next_page = quiz_sets.len() > end;
+        Ok((self.paginate(quiz_sets, page), has_next_page))
+    }
+
+    /// 按游标读取一页可见的Quiz，游标中记录上一页最后一个Quiz的ID
+    async fn quiz_sets_cursor_page(
+        &self,
+        viewer: Option<AccountOwner>,
+        page: &CursorArgs,
+        filter: impl Fn(&QuizSet) -> bool,
+        min_participants: Option<u64>,
+    ) -> async_graphql::Result<Connection<QuizSetView>> {
+        let cursor = page.cursor()?;
+        let after = match &cursor {
+            Some(cursor) => Some(
+                cursor
+                    .key
+                    .parse::<u64>()
+                    .map_err(|_| async_graphql::Error::new("Invalid cursor"))?,
+            ),
+            None => None,
+        };
+        let (quiz_sets, has_next_page) = self
+            .quiz_sets_page(viewer, &page.page(), after, filter, min_participants)
+            .await?;
+        let start = cursor.map_or(0, |cursor| cursor.position as usize + 1);
+        Ok(connection(quiz_sets, start, has_next_page, |quiz| {
+            quiz.id.to_string()
+        }))
+    }
+
+    /// Quiz的不同答题者数是否达到 min_participants，未指定时总是满足
+    async fn has_participants(
+        &self,
+        quiz_id: u64,
+        min_participants: Option<u64>,
+    ) -> async_graphql::Result<bool> {
+        let Some(min_participants) = min_participants else {
+            return Ok(true);
+        };
+        let attempts = self
             .state
-            .user_attempts
-            .for_each_index_value(|(quiz_id, u), attempt| {
-                if u == user {
-                    let attempt = attempt.into_owned();
-                    let attempt_view = UserAttemptView {
-                        quiz_id: attempt.quiz_id,
-                        user: attempt.user,
-                        answers: attempt.answers,
-                        score: attempt.score,
-                        time_taken: attempt.time_taken,
-                        completed_at: attempt.completed_at.micros().to_string(),
-                    };
-                    attempts.push(QuizAttempt {
-                        quiz_id,
-                        attempt: attempt_view,
-                    });
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default();
+        let participants: HashSet<AccountOwner> = attempts
+            .into_iter()
+            .map(|(user, _attempt_no)| user)
+            .collect();
+        Ok(participants.len() as u64 >= min_participants)
+    }
+
+    /// 在已排好序的列表上按游标取一页；游标所指的项已不在原位置时按键重新定位
+    fn cursor_page<T>(
+        &self,
+        items: Vec<T>,
+        page: &CursorArgs,
+        key: impl Fn(&T) -> String,
+    ) -> async_graphql::Result<Connection<T>>
+    where
+        T: OutputType,
+        Edge<T>: OutputType,
+    {
+        let start = match page.cursor()? {
+            None => 0,
+            Some(cursor) => {
+                let position = cursor.position as usize;
+                if items
+                    .get(position)
+                    .is_some_and(|item| key(item) == cursor.key)
+                {
+                    position + 1
+                } else {
+                    items
+                        .iter()
+                        .position(|item| key(item) == cursor.key)
+                        .map_or(position + 1, |index| index + 1)
+                }
+            }
+        };
+        let limit = self.page_limit(&page.page()) as usize;
+        let has_next_page = items.len() > start + limit;
+        let items = items.into_iter().skip(start).take(limit).collect();
+        Ok(connection(items, start, has_next_page, key))
+    }
+
+    async fn collect_org_view(&self, organization: Organization) -> async_graphql::Result<OrgView> {
+        let mut members = Vec::new();
+        self.state
+            .org_members
+            .for_each_index(|(org, member)| {
+                if org == organization.slug {
+                    members.push(member);
                 }
                 Ok(())
             })
-            .await;
+            .await?;
+        let mut admins = Vec::new();
+        self.state
+            .org_admins
+            .for_each_index(|(org, admin)| {
+                if org == organization.slug {
+                    admins.push(admin);
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(OrgView {
+            branding: organization.branding_entries(),
+            slug: organization.slug,
+            name: organization.name,
+            owner: organization.owner,
+            admins,
+            members,
+            settings: organization.settings,
+            created_at: organization.created_at,
+        })
+    }
+
+    /// 读取用户的答题记录；私密用户的记录只对本人及对应Quiz的创建者可见
+    async fn collect_user_attempts(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> Vec<QuizAttempt> {
+        let mut user_attempts = Vec::new();
+        let index = self
+            .state
+            .user_attempt_index
+            .get(&user)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        for (quiz_id, attempt_no) in index {
+            if let Ok(Some(attempt)) = self
+                .state
+                .user_attempts
+                .get(&(quiz_id, user, attempt_no))
+                .await
+            {
+                user_attempts.push(attempt);
+            }
+        }
+
+        let now = self.runtime.system_time();
+        let mut attempts = Vec::with_capacity(user_attempts.len());
+        for attempt in user_attempts {
+            // 结果未公布时隐藏分数和及格情况
+            let visible = self.quiz_results_visible(attempt.quiz_id, now).await;
+            attempts.push(QuizAttempt {
+                quiz_id: attempt.quiz_id,
+                attempt: attempt_view(attempt, visible, &self.parameters),
+                note: None,
+            });
+        }
 
-        attempts
+        if viewer == Some(user) {
+            // 私人笔记只返回给答题者本人
+            for attempt in &mut attempts {
+                attempt.note = self
+                    .state
+                    .attempt_notes
+                    .get(&(attempt.quiz_id, user))
+                    .await
+                    .unwrap_or_default();
+            }
+            return attempts;
+        }
+        if !self.is_private(&user).await {
+            return attempts;
+        }
+        let mut visible = Vec::new();
+        for attempt in attempts {
+            if self.is_quiz_creator(attempt.quiz_id, viewer).await {
+                visible.push(attempt);
+            }
+        }
+        visible
     }
 
-    async fn leaderboard(&self) -> Vec<UserAttemptView> {
-        let mut entries = std::collections::HashMap::new();
+    async fn collect_leaderboard(&self, viewer: Option<AccountOwner>) -> Vec<UserAttemptView> {
+        let now = self.runtime.system_time();
+        // 未公布结果的测验不计入总排行榜
+        let mut policies = std::collections::HashMap::new();
+        let _ = self
+            .state
+            .quiz_sets
+            .for_each_index_value(|quiz_id, quiz| {
+                if quiz.results_visible(now) {
+                    policies.insert(quiz_id, quiz.attempt_policy);
+                }
+                Ok(())
+            })
+            .await;
 
+        let mut attempts_by_quiz = std::collections::HashMap::<_, Vec<UserAttempt>>::new();
         let _ = self
             .state
             .user_attempts
-            .for_each_index_value(|(_quiz_id, user), attempt| {
-                let attempt = attempt.into_owned();
-                let entry = entries.entry(user).or_insert((0, u64::MAX));
-                if entry.0 < u32::MAX - attempt.score {
-                    entry.0 += attempt.score;
-                } else {
-                    entry.0 = u32::MAX;
-                }
-                if attempt.time_taken < entry.1 {
-                    entry.1 = attempt.time_taken;
+            .for_each_index_value(|(quiz_id, user, _attempt_no), attempt| {
+                if policies.contains_key(&quiz_id) {
+                    attempts_by_quiz
+                        .entry((quiz_id, user))
+                        .or_default()
+                        .push(attempt.into_owned());
                 }
                 Ok(())
             })
             .await;
 
+        let mut entries = std::collections::HashMap::new();
+        for ((quiz_id, user), mut attempts) in attempts_by_quiz {
+            attempts.sort_by_key(|attempt| attempt.attempt_no);
+            // 每个Quiz按其多次答题策略计一个分数
+            let score = policy_score(&attempts, policies[&quiz_id]).unwrap_or_default();
+            let entry = entries.entry(user).or_insert((0, u64::MAX, String::new()));
+            // 以定点分数累加，避免逐份取整造成的精度损失
+            entry.0 = FixedScore::saturating_add(entry.0, score);
+            for attempt in attempts {
+                entry.2 = attempt.nick_name;
+                if attempt.time_taken < entry.1 {
+                    entry.1 = attempt.time_taken;
+                }
+            }
+        }
+
         let mut leaderboard: Vec<_> = entries
             .into_iter()
-            .map(|(user, (score, time_taken))| UserAttemptView {
-                quiz_id: 0,
-                user,
-                answers: Vec::new(),
-                score,
-                time_taken,
-                completed_at: self.runtime.system_time().micros().to_string(),
+            .map(|(user, (score, time_taken, nick_name))| {
+                ranking_view(user, nick_name, score, time_taken, now, &self.parameters)
             })
             .collect();
         leaderboard.sort_by(|a, b| b.score.cmp(&a.score).then(a.time_taken.cmp(&b.time_taken)));
-        leaderboard
+        self.anonymize(leaderboard, viewer).await
     }
 
-    async fn quiz_leaderboard(&self, quiz_id: u64) -> Vec<UserAttemptView> {
-        let mut entries = std::collections::HashMap::new();
-
-        let _ = self
-            .state
-            .user_attempts
-            .for_each_index_value(|(q_id, user), attempt| {
-                if q_id == quiz_id {
-                    let attempt = attempt.into_owned();
-                    let entry = entries.entry(user).or_insert((0, u64::MAX, String::new()));
-                    if attempt.score > entry.0
-                        || (attempt.score == entry.0 && attempt.time_taken < entry.1)
-                    {
-                        entry.0 = attempt.score;
-                        entry.1 = attempt.time_taken;
-                        entry.2 = attempt.completed_at.micros().to_string();
-                    }
+    /// 按衰减到今天的积分排名的全局排行榜，积分已衰减为零的用户不列出
+    async fn collect_decayed_leaderboard(
+        &self,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<UserAttemptView>> {
+        let today = CompletionStreak::day_of(self.runtime.system_time());
+        let half_life_days = self.parameters.ranking_half_life_days;
+        let mut rows = Vec::new();
+        self.state
+            .decayed_ranking
+            .for_each_index_value(|user, ranking| {
+                let points = ranking.points_at(today, half_life_days);
+                if points > 0 {
+                    rows.push((points, user, ranking.into_owned()));
                 }
                 Ok(())
             })
-            .await;
-
-        let mut leaderboard: Vec<_> = entries
+            .await?;
+        rows.sort_by(|a, b| b.0.cmp(&a.0));
+        let leaderboard = rows
             .into_iter()
-            .map(
-                |(user, (score, time_taken, completed_at))| UserAttemptView {
-                    quiz_id,
+            .map(|(points, user, ranking)| {
+                ranking_view(
                     user,
-                    answers: Vec::new(),
-                    score,
-                    time_taken,
-                    completed_at: completed_at,
-                },
-            )
+                    ranking.nick_name,
+                    points,
+                    0,
+                    ranking.updated_at,
+                    &self.parameters,
+                )
+            })
             .collect();
-        leaderboard.sort_by(|a, b| b.score.cmp(&a.score).then(a.time_taken.cmp(&b.time_taken)));
-        leaderboard
+        Ok(self.anonymize(leaderboard, viewer).await)
     }
 
-    async fn user_participations(&self, user: String) -> Vec<u64> {
-        match self.state.user_participations.get(&user).await {
-            Ok(Some(v)) => v,
-            Ok(None) => Vec::default(),
-            Err(_) => Vec::default(),
+    /// 跨Quiz累计排行榜：按累计积分、再按计入的Quiz数排名，积分为零的用户不列出
+    async fn collect_global_standings(
+        &self,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<GlobalStandingView>> {
+        let mut rows = Vec::new();
+        self.state
+            .global_standings
+            .for_each_index_value(|user, standing| {
+                if standing.points > 0 {
+                    rows.push((user, standing.into_owned()));
+                }
+                Ok(())
+            })
+            .await?;
+        rows.sort_by(|a, b| {
+            b.1.points
+                .cmp(&a.1.points)
+                .then(b.1.quizzes_completed.cmp(&a.1.quizzes_completed))
+        });
+        let mut standings = Vec::with_capacity(rows.len());
+        for (index, (user, standing)) in rows.into_iter().enumerate() {
+            let mut view = global_standing_view(index as u32 + 1, user, standing, &self.parameters);
+            if viewer != Some(user) && self.is_private(&user).await {
+                view.user = None;
+                view.nick_name = ANONYMOUS_USER.to_string();
+            }
+            standings.push(view);
         }
+        Ok(standings)
     }
-    async fn get_user_created_quizzes(&self, nickname: String) -> Vec<QuizSetView> {
-        let mut created_quizzes = Vec::new();
-        let _ = self
-            .state
-            .quiz_sets
-            .for_each_index_value(|_key, quiz| {
-                let quiz = quiz.into_owned();
-                if quiz.creator == nickname {
-                    created_quizzes.push(QuizSetView {
-                        id: quiz.id,
-                        title: quiz.title.clone(),
-                        description: quiz.description.clone(),
-                        creator: quiz.creator,
-                        questions: quiz
-                            .questions
+
+    async fn collect_quiz_leaderboard(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+        kind: LeaderboardKind,
+    ) -> Vec<UserAttemptView> {
+        let now = self.runtime.system_time();
+        let Ok(Some(quiz)) = self.state.quiz_sets.get(&quiz_id).await else {
+            return Vec::new();
+        };
+        if !quiz.results_visible(now) {
+            return Vec::new();
+        }
+        // 满分按答卷实际作答的问题计算，题库模式下每份答卷可能不同
+        let perfect_score = |attempt: &UserAttempt| -> FixedScore {
+            attempt
+                .question_results
+                .iter()
+                .filter_map(|result| quiz.questions.get(result.question_id as usize))
+                .map(|question| fixed_points(question.points))
+                .sum()
+        };
+
+        let mut attempts_by_user = std::collections::HashMap::<_, Vec<UserAttempt>>::new();
+        for attempt in self.state.quiz_attempts(quiz_id).await.unwrap_or_default() {
+            attempts_by_user
+                .entry(attempt.user)
+                .or_default()
+                .push(attempt);
+        }
+
+        // 每个用户取该榜单指标最好的一次答题，指标相同时用时短者优先
+        let mut rows = Vec::new();
+        for mut attempts in attempts_by_user.into_values() {
+            attempts.sort_by_key(|attempt| attempt.attempt_no);
+            if kind == LeaderboardKind::Score && quiz.attempt_policy != AttemptPolicy::Best {
+                // 分数榜按Quiz的多次答题策略计分，展示最近一次答题
+                let score = policy_score(&attempts, quiz.attempt_policy).unwrap_or_default();
+                let mut latest = attempts.pop().expect("User has at least one attempt");
+                latest.fixed_score = score;
+                rows.push((score, latest));
+                continue;
+            }
+            let first_score = attempts[0].fixed_score;
+            let best = attempts
+                .into_iter()
+                .filter_map(|attempt| {
+                    let metric = match kind {
+                        LeaderboardKind::Score | LeaderboardKind::MostImproved => {
+                            attempt.fixed_score
+                        }
+                        LeaderboardKind::Speed
+                            if attempt.fixed_score >= perfect_score(&attempt) =>
+                        {
+                            0
+                        }
+                        LeaderboardKind::Speed => return None,
+                        LeaderboardKind::Accuracy => attempt
+                            .question_results
                             .iter()
-                            .map(|q| QuestionView {
-                                id: q.id,
-                                text: q.text.clone(),
-                                options: q.options.clone(),
-                                points: q.points,
-                            })
-                            .collect(),
-                        start_time: quiz.start_time.micros().to_string(),
-                        end_time: quiz.end_time.micros().to_string(),
-                        created_at: quiz.created_at.micros().to_string(),
-                    });
-                }
-                Ok(())
+                            .filter(|result| result.correct)
+                            .count()
+                            as FixedScore,
+                    };
+                    Some((metric, attempt))
+                })
+                .min_by(|a, b| {
+                    b.0.cmp(&a.0)
+                        .then(a.1.time_taken.cmp(&b.1.time_taken))
+                        .then(a.1.completed_at.cmp(&b.1.completed_at))
+                });
+            if let Some((metric, attempt)) = best {
+                let metric = match kind {
+                    LeaderboardKind::MostImproved => metric - first_score,
+                    _ => metric,
+                };
+                rows.push((metric, attempt));
+            }
+        }
+        rows.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(a.1.time_taken.cmp(&b.1.time_taken))
+                .then(a.1.completed_at.cmp(&b.1.completed_at))
+        });
+
+        let leaderboard = rows
+            .into_iter()
+            .map(|(_, attempt)| UserAttemptView {
+                quiz_id,
+                attempt_no: attempt.attempt_no,
+                ..ranking_view(
+                    attempt.user,
+                    attempt.nick_name,
+                    attempt.fixed_score,
+                    attempt.time_taken,
+                    attempt.completed_at,
+                    &self.parameters,
+                )
             })
-            .await;
-        created_quizzes
+            .collect();
+        if self.is_quiz_creator(quiz_id, viewer).await {
+            return leaderboard;
+        }
+        self.anonymize(leaderboard, viewer).await
     }
 
-    async fn get_user_participated_quizzes(&self, nickname: String) -> Vec<QuizSetView> {
-        let mut participated_quizzes = Vec::new();
-        let quiz_ids = self
-            .state
-            .user_participations
-            .get(&nickname)
+    /// 指定Quiz的分数和排行榜当前是否可见
+    async fn quiz_results_visible(&self, quiz_id: u64, now: Timestamp) -> bool {
+        match self.state.quiz_sets.get(&quiz_id).await {
+            Ok(Some(quiz)) => quiz.results_visible(now),
+            _ => true,
+        }
+    }
+
+    /// 用户是否开启了答题隐私
+    async fn is_private(&self, user: &AccountOwner) -> bool {
+        self.state
+            .private_users
+            .contains(user)
             .await
-            .unwrap()
-            .unwrap_or_default();
-        for &quiz_id in &quiz_ids {
-            if let Some(quiz_set) = self.state.quiz_sets.get(&quiz_id).await.unwrap() {
-                participated_quizzes.push(QuizSetView {
-                    id: quiz_set.id,
-                    title: quiz_set.title.clone(),
-                    description: quiz_set.description.clone(),
-                    creator: quiz_set.creator.clone(),
-                    questions: quiz_set
-                        .questions
-                        .iter()
-                        .map(|q| QuestionView {
-                            id: q.id,
-                            text: q.text.clone(),
-                            options: q.options.clone(),
-                            points: q.points,
-                        })
-                        .collect(),
-                    start_time: quiz_set.start_time.micros().to_string(),
-                    end_time: quiz_set.end_time.micros().to_string(),
-                    created_at: quiz_set.created_at.micros().to_string(),
-                });
+            .unwrap_or(false)
+    }
+
+    /// 查看者是否为指定Quiz的创建者
+    async fn is_quiz_creator(&self, quiz_id: u64, viewer: Option<AccountOwner>) -> bool {
+        let Some(viewer) = viewer else {
+            return false;
+        };
+        matches!(
+            self.state.quiz_sets.get(&quiz_id).await,
+            Ok(Some(quiz)) if quiz.creator == viewer
+        )
+    }
+
+    /// 将私密用户在排行榜中替换为匿名显示（查看者本人除外）
+    async fn anonymize(
+        &self,
+        mut entries: Vec<UserAttemptView>,
+        viewer: Option<AccountOwner>,
+    ) -> Vec<UserAttemptView> {
+        for entry in &mut entries {
+            let Some(user) = entry.user else {
+                continue;
+            };
+            if viewer != Some(user) && self.is_private(&user).await {
+                entry.user = None;
+                entry.nick_name = ANONYMOUS_USER.to_string();
             }
         }
-        participated_quizzes
+        entries
+    }
+
+    /// 按参数中的默认值与上限截取一页结果
+    fn paginate<T>(&self, items: Vec<T>, page: &PageArgs) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(page.offset.unwrap_or(0) as usize)
+            .take(self.page_limit(page) as usize)
+            .collect()
+    }
+
+    /// 页大小，未指定时使用默认值，且不超过参数中的上限
+    fn page_limit(&self, page: &PageArgs) -> u32 {
+        page.limit
+            .unwrap_or(self.parameters.default_page_size)
+            .min(self.parameters.max_page_size)
+    }
+
+    /// 按键对列表稳定排序，未指定方向时使用参数中的默认排序
+    fn sort_items<T, K: Ord>(
+        &self,
+        mut items: Vec<T>,
+        page: &PageArgs,
+        key: impl FnMut(&T) -> K,
+    ) -> Vec<T> {
+        items.sort_by_key(key);
+        if page.sort.unwrap_or(self.parameters.default_sort) == SortOrder::Descending {
+            items.reverse();
+        }
+        items
+    }
+
+    /// 包含指定时间点的平台禁排时段
+    async fn blackout_windows_at(&self, time: Timestamp) -> Vec<(u64, BlackoutWindow)> {
+        self.state
+            .blackout_window_list()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, window)| window.contains(time))
+            .collect()
+    }
+
+    fn metadata(&self) -> ChainMetadata {
+        ChainMetadata {
+            chain_id: self.runtime.chain_id().to_string(),
+            application_id: self.runtime.application_id().forget_abi().to_string(),
+            next_block_height: self.runtime.next_block_height().0,
+            timestamp: self.runtime.system_time(),
+        }
+    }
+}
+
+/// 由本页的项构造游标分页结果，start 为本页第一项在列表中的位置
+fn connection<T>(
+    items: Vec<T>,
+    start: usize,
+    has_next_page: bool,
+    key: impl Fn(&T) -> String,
+) -> Connection<T>
+where
+    T: OutputType,
+    Edge<T>: OutputType,
+{
+    let edges: Vec<Edge<T>> = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| Edge {
+            cursor: encode_cursor(&PageCursor {
+                key: key(&node),
+                position: (start + index) as u64,
+            }),
+            node,
+        })
+        .collect();
+    let end_cursor = edges.last().map(|edge| edge.cursor.clone());
+    Connection {
+        edges,
+        page_info: PageInfo {
+            end_cursor,
+            has_next_page,
+        },
+    }
+}
+
+/// 游标编码为JSON的十六进制串，客户端应将其视为不透明值
+fn encode_cursor(cursor: &PageCursor) -> String {
+    serde_json::to_vec(cursor)
+        .expect("Failed to serialize cursor")
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> async_graphql::Result<PageCursor> {
+    let invalid = || async_graphql::Error::new("Invalid cursor");
+    if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+        return Err(invalid());
     }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+/// 远程Quiz目录的排序键：所在链和Quiz ID
+fn remote_quiz_key(quiz: &RemoteQuizView) -> (ChainId, u64) {
+    (quiz.host_chain_id, quiz.quiz_id)
+}
+
+/// 排行榜条目的游标键：Quiz、答题者（匿名时为昵称）和答题次序
+fn leaderboard_key(entry: &UserAttemptView) -> String {
+    let user = entry
+        .user
+        .map_or_else(|| entry.nick_name.clone(), |user| user.to_string());
+    format!("{}:{}:{}", entry.quiz_id, user, entry.attempt_no)
 }
 
 impl WithServiceAbi for QuizService {
@@ -269,28 +914,106 @@ impl WithServiceAbi for QuizService {
 }
 
 impl Service for QuizService {
-    type Parameters = ();
+    type Parameters = QuizParameters;
 
     async fn new(runtime: ServiceRuntime<Self>) -> Self {
         let state = QuizState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load QuizState");
+        let parameters = runtime.application_parameters();
+        logging::init(parameters.log_level);
         QuizService {
             state: Arc::new(state),
             runtime: Arc::new(runtime),
+            parameters,
+            query_windows: Mutex::new(HashMap::new()),
         }
     }
 
     async fn handle_query(&self, request: Request) -> Response {
         let schema = Schema::build(
-            QueryRoot {
+            QueryRoot::new(QueryContext {
                 state: self.state.clone(),
                 runtime: self.runtime.clone(),
-            },
+                parameters: self.parameters.clone(),
+            }),
             Operation::mutation_root(self.runtime.clone()),
             EmptySubscription,
         )
+        .extension(ResolverSpans)
         .finish();
-        schema.execute(request).await
+        let caller = match request.extensions.get("caller") {
+            Some(async_graphql::Value::String(caller)) => caller.clone(),
+            _ => UNKNOWN_CALLER.to_string(),
+        };
+        log_event!(
+            Level::Debug,
+            SERVICE_TARGET,
+            "query received",
+            caller = caller,
+            operation = request.operation_name.as_deref().unwrap_or("-"),
+        );
+        let hint = self.record_query(caller);
+        let mut response = schema.execute(request).await;
+        if let Ok(hint) = async_graphql::Value::from_json(serde_json::json!(hint)) {
+            response.extensions.insert("rateLimit".to_string(), hint);
+        }
+        response
+    }
+}
+
+/// 在每个顶层查询字段的解析前后输出区间日志（仅 Trace 级别）
+struct ResolverSpans;
+
+impl ExtensionFactory for ResolverSpans {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ResolverSpans)
+    }
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for ResolverSpans {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<async_graphql::Value>> {
+        let _span = if info.path_node.parent.is_none() {
+            Span::enter(SERVICE_TARGET, || info.name.to_string())
+        } else {
+            None
+        };
+        next.run(ctx, info).await
+    }
+}
+
+impl QuizService {
+    /// 统计调用方的查询量并返回建议性限流信息
+    fn record_query(&self, caller: String) -> RateLimitHint {
+        let now = self.runtime.system_time();
+        let window_length = TimeDelta::from_secs(self.parameters.rate_limit_window_secs);
+        let mut windows = self
+            .query_windows
+            .lock()
+            .expect("Query window lock poisoned");
+        // 调用方标识由请求自报，清理已过期的窗口以免记录无限增长
+        windows.retain(|_, window| now.delta_since(window.started_at) < window_length);
+        let window = windows.entry(caller).or_insert(QueryWindow {
+            started_at: now,
+            count: 0,
+        });
+        if now.delta_since(window.started_at) >= window_length {
+            window.started_at = now;
+            window.count = 0;
+        }
+        window.count = window.count.saturating_add(1);
+
+        let limit = self.parameters.rate_limit_queries;
+        RateLimitHint {
+            limit,
+            remaining: limit.saturating_sub(window.count),
+            reset: window.started_at.saturating_add(window_length),
+        }
     }
 }
@@ -0,0 +1,384 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 答卷的查询：答题记录、答卷详情、草稿、证书和导出 */
+
+use linera_sdk::linera_base_types::{AccountOwner, CryptoHash};
+use quiz::views::{attempt_view, rubric_scores};
+use quiz::{
+    AttemptDetail, AttemptHistoryEntry, AttemptHistoryExport, AttemptSortField, BookmarkedQuestion,
+    CertificateView, Connection, Envelope, ExportFormat, QuestionResultView, QuestionReview,
+    QuizAttempt, SavedAnswer, UserAttemptView,
+};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{attempt_sort_key, CursorArgs, PageArgs, QueryContext};
+
+/// 答卷相关查询
+pub(super) struct AttemptQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for AttemptQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl AttemptQueries {
+    async fn user_attempts(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: AttemptSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> Vec<QuizAttempt> {
+        let attempts = self.collect_user_attempts(user, viewer).await;
+        let attempts = self.sort_items(attempts, &page, attempt_sort_key(sort_by));
+        self.paginate(attempts, &page)
+    }
+
+    /// 游标分页的 user_attempts
+    async fn user_attempts_connection(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: AttemptSortField,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<QuizAttempt>> {
+        let attempts = self.collect_user_attempts(user, viewer).await;
+        let attempts = self.sort_items(attempts, &page.page(), attempt_sort_key(sort_by));
+        self.cursor_page(attempts, &page, |attempt| {
+            format!("{}:{}", attempt.quiz_id, attempt.attempt.attempt_no)
+        })
+    }
+
+    /// 测验结束后查看答卷每个问题的对错和得分，attempt_no 默认最近一次答题；私密用户只对本人可见
+    async fn attempt_detail(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+        attempt_no: Option<u32>,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Option<AttemptDetail>> {
+        if viewer != Some(user) && self.is_private(&user).await {
+            return Err("This user's attempts are private".into());
+        }
+        let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await? else {
+            return Ok(None);
+        };
+        let now = self.runtime.system_time();
+        if now <= quiz.end_time {
+            return Err("Attempt details are available after the quiz has ended".into());
+        }
+        if !quiz.results_visible(now) {
+            return Err("Quiz results have not been revealed yet".into());
+        }
+        let attempts = self.state.user_quiz_attempts(quiz_id, &user).await?;
+        let attempt = match attempt_no {
+            Some(attempt_no) => attempts
+                .into_iter()
+                .find(|attempt| attempt.attempt_no == attempt_no),
+            None => attempts.into_iter().next_back(),
+        };
+        let Some(attempt) = attempt else {
+            return Ok(None);
+        };
+        Ok(Some(AttemptDetail {
+            quiz_id,
+            user,
+            attempt_no: attempt.attempt_no,
+            score: attempt.score,
+            precise_score: self.parameters.format_score(attempt.fixed_score),
+            results: attempt
+                .question_results
+                .into_iter()
+                .enumerate()
+                .map(|(index, result)| QuestionResultView {
+                    question_id: result.question_id,
+                    correct: result.correct,
+                    skipped: result.skipped,
+                    points_awarded: self.parameters.format_score(result.points_awarded),
+                    answer_changes: attempt
+                        .answer_changes
+                        .get(index)
+                        .copied()
+                        .unwrap_or_default(),
+                    rubric: quiz
+                        .questions
+                        .get(result.question_id as usize)
+                        .map(|question| rubric_scores(question, &result.rubric_levels))
+                        .unwrap_or_default(),
+                })
+                .collect(),
+        }))
+    }
+
+    /// 用户在进行中的答题会话里看到的问题顺序
+    async fn question_order(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+    ) -> async_graphql::Result<Vec<u32>> {
+        if !self.state.quiz_sets.contains_key(&quiz_id).await? {
+            return Err("QuizSet not found".into());
+        }
+        let session = self
+            .state
+            .attempt_sessions
+            .get(&(quiz_id, user))
+            .await?
+            .ok_or("Attempt has not started yet")?;
+        Ok(session.question_order)
+    }
+
+    /// 进行中的答题会话里已保存的答案，按答题者看到的顺序排列，用于断线后继续答题
+    async fn saved_answers(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+    ) -> async_graphql::Result<Vec<SavedAnswer>> {
+        let session = self
+            .state
+            .attempt_sessions
+            .get(&(quiz_id, user))
+            .await?
+            .ok_or("Attempt has not started yet")?;
+        Ok(session
+            .question_order
+            .iter()
+            .filter_map(|&question_id| {
+                let answer = session.answers.get(question_id as usize)?.clone()?;
+                Some(SavedAnswer {
+                    question_id,
+                    answer: answer.into(),
+                })
+            })
+            .collect())
+    }
+
+    /// 用户在各Quiz中收藏的问题，按收藏时间排序；结果尚未公布或尚不能回顾的问题暂不显示
+    async fn bookmarked_questions(
+        &self,
+        address: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<BookmarkedQuestion>> {
+        if viewer != Some(address) && self.is_private(&address).await {
+            return Err("This user's bookmarks are private".into());
+        }
+        let mut bookmarks = Vec::new();
+        let index = self
+            .state
+            .user_bookmark_index
+            .get(&address)
+            .await?
+            .unwrap_or_default();
+        for (quiz_id, question_id) in index {
+            let key = (address, quiz_id, question_id);
+            if let Some(bookmarked_at) = self.state.bookmarks.get(&key).await? {
+                bookmarks.push((quiz_id, question_id, bookmarked_at));
+            }
+        }
+        bookmarks.sort_by_key(|bookmark| bookmark.2);
+
+        let now = self.runtime.system_time();
+        let mut questions = Vec::new();
+        for (quiz_id, question_id, bookmarked_at) in bookmarks {
+            let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await? else {
+                continue;
+            };
+            if !quiz.results_visible(now) {
+                continue;
+            }
+            let reviewable = self
+                .state
+                .reviewable_questions(&quiz, &address, now)
+                .await?
+                .is_some_and(|reviewable| reviewable.contains(&question_id));
+            if !reviewable {
+                continue;
+            }
+            if let Some(question) = quiz.questions.get(question_id as usize) {
+                questions.push(BookmarkedQuestion {
+                    quiz_id,
+                    quiz_title: quiz.title.clone(),
+                    bookmarked_at,
+                    question: QuestionReview::from(question.clone()),
+                });
+            }
+        }
+        Ok(questions)
+    }
+
+    /// 等待监考人确认的答卷
+    async fn pending_attempts(&self, quiz_id: u64) -> Vec<UserAttemptView> {
+        let visible = self
+            .quiz_results_visible(quiz_id, self.runtime.system_time())
+            .await;
+        self.state
+            .quiz_pending_attempts(quiz_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|attempt| attempt_view(attempt, visible, &self.parameters))
+            .collect()
+    }
+
+    /// 用户在Quiz上分数最高的及格答卷的证书记录，没有及格答卷或结果尚未公布时为空；
+    /// 私密用户只对本人可见
+    async fn certificate(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Option<CertificateView>> {
+        if viewer != Some(user) && self.is_private(&user).await {
+            return Err("This user's attempts are private".into());
+        }
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        let Some(pass_score) = quiz.pass_score else {
+            return Ok(None);
+        };
+        if !quiz.results_visible(self.runtime.system_time()) {
+            return Ok(None);
+        }
+        let best = self
+            .state
+            .user_quiz_attempts(quiz_id, &user)
+            .await?
+            .into_iter()
+            .filter(|attempt| attempt.passed == Some(true))
+            .max_by(|a, b| {
+                a.fixed_score
+                    .cmp(&b.fixed_score)
+                    .then(b.completed_at.cmp(&a.completed_at))
+            });
+        Ok(best.map(|attempt| CertificateView {
+            quiz_id,
+            quiz_title: quiz.title,
+            user,
+            attempt_no: attempt.attempt_no,
+            score: attempt.score,
+            pass_score,
+            completed_at: attempt.completed_at,
+            chain_id: self.runtime.chain_id(),
+            receipt: attempt.receipt,
+        }))
+    }
+
+    /// 按提交回执ID查找答卷（包括等待监考确认的答卷）；私密用户只对本人可见
+    async fn attempt_by_receipt(
+        &self,
+        receipt: CryptoHash,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Option<UserAttemptView>> {
+        let Some(key) = self.state.attempt_receipts.get(&receipt).await? else {
+            return Ok(None);
+        };
+        if viewer != Some(key.1) && self.is_private(&key.1).await {
+            return Err("This user's attempts are private".into());
+        }
+        let attempt = match self.state.user_attempts.get(&key).await? {
+            Some(attempt) => Some(attempt),
+            None => self.state.pending_attempts.get(&(key.0, key.1)).await?,
+        }
+        .filter(|attempt| attempt.receipt == receipt);
+        let visible = self
+            .quiz_results_visible(key.0, self.runtime.system_time())
+            .await;
+        Ok(attempt.map(|attempt| attempt_view(attempt, visible, &self.parameters)))
+    }
+
+    /// 导出用户完整的答题记录（含待确认答卷和私人笔记），返回单个JSON文档；只有用户本人可以导出
+    async fn export_my_attempts(
+        &self,
+        address: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] format: ExportFormat,
+    ) -> async_graphql::Result<String> {
+        if viewer != Some(address) {
+            return Err("Attempt history can only be exported by its owner".into());
+        }
+        let mut entries: Vec<AttemptHistoryEntry> = self
+            .collect_user_attempts(address, Some(address))
+            .await
+            .into_iter()
+            .map(|attempt| AttemptHistoryEntry {
+                quiz_id: attempt.quiz_id,
+                quiz_title: None,
+                attempt: attempt.attempt,
+                note: attempt.note,
+                pending_confirmation: false,
+            })
+            .collect();
+
+        let mut pending = Vec::new();
+        self.state
+            .pending_attempts
+            .for_each_index_value(|(_, user), attempt| {
+                if user == address {
+                    pending.push(attempt.into_owned());
+                }
+                Ok(())
+            })
+            .await?;
+        // 待确认答卷的分数在确认前不公开
+        for attempt in pending {
+            entries.push(AttemptHistoryEntry {
+                quiz_id: attempt.quiz_id,
+                quiz_title: None,
+                note: self
+                    .state
+                    .attempt_notes
+                    .get(&(attempt.quiz_id, address))
+                    .await?,
+                attempt: attempt_view(attempt, false, &self.parameters),
+                pending_confirmation: true,
+            });
+        }
+
+        for entry in &mut entries {
+            entry.quiz_title = self
+                .state
+                .quiz_sets
+                .get(&entry.quiz_id)
+                .await?
+                .map(|quiz| quiz.title);
+        }
+        entries.sort_by_key(|entry| entry.quiz_id);
+
+        let document = AttemptHistoryExport {
+            user: address,
+            metadata: self.metadata(),
+            attempts: entries,
+        };
+        let json = match format {
+            ExportFormat::Json => serde_json::to_string(&document)?,
+            ExportFormat::PrettyJson => serde_json::to_string_pretty(&document)?,
+        };
+        Ok(json)
+    }
+
+    /// 附带链元数据的 user_attempts
+    async fn user_attempts_envelope(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: AttemptSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> Envelope<QuizAttempt> {
+        let attempts = self.collect_user_attempts(user, viewer).await;
+        let attempts = self.sort_items(attempts, &page, attempt_sort_key(sort_by));
+        Envelope {
+            metadata: self.metadata(),
+            data: self.paginate(attempts, &page),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 排行榜的查询：全局排行榜、Quiz排行榜和快照 */
+
+use linera_sdk::linera_base_types::AccountOwner;
+use quiz::{
+    Connection, Envelope, GlobalStandingView, LeaderboardKind, LeaderboardRank,
+    LeaderboardSnapshotEntry, LeaderboardSnapshotView, LeaderboardWindow, UserAttemptView,
+};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{leaderboard_key, CursorArgs, PageArgs, QueryContext, ANONYMOUS_USER};
+
+/// 排行榜相关查询
+pub(super) struct LeaderboardQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for LeaderboardQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl LeaderboardQueries {
+    async fn leaderboard(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> Vec<UserAttemptView> {
+        let leaderboard = self.collect_leaderboard(viewer).await;
+        self.paginate(leaderboard, &page)
+    }
+
+    /// 游标分页的 leaderboard
+    async fn leaderboard_connection(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<UserAttemptView>> {
+        let leaderboard = self.collect_leaderboard(viewer).await;
+        self.cursor_page(leaderboard, &page, leaderboard_key)
+    }
+
+    /// 按时间衰减的全局排行榜，较早的成绩每经过一个半衰期减半
+    async fn decayed_leaderboard(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<UserAttemptView>> {
+        let leaderboard = self.collect_decayed_leaderboard(viewer).await?;
+        Ok(self.paginate(leaderboard, &page))
+    }
+
+    /// 游标分页的 decayed_leaderboard
+    async fn decayed_leaderboard_connection(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<UserAttemptView>> {
+        let leaderboard = self.collect_decayed_leaderboard(viewer).await?;
+        self.cursor_page(leaderboard, &page, leaderboard_key)
+    }
+
+    /// 跨Quiz的累计排行榜，按累计积分、再按计入的Quiz数排名，由合约增量维护
+    async fn global_leaderboard(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<GlobalStandingView>> {
+        let standings = self.collect_global_standings(viewer).await?;
+        Ok(self.paginate(standings, &page))
+    }
+
+    /// 用户在跨Quiz累计排行榜上的名次，没有积分时为空；私密用户只对本人可见
+    async fn global_rank(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Option<GlobalStandingView>> {
+        if viewer != Some(user) && self.is_private(&user).await {
+            return Err("This user's rank is private".into());
+        }
+        Ok(self
+            .collect_global_standings(viewer)
+            .await?
+            .into_iter()
+            .find(|standing| standing.user == Some(user)))
+    }
+
+    /// Quiz排行榜，kind 选择排名方式，默认按分数
+    async fn quiz_leaderboard(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] kind: LeaderboardKind,
+        #[graphql(default)] page: PageArgs,
+    ) -> Vec<UserAttemptView> {
+        let leaderboard = self.collect_quiz_leaderboard(quiz_id, viewer, kind).await;
+        self.paginate(leaderboard, &page)
+    }
+
+    /// 用户在Quiz排行榜上的名次和总人数，用户不在榜上时为空
+    async fn my_rank(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+        #[graphql(default)] kind: LeaderboardKind,
+    ) -> Option<LeaderboardRank> {
+        let mut leaderboard = self
+            .collect_quiz_leaderboard(quiz_id, Some(user), kind)
+            .await;
+        let total = leaderboard.len() as u32;
+        let index = leaderboard
+            .iter()
+            .position(|entry| entry.user == Some(user))?;
+        Some(LeaderboardRank {
+            quiz_id,
+            rank: index as u32 + 1,
+            total,
+            entry: leaderboard.swap_remove(index),
+        })
+    }
+
+    /// 用户名次上下各 radius 名（不超过每页上限）的排行榜片段，用户不在榜上时为空
+    async fn leaderboard_window(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+        radius: u32,
+        #[graphql(default)] kind: LeaderboardKind,
+    ) -> Option<LeaderboardWindow> {
+        let leaderboard = self
+            .collect_quiz_leaderboard(quiz_id, Some(user), kind)
+            .await;
+        let total = leaderboard.len() as u32;
+        let index = leaderboard
+            .iter()
+            .position(|entry| entry.user == Some(user))?;
+        let radius = radius.min(self.parameters.max_page_size) as usize;
+        let start = index.saturating_sub(radius);
+        let end = index.saturating_add(radius + 1).min(leaderboard.len());
+        Some(LeaderboardWindow {
+            quiz_id,
+            rank: index as u32 + 1,
+            total,
+            first_rank: start as u32 + 1,
+            entries: leaderboard
+                .into_iter()
+                .skip(start)
+                .take(end - start)
+                .collect(),
+        })
+    }
+
+    /// 游标分页的 quiz_leaderboard
+    async fn quiz_leaderboard_connection(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] kind: LeaderboardKind,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<UserAttemptView>> {
+        let leaderboard = self.collect_quiz_leaderboard(quiz_id, viewer, kind).await;
+        self.cursor_page(leaderboard, &page, leaderboard_key)
+    }
+
+    /// 冻结的排行榜修订版，修订版1为原始榜单
+    async fn leaderboard_snapshots(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<LeaderboardSnapshotView>> {
+        let now = self.runtime.system_time();
+        if !self.quiz_results_visible(quiz_id, now).await {
+            return Ok(Vec::new());
+        }
+        let snapshots = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default();
+        let is_creator = self.is_quiz_creator(quiz_id, viewer).await;
+
+        let mut views = Vec::new();
+        for snapshot in snapshots {
+            let mut entries = Vec::new();
+            for (index, entry) in snapshot.entries.into_iter().enumerate() {
+                let hidden =
+                    !is_creator && viewer != Some(entry.user) && self.is_private(&entry.user).await;
+                entries.push(LeaderboardSnapshotEntry {
+                    rank: index as u32 + 1,
+                    user: (!hidden).then_some(entry.user),
+                    nick_name: if hidden {
+                        ANONYMOUS_USER.to_string()
+                    } else {
+                        entry.nick_name
+                    },
+                    score: entry.score,
+                });
+            }
+            views.push(LeaderboardSnapshotView {
+                quiz_id,
+                revision: snapshot.revision,
+                frozen_at: snapshot.frozen_at,
+                entries,
+            });
+        }
+        Ok(views)
+    }
+
+    /// 附带链元数据的 leaderboard
+    async fn leaderboard_envelope(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> Envelope<UserAttemptView> {
+        let leaderboard = self.collect_leaderboard(viewer).await;
+        Envelope {
+            metadata: self.metadata(),
+            data: self.paginate(leaderboard, &page),
+        }
+    }
+
+    /// 附带链元数据的 quiz_leaderboard
+    async fn quiz_leaderboard_envelope(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] kind: LeaderboardKind,
+        #[graphql(default)] page: PageArgs,
+    ) -> Envelope<UserAttemptView> {
+        let leaderboard = self.collect_quiz_leaderboard(quiz_id, viewer, kind).await;
+        Envelope {
+            metadata: self.metadata(),
+            data: self.paginate(leaderboard, &page),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 组织的查询：详情、分析和组织下的Quiz */
+
+use linera_sdk::linera_base_types::AccountOwner;
+use quiz::scoring::FixedScore;
+use quiz::state::QuizSet;
+use quiz::{Connection, OrgAnalytics, OrgView, QuizSetView};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{CursorArgs, PageArgs, QueryContext};
+
+/// 组织相关查询
+pub(super) struct OrgQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for OrgQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl OrgQueries {
+    /// 组织信息，包括成员和品牌元数据
+    async fn org(&self, slug: String) -> async_graphql::Result<Option<OrgView>> {
+        match self.state.orgs.get(&slug).await? {
+            Some(organization) => Ok(Some(self.collect_org_view(organization).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn orgs(&self) -> async_graphql::Result<Vec<OrgView>> {
+        let mut organizations = Vec::new();
+        self.state
+            .orgs
+            .for_each_index_value(|_slug, organization| {
+                organizations.push(organization.into_owned());
+                Ok(())
+            })
+            .await?;
+        let mut views = Vec::new();
+        for organization in organizations {
+            views.push(self.collect_org_view(organization).await?);
+        }
+        Ok(views)
+    }
+
+    /// 组织统计，仅对组织管理员开放
+    async fn org_analytics(
+        &self,
+        org: String,
+        viewer: AccountOwner,
+    ) -> async_graphql::Result<OrgAnalytics> {
+        if !self.state.orgs.contains_key(&org).await? {
+            return Err("Organization not found".into());
+        }
+        if !self.state.is_org_admin(&org, &viewer).await {
+            return Err("Only organization admins can view its analytics".into());
+        }
+        let now = self.runtime.system_time();
+        let mut quiz_ids = std::collections::HashSet::new();
+        let mut active_quizzes = 0;
+        let mut cancelled_quizzes = 0;
+        self.state
+            .quiz_sets
+            .for_each_index_value(|quiz_id, quiz| {
+                if quiz.org.as_ref() == Some(&org) {
+                    quiz_ids.insert(quiz_id);
+                    if quiz.cancelled {
+                        cancelled_quizzes += 1;
+                    } else if quiz.has_started(now) && now <= quiz.end_time {
+                        active_quizzes += 1;
+                    }
+                }
+                Ok(())
+            })
+            .await?;
+
+        let mut attempt_count = 0;
+        let mut participants = std::collections::HashSet::new();
+        let mut total: FixedScore = 0;
+        self.state
+            .user_attempts
+            .for_each_index_value(|(quiz_id, user, _attempt_no), attempt| {
+                if quiz_ids.contains(&quiz_id) {
+                    attempt_count += 1;
+                    participants.insert(user);
+                    total = total.saturating_add(attempt.fixed_score);
+                }
+                Ok(())
+            })
+            .await?;
+
+        Ok(OrgAnalytics {
+            org,
+            quiz_count: quiz_ids.len() as u64,
+            active_quizzes,
+            cancelled_quizzes,
+            attempt_count,
+            participant_count: participants.len() as u64,
+            average_score: (attempt_count > 0).then(|| {
+                self.parameters
+                    .format_score(total / attempt_count as FixedScore)
+            }),
+        })
+    }
+
+    /// 组织下的Quiz
+    async fn org_quizzes(
+        &self,
+        org: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        Ok(self
+            .quiz_sets_page(
+                viewer,
+                &page,
+                None,
+                |quiz| quiz.org.as_ref() == Some(&org),
+                None,
+            )
+            .await?
+            .0)
+    }
+
+    /// 游标分页的 org_quizzes
+    async fn org_quizzes_connection(
+        &self,
+        org: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<QuizSetView>> {
+        let filter = |quiz: &QuizSet| quiz.org.as_ref() == Some(&org);
+        self.quiz_sets_cursor_page(viewer, &page, filter, None)
+            .await
+    }
+}
@@ -0,0 +1,704 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 平台级查询：事件流、管理员、概览、禁排时段和状态导出 */
+
+use linera_sdk::linera_base_types::{AccountOwner, TimeDelta, Timestamp};
+use linera_sdk::views::{CollectionView, LogView, MapView, SetView};
+use quiz::state::{preview_token_hash, AttemptSession, QuizSet, UserAttempt};
+use quiz::{
+    AdminProposalView, AppSummary, BlackoutWindowView, ChainMetadata, EventStreamView,
+    ExportSection, Principal, QuestionKind, StateExportPage, EVENT_STREAM_NAME,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::QueryContext;
+
+/// 状态导出默认每页记录数
+const EXPORT_DEFAULT_PAGE_SIZE: u64 = 100;
+
+/// 状态导出每页最大记录数
+const EXPORT_MAX_PAGE_SIZE: u64 = 1000;
+
+/// app_summary 统计近期答卷的时间范围（24小时）
+const RECENT_ATTEMPTS_WINDOW_SECS: u64 = 24 * 3600;
+
+/// app_summary 从事件日志尾部每次读取的答卷数
+const RECENT_ATTEMPTS_CHUNK: usize = 100;
+
+/// app_summary 最多回溯的答卷数，超出部分不计入近期答卷
+const MAX_RECENT_ATTEMPTS_SCAN: usize = 10_000;
+
+/// 平台级查询
+pub(super) struct PlatformQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for PlatformQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl PlatformQueries {
+    /// 应用事件流的位置：事件通过 `quiz_events` 流发布，索引器从节点读取。
+    /// 服务本身不提供订阅，客户端在收到节点的新区块通知后用上次读到的 since_index 查询是否有新事件
+    async fn event_stream(&self, since_index: Option<u32>) -> EventStreamView {
+        let next_index = *self.state.next_event_index.get();
+        EventStreamView {
+            chain_id: self.runtime.chain_id(),
+            application_id: self.runtime.application_id().forget_abi(),
+            stream_name: String::from_utf8_lossy(EVENT_STREAM_NAME).into_owned(),
+            next_index,
+            pending: next_index.saturating_sub(since_index.unwrap_or(0)),
+        }
+    }
+
+    /// 管理员列表
+    async fn admins(&self) -> Vec<String> {
+        self.state
+            .admins
+            .indices()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// 管理操作提案及其确认状态
+    async fn admin_proposals(&self) -> Vec<AdminProposalView> {
+        let mut proposals = Vec::new();
+        let _ = self
+            .state
+            .admin_proposals
+            .for_each_index_value(|_id, proposal| {
+                proposals.push(AdminProposalView {
+                    id: proposal.id,
+                    action: proposal.action.to_string(),
+                    confirmations: proposal
+                        .confirmations
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                    executed: proposal.executed,
+                    created_at: proposal.created_at,
+                });
+                Ok(())
+            })
+            .await;
+        proposals.sort_by_key(|proposal| proposal.id);
+        proposals
+    }
+
+    /// 供通用Linera浏览器使用的应用概要
+    async fn app_summary(&self) -> async_graphql::Result<AppSummary> {
+        let now = self.runtime.system_time();
+        let mut quiz_count = 0;
+        let mut active_quizzes = 0;
+        self.state
+            .quiz_sets
+            .for_each_index_value(|_quiz_id, quiz| {
+                quiz_count += 1;
+                if !quiz.cancelled && quiz.has_started(now) && now <= quiz.end_time {
+                    active_quizzes += 1;
+                }
+                Ok(())
+            })
+            .await?;
+
+        // 事件日志按提交顺序追加，从尾部分段回溯到窗口之外即可停止
+        let window = TimeDelta::from_secs(RECENT_ATTEMPTS_WINDOW_SECS);
+        let total = self.state.quiz_events.count();
+        let floor = total.saturating_sub(MAX_RECENT_ATTEMPTS_SCAN);
+        let mut end = total;
+        let mut attempts_last_day = 0;
+        while end > floor {
+            let start = end.saturating_sub(RECENT_ATTEMPTS_CHUNK).max(floor);
+            let chunk = self.state.quiz_events.read(start..end).await?;
+            let recent = chunk
+                .iter()
+                .rev()
+                .take_while(|attempt| now.delta_since(attempt.completed_at) < window)
+                .count();
+            attempts_last_day += recent as u64;
+            if recent < chunk.len() {
+                break;
+            }
+            end = start;
+        }
+
+        let admins = self
+            .state
+            .admins
+            .indices()
+            .await?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        Ok(AppSummary {
+            metadata: self.metadata(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            quiz_count,
+            active_quizzes,
+            attempts_last_day,
+            admins,
+        })
+    }
+
+    /// 当前查询所基于的链元数据
+    async fn chain_metadata(&self) -> ChainMetadata {
+        self.metadata()
+    }
+
+    /// 平台禁排时段
+    async fn blackout_windows(&self) -> async_graphql::Result<Vec<BlackoutWindowView>> {
+        Ok(self
+            .state
+            .blackout_window_list()
+            .await?
+            .into_iter()
+            .map(BlackoutWindowView::from)
+            .collect())
+    }
+
+    /// 开始时间所在的平台禁排时段，为空表示可以安排在该时间开始
+    async fn schedule_conflicts(
+        &self,
+        start_time: Timestamp,
+    ) -> async_graphql::Result<Vec<BlackoutWindowView>> {
+        Ok(self
+            .blackout_windows_at(start_time)
+            .await
+            .into_iter()
+            .map(BlackoutWindowView::from)
+            .collect())
+    }
+
+    /// 审计用：按分区分页导出完整状态，记录按存储顺序排列，每页附带内容哈希；
+    /// 仅限以账户登记的平台管理员。答案公开前Quiz不含答案、答卷不含答案和逐题结果；
+    /// 进行中的答题会话不含答案，私人笔记不含内容，预览令牌只导出哈希，
+    /// 键中含有私密用户的记录不导出
+    async fn export_state(
+        &self,
+        section: ExportSection,
+        viewer: Option<AccountOwner>,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> async_graphql::Result<StateExportPage> {
+        let is_admin = match viewer {
+            Some(viewer) => {
+                self.state
+                    .admins
+                    .contains(&Principal::Owner(viewer))
+                    .await?
+            }
+            None => false,
+        };
+        if !is_admin {
+            return Err("Only platform admins can export the application state".into());
+        }
+        let now = self.runtime.system_time();
+        let offset = offset.unwrap_or(0);
+        let limit = limit
+            .unwrap_or(EXPORT_DEFAULT_PAGE_SIZE)
+            .clamp(1, EXPORT_MAX_PAGE_SIZE);
+
+        let (records, has_more) = match section {
+            ExportSection::Counters => {
+                let record = serde_json::json!({
+                    "next_quiz_id": *self.state.next_quiz_id.get(),
+                    "next_proposal_id": *self.state.next_proposal_id.get(),
+                    "next_blackout_id": *self.state.next_blackout_id.get(),
+                    "next_notification_id": *self.state.next_notification_id.get(),
+                    "next_event_index": *self.state.next_event_index.get(),
+                });
+                let records = if offset == 0 {
+                    vec![record.to_string()]
+                } else {
+                    Vec::new()
+                };
+                (records, false)
+            }
+            ExportSection::Quizzes => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.quiz_sets, offset, limit).await?;
+                let entries = entries
+                    .into_iter()
+                    .map(|(quiz_id, quiz)| (quiz_id, redact_quiz(quiz, now)))
+                    .collect();
+                (export_records(entries), has_more)
+            }
+            ExportSection::Attempts => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.user_attempts, offset, limit).await?;
+                let mut redacted = Vec::with_capacity(entries.len());
+                for (key, attempt) in entries {
+                    let attempt = self.redact_attempt(attempt, now).await?;
+                    redacted.push((key, attempt));
+                }
+                (export_records(redacted), has_more)
+            }
+            ExportSection::Participations => {
+                export_map_page(&self.state.user_participations, offset, limit).await?
+            }
+            ExportSection::CreatorQuizzes => {
+                export_map_page(&self.state.creator_quizzes, offset, limit).await?
+            }
+            ExportSection::AnswerFingerprints => {
+                export_map_page(&self.state.answer_fingerprints, offset, limit).await?
+            }
+            ExportSection::QuizAttemptIndex => {
+                export_map_page(&self.state.quiz_attempt_index, offset, limit).await?
+            }
+            ExportSection::UserAttemptIndex => {
+                export_map_page(&self.state.user_attempt_index, offset, limit).await?
+            }
+            ExportSection::Leaderboards => {
+                export_map_page(&self.state.leaderboard, offset, limit).await?
+            }
+            ExportSection::PrivateUsers => {
+                export_set_page(&self.state.private_users, offset, limit).await?
+            }
+            ExportSection::PendingAttempts => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.pending_attempts, offset, limit).await?;
+                let mut redacted = Vec::with_capacity(entries.len());
+                for (key, attempt) in entries {
+                    let attempt = self.redact_attempt(attempt, now).await?;
+                    redacted.push((key, attempt));
+                }
+                (export_records(redacted), has_more)
+            }
+            ExportSection::Events => {
+                export_log_page(&self.state.quiz_events, offset, limit).await?
+            }
+            ExportSection::AttemptSessions => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.attempt_sessions, offset, limit).await?;
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, session)| (key, redact_session(session)))
+                    .collect();
+                (export_records(entries), has_more)
+            }
+            ExportSection::Admins => export_set_page(&self.state.admins, offset, limit).await?,
+            ExportSection::AdminProposals => {
+                export_map_page(&self.state.admin_proposals, offset, limit).await?
+            }
+            ExportSection::AttemptNotes => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.attempt_notes, offset, limit).await?;
+                let entries = entries.into_iter().map(|(key, _note)| (key, ())).collect();
+                (export_records(entries), has_more)
+            }
+            ExportSection::QuestionAppeals => {
+                export_map_page(&self.state.question_appeals, offset, limit).await?
+            }
+            ExportSection::QuestionFingerprints => {
+                export_map_page(&self.state.question_fingerprints, offset, limit).await?
+            }
+            ExportSection::AttemptReceipts => {
+                export_map_page(&self.state.attempt_receipts, offset, limit).await?
+            }
+            ExportSection::LeaderboardSnapshots => {
+                export_map_page(&self.state.leaderboard_snapshots, offset, limit).await?
+            }
+            ExportSection::Orgs => export_map_page(&self.state.orgs, offset, limit).await?,
+            ExportSection::OrgMembers => {
+                export_set_page(&self.state.org_members, offset, limit).await?
+            }
+            ExportSection::OrgAdmins => {
+                export_set_page(&self.state.org_admins, offset, limit).await?
+            }
+            ExportSection::Nicknames => {
+                export_map_page(&self.state.nicknames, offset, limit).await?
+            }
+            ExportSection::UserProfiles => {
+                export_map_page(&self.state.user_profiles, offset, limit).await?
+            }
+            ExportSection::Escrow => export_map_page(&self.state.escrow, offset, limit).await?,
+            ExportSection::BlackoutWindows => {
+                export_map_page(&self.state.blackout_windows, offset, limit).await?
+            }
+            ExportSection::Subscribers => {
+                export_set_page(&self.state.subscribers, offset, limit).await?
+            }
+            ExportSection::RemoteQuizzes => {
+                export_map_page(&self.state.remote_quizzes, offset, limit).await?
+            }
+            ExportSection::Bookmarks => {
+                export_map_page(&self.state.bookmarks, offset, limit).await?
+            }
+            ExportSection::UserBookmarkIndex => {
+                export_map_page(&self.state.user_bookmark_index, offset, limit).await?
+            }
+            ExportSection::QuizViews => {
+                export_map_page(&self.state.quiz_views, offset, limit).await?
+            }
+            ExportSection::QuizViewers => {
+                export_collection_set_page(&self.state.quiz_viewers, offset, limit).await?
+            }
+            ExportSection::Notifications => {
+                export_collection_map_page(&self.state.notifications, offset, limit).await?
+            }
+            ExportSection::QuizStarters => {
+                export_collection_set_page(&self.state.quiz_starters, offset, limit).await?
+            }
+            ExportSection::QuizParticipants => {
+                export_collection_set_page(&self.state.quiz_participants, offset, limit).await?
+            }
+            ExportSection::Certificates => {
+                export_map_page(&self.state.certificates, offset, limit).await?
+            }
+            ExportSection::TagIndex => {
+                export_map_page(&self.state.tag_index, offset, limit).await?
+            }
+            ExportSection::CategoryIndex => {
+                export_map_page(&self.state.category_index, offset, limit).await?
+            }
+            ExportSection::SearchIndex => {
+                export_map_page(&self.state.search_index, offset, limit).await?
+            }
+            ExportSection::Finalizations => {
+                export_map_page(&self.state.finalizations, offset, limit).await?
+            }
+            ExportSection::Playoffs => export_map_page(&self.state.playoffs, offset, limit).await?,
+            ExportSection::PreviewTokens => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.preview_tokens, offset, limit).await?;
+                let entries = entries
+                    .into_iter()
+                    .map(|(token, preview)| (preview_token_hash(&token), preview))
+                    .collect();
+                (export_records(entries), has_more)
+            }
+            ExportSection::QuizPreviewTokens => {
+                let (entries, has_more) =
+                    export_map_entries(&self.state.quiz_preview_tokens, offset, limit).await?;
+                let entries = entries
+                    .into_iter()
+                    .map(|(quiz_id, tokens)| {
+                        let hashes: Vec<_> = tokens.iter().map(preview_token_hash).collect();
+                        (quiz_id, hashes)
+                    })
+                    .collect();
+                (export_records(entries), has_more)
+            }
+            ExportSection::ArchivedQuizzes => {
+                export_map_page(&self.state.archived_quizzes, offset, limit).await?
+            }
+            ExportSection::RetentionPolicies => {
+                export_map_page(&self.state.retention_policies, offset, limit).await?
+            }
+            ExportSection::UnclaimedPrizes => {
+                export_map_page(&self.state.unclaimed_prizes, offset, limit).await?
+            }
+            ExportSection::PrizeSweeps => {
+                export_map_page(&self.state.prize_sweeps, offset, limit).await?
+            }
+            ExportSection::PrizePools => {
+                export_map_page(&self.state.prize_pools, offset, limit).await?
+            }
+            ExportSection::Announcements => {
+                export_map_page(&self.state.announcements, offset, limit).await?
+            }
+            ExportSection::Accommodations => {
+                export_map_page(&self.state.accommodations, offset, limit).await?
+            }
+            ExportSection::ExtraAttempts => {
+                export_map_page(&self.state.extra_attempts, offset, limit).await?
+            }
+            ExportSection::QuizIdentities => {
+                export_collection_map_page(&self.state.quiz_identities, offset, limit).await?
+            }
+            ExportSection::QuestionTimes => {
+                export_map_page(&self.state.question_times, offset, limit).await?
+            }
+            ExportSection::QuestionTallies => {
+                export_map_page(&self.state.question_tallies, offset, limit).await?
+            }
+            ExportSection::SeasonPoints => {
+                export_map_page(&self.state.season_points, offset, limit).await?
+            }
+            ExportSection::GlobalStandings => {
+                export_map_page(&self.state.global_standings, offset, limit).await?
+            }
+            ExportSection::GlobalContributions => {
+                export_map_page(&self.state.global_contributions, offset, limit).await?
+            }
+            ExportSection::DecayedRanking => {
+                export_map_page(&self.state.decayed_ranking, offset, limit).await?
+            }
+        };
+
+        let next_offset = has_more.then(|| offset + records.len() as u64);
+        let records = if section == ExportSection::PrivateUsers {
+            records
+        } else {
+            let private_users = self
+                .state
+                .private_users
+                .indices()
+                .await?
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            records
+                .into_iter()
+                .filter(|record| !key_mentions_any(record, &private_users))
+                .collect()
+        };
+        Ok(StateExportPage::new(section, offset, records, next_offset))
+    }
+}
+
+impl PlatformQueries {
+    /// 导出用的答卷：所属Quiz的答案公开前去掉答案和逐题结果，结果公布前去掉分数
+    async fn redact_attempt(
+        &self,
+        mut attempt: UserAttempt,
+        now: Timestamp,
+    ) -> async_graphql::Result<UserAttempt> {
+        let quiz = self.state.quiz_sets.get(&attempt.quiz_id).await?;
+        if quiz
+            .as_ref()
+            .is_some_and(|quiz| quiz.answer_key_public(now))
+        {
+            return Ok(attempt);
+        }
+        attempt.answers.clear();
+        attempt.question_results.clear();
+        attempt.answer_changes.clear();
+        if !quiz.is_some_and(|quiz| quiz.results_visible(now)) {
+            attempt.score = 0;
+            attempt.fixed_score = 0;
+            attempt.passed = None;
+        }
+        Ok(attempt)
+    }
+}
+
+/// 导出记录的键中是否含有任一指定值（如私密用户）
+fn key_mentions_any(record: &str, values: &[serde_json::Value]) -> bool {
+    fn contains(value: &serde_json::Value, values: &[serde_json::Value]) -> bool {
+        values.contains(value)
+            || match value {
+                serde_json::Value::Array(items) => items.iter().any(|item| contains(item, values)),
+                serde_json::Value::Object(fields) => {
+                    fields.values().any(|field| contains(field, values))
+                }
+                _ => false,
+            }
+    }
+    serde_json::from_str::<serde_json::Value>(record)
+        .ok()
+        .and_then(|record| record.get("key").map(|key| contains(key, values)))
+        .unwrap_or(false)
+}
+
+/// 导出用的Quiz：答案公开前去掉正确选项、可接受答案和解析
+fn redact_quiz(mut quiz: QuizSet, now: Timestamp) -> QuizSet {
+    if quiz.answer_key_public(now) {
+        return quiz;
+    }
+    for question in &mut quiz.questions {
+        question.correct_options.clear();
+        question.explanation = None;
+        match &mut question.kind {
+            QuestionKind::FillInBlank { answers } => answers.clear(),
+            QuestionKind::Numeric { answer, tolerance } => {
+                answer.clear();
+                tolerance.clear();
+            }
+            QuestionKind::MultipleChoice | QuestionKind::TrueFalse | QuestionKind::Essay { .. } => {
+            }
+        }
+    }
+    quiz
+}
+
+/// 导出用的答题会话：进行中的答案不导出
+fn redact_session(mut session: AttemptSession) -> AttemptSession {
+    session.answers = vec![None; session.answers.len()];
+    session
+}
+
+/// 按存储顺序读取MapView中的一页记录，返回规范化JSON记录以及是否还有后续记录
+async fn export_map_page<I, V>(
+    map: &MapView<I, V>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<String>, bool)>
+where
+    I: Serialize + DeserializeOwned + Send + Sync,
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let (entries, has_more) = export_map_entries(map, offset, limit).await?;
+    Ok((export_records(entries), has_more))
+}
+
+/// 按存储顺序读取分组映射中的一页记录，展开为与MapView相同的 `{"key":[外层键, 键]}` 记录
+async fn export_collection_map_page<K, I, V>(
+    collection: &CollectionView<K, MapView<I, V>>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<String>, bool)>
+where
+    K: Serialize + DeserializeOwned + Clone + Send + Sync,
+    I: Serialize + DeserializeOwned + Send + Sync,
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let end = offset.saturating_add(limit);
+    let mut entries = Vec::new();
+    let mut index = 0u64;
+    for outer in collection.indices().await? {
+        let Some(map) = collection.try_load_entry(&outer).await? else {
+            continue;
+        };
+        let mut has_more = false;
+        map.for_each_index_value_while(|key, value| {
+            if index == end {
+                has_more = true;
+                return Ok(false);
+            }
+            if index >= offset {
+                entries.push(((outer.clone(), key), value.into_owned()));
+            }
+            index += 1;
+            Ok(true)
+        })
+        .await?;
+        if has_more {
+            return Ok((export_records(entries), true));
+        }
+    }
+    Ok((export_records(entries), false))
+}
+
+/// 按存储顺序读取MapView中的一页键值，以及是否还有后续记录
+async fn export_map_entries<I, V>(
+    map: &MapView<I, V>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<(I, V)>, bool)>
+where
+    I: Serialize + DeserializeOwned + Send + Sync,
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let end = offset.saturating_add(limit);
+    let mut entries = Vec::new();
+    let mut index = 0u64;
+    let mut has_more = false;
+    map.for_each_index_value_while(|key, value| {
+        if index == end {
+            has_more = true;
+            return Ok(false);
+        }
+        if index >= offset {
+            entries.push((key, value.into_owned()));
+        }
+        index += 1;
+        Ok(true)
+    })
+    .await?;
+    Ok((entries, has_more))
+}
+
+/// 把键值转换为规范化JSON记录
+fn export_records<I: Serialize, V: Serialize>(entries: Vec<(I, V)>) -> Vec<String> {
+    entries
+        .into_iter()
+        .map(|(key, value)| serde_json::json!({ "key": key, "value": value }).to_string())
+        .collect()
+}
+
+/// 读取LogView中的一页记录，返回规范化JSON记录以及是否还有后续记录
+async fn export_log_page<T>(
+    log: &LogView<T>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<String>, bool)>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync,
+{
+    let count = log.count() as u64;
+    let start = offset.min(count);
+    let end = offset.saturating_add(limit).min(count);
+    let entries = log.read(start as usize..end as usize).await?;
+    let records = entries
+        .iter()
+        .zip(start..)
+        .map(|(entry, index)| serde_json::json!({ "key": index, "value": entry }).to_string())
+        .collect();
+    Ok((records, end < count))
+}
+
+/// 按存储顺序读取SetView中的一页记录，返回规范化JSON记录以及是否还有后续记录
+async fn export_set_page<I>(
+    set: &SetView<I>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<String>, bool)>
+where
+    I: Serialize + DeserializeOwned + Send + Sync,
+{
+    let end = offset.saturating_add(limit);
+    let mut records = Vec::new();
+    let mut index = 0u64;
+    let mut has_more = false;
+    set.for_each_index_while(|key| {
+        if index == end {
+            has_more = true;
+            return Ok(false);
+        }
+        if index >= offset {
+            records.push(serde_json::json!({ "key": key }).to_string());
+        }
+        index += 1;
+        Ok(true)
+    })
+    .await?;
+    Ok((records, has_more))
+}
+
+/// 按存储顺序读取分组集合中的一页记录，展开为与SetView相同的 `{"key":[外层键, 元素]}` 记录
+async fn export_collection_set_page<K, I>(
+    collection: &CollectionView<K, SetView<I>>,
+    offset: u64,
+    limit: u64,
+) -> async_graphql::Result<(Vec<String>, bool)>
+where
+    K: Serialize + DeserializeOwned + Send + Sync,
+    I: Serialize + DeserializeOwned + Send + Sync,
+{
+    let end = offset.saturating_add(limit);
+    let mut records = Vec::new();
+    let mut index = 0u64;
+    for outer in collection.indices().await? {
+        let Some(set) = collection.try_load_entry(&outer).await? else {
+            continue;
+        };
+        let mut has_more = false;
+        set.for_each_index_while(|key| {
+            if index == end {
+                has_more = true;
+                return Ok(false);
+            }
+            if index >= offset {
+                records.push(serde_json::json!({ "key": (&outer, key) }).to_string());
+            }
+            index += 1;
+            Ok(true)
+        })
+        .await?;
+        if has_more {
+            return Ok((records, true));
+        }
+    }
+    Ok((records, false))
+}
@@ -0,0 +1,886 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Quiz的查询：详情、列表、标签、复习、奖池与统计 */
+
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, CryptoHash};
+use quiz::scoring::{max_score, score_answers, total_score};
+use quiz::similarity::{similarity, DEFAULT_SIMILARITY_THRESHOLD};
+use quiz::state::{search_terms, QuizSet};
+use quiz::validation::{
+    validate_blackouts, validate_create_quiz, validate_org_limits, validate_question_media,
+    Violation,
+};
+use quiz::{
+    Answer, ArchivedQuizView, CategoryCount, Connection, CreateQuizParams, CreatorAnnouncement,
+    DuplicateQuestionGroup, Envelope, FinalizationView, LeaderboardRevision, PlayoffView,
+    PracticePoolQuestion, PreviewTokenView, PrizeDistributionView, PrizePoolView, PrizeSweepView,
+    QuestionDifficulty, QuestionReview, QuestionStats, QuizFilter, QuizSetView, QuizSortField,
+    QuizStartMode, QuizStats, RegradeRecord, RemoteQuizView, ScorePreview, SimilarityPair,
+    SubmissionWindow, TagCount, UnclaimedPrizeView,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{quiz_sort_key, remote_quiz_key, CursorArgs, PageArgs, QueryContext, QuizMatcher};
+
+/// Quiz相关查询
+pub(super) struct QuizQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for QuizQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl QuizQueries {
+    async fn quiz_set(&self, quiz_id: u64, viewer: Option<AccountOwner>) -> Option<QuizSetView> {
+        let now = self.runtime.system_time();
+        let quiz = self.state.quiz_sets.get(&quiz_id).await.ok().flatten()?;
+        if !self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+            return None;
+        }
+        Some(QuizSetView::from((&quiz, now)))
+    }
+
+    /// 凭预览令牌查看尚未开始的Quiz，不受成员可见限制；令牌过期、被撤销或Quiz已开始时为空
+    async fn quiz_by_share_token(
+        &self,
+        token: CryptoHash,
+    ) -> async_graphql::Result<Option<QuizSetView>> {
+        let now = self.runtime.system_time();
+        Ok(self
+            .state
+            .preview_quiz(&token, now)
+            .await?
+            .map(|quiz| QuizSetView::from((&quiz, now))))
+    }
+
+    /// 凭预览令牌试答尚未开始的Quiz：只计算得分，不记录答卷
+    async fn preview_attempt(
+        &self,
+        token: CryptoHash,
+        answers: Vec<Answer>,
+    ) -> async_graphql::Result<ScorePreview> {
+        let now = self.runtime.system_time();
+        let quiz = self
+            .state
+            .preview_quiz(&token, now)
+            .await?
+            .ok_or("Preview token is invalid or has expired")?;
+        if quiz.is_embargoed() {
+            return Err("Quiz content has not been revealed yet".into());
+        }
+        let score = total_score(&score_answers(&quiz.questions, &answers, quiz.scoring)?);
+        Ok(ScorePreview {
+            quiz_id: quiz.id,
+            score: self.parameters.display_score(score),
+            precise_score: self.parameters.format_score(score),
+            max_score: max_score(&quiz.questions),
+        })
+    }
+
+    /// Quiz的预览令牌，包括已过期的；只有创建者可以查看
+    async fn preview_tokens(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<PreviewTokenView>> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        if viewer != Some(quiz.creator) {
+            return Err("Preview tokens are only visible to the quiz creator".into());
+        }
+        let mut tokens = Vec::new();
+        for token in self
+            .state
+            .quiz_preview_tokens
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default()
+        {
+            if let Some(preview) = self.state.preview_tokens.get(&token).await? {
+                tokens.push(PreviewTokenView {
+                    token,
+                    quiz_id,
+                    label: preview.label,
+                    created_at: preview.created_at,
+                    expires_at: preview.expires_at,
+                });
+            }
+        }
+        tokens.sort_by_key(|token| token.created_at);
+        Ok(tokens)
+    }
+
+    /// 创建者发布的Quiz公告，按发布时间排列
+    async fn quiz_announcements(
+        &self,
+        quiz_id: u64,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<CreatorAnnouncement>> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        if !self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .state
+            .announcements
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// 所有Quiz；仅成员可见的Quiz只返回给创建者和组织成员
+    async fn quiz_sets(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] filter: QuizFilter,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        let min_participants = filter.min_participants;
+        let matcher = QuizMatcher::new(filter, self.runtime.system_time())?;
+        let filter = |quiz: &QuizSet| matcher.matches(quiz);
+        Ok(self
+            .quiz_sets_page(viewer, &page, None, filter, min_participants)
+            .await?
+            .0)
+    }
+
+    /// 符合筛选条件的可见Quiz数量
+    async fn quiz_sets_count(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] filter: QuizFilter,
+    ) -> async_graphql::Result<u64> {
+        let min_participants = filter.min_participants;
+        let matcher = QuizMatcher::new(filter, self.runtime.system_time())?;
+        let mut quizzes = Vec::new();
+        self.state
+            .quiz_sets
+            .for_each_index_value(|_quiz_id, quiz| {
+                if matcher.matches(&quiz) {
+                    quizzes.push(quiz.into_owned());
+                }
+                Ok(())
+            })
+            .await?;
+        let mut count = 0;
+        for quiz in quizzes {
+            if self.state.can_access_quiz(&quiz, viewer.as_ref()).await
+                && self.has_participants(quiz.id, min_participants).await?
+            {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 游标分页的 quiz_sets，游标中记录上一页最后一个Quiz的ID
+    async fn quiz_sets_connection(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] filter: QuizFilter,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<QuizSetView>> {
+        let min_participants = filter.min_participants;
+        let matcher = QuizMatcher::new(filter, self.runtime.system_time())?;
+        let filter = |quiz: &QuizSet| matcher.matches(quiz);
+        self.quiz_sets_cursor_page(viewer, &page, filter, min_participants)
+            .await
+    }
+
+    async fn quizzes_by_tag(
+        &self,
+        tag: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        let quiz_sets = self
+            .collect_indexed_quiz_sets(&self.state.tag_index, &tag, viewer)
+            .await?;
+        Ok(self.paginate(
+            self.sort_items(quiz_sets, &page, quiz_sort_key(sort_by)),
+            &page,
+        ))
+    }
+
+    /// 游标分页的 quizzes_by_tag
+    async fn quizzes_by_tag_connection(
+        &self,
+        tag: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<QuizSetView>> {
+        let quiz_sets = self
+            .collect_indexed_quiz_sets(&self.state.tag_index, &tag, viewer)
+            .await?;
+        let quiz_sets = self.sort_items(quiz_sets, &page.page(), quiz_sort_key(sort_by));
+        self.cursor_page(quiz_sets, &page, |quiz| quiz.id.to_string())
+    }
+
+    /// 可见Quiz上使用的全部标签及其Quiz数，按标签排序
+    async fn tags(&self, viewer: Option<AccountOwner>) -> Vec<TagCount> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for quiz in self.collect_quiz_sets(viewer).await {
+            for tag in quiz.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<TagCount> = counts
+            .into_iter()
+            .map(|(tag, quiz_count)| TagCount { tag, quiz_count })
+            .collect();
+        tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+        tags
+    }
+
+    /// 按标题和描述全文搜索可见的Quiz：忽略大小写，须包含查询中的全部词，按ID升序返回
+    async fn search_quizzes(
+        &self,
+        query: String,
+        viewer: Option<AccountOwner>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        let terms = search_terms(&query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut matches: Option<Vec<u64>> = None;
+        for term in &terms {
+            let quiz_ids = self.state.search_index.get(term).await?.unwrap_or_default();
+            matches = Some(match matches {
+                None => quiz_ids,
+                Some(mut matches) => {
+                    matches.retain(|quiz_id| quiz_ids.binary_search(quiz_id).is_ok());
+                    matches
+                }
+            });
+        }
+
+        let now = self.runtime.system_time();
+        let limit = self.page_limit(&PageArgs {
+            limit,
+            ..PageArgs::default()
+        }) as usize;
+        let mut quiz_sets = Vec::new();
+        for quiz_id in matches.unwrap_or_default() {
+            if quiz_sets.len() >= limit {
+                break;
+            }
+            let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await? else {
+                continue;
+            };
+            if self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+                quiz_sets.push(QuizSetView::from((&quiz, now)));
+            }
+        }
+        Ok(quiz_sets)
+    }
+
+    async fn quizzes_by_category(
+        &self,
+        category: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<QuizSetView>> {
+        let quiz_sets = self
+            .collect_indexed_quiz_sets(&self.state.category_index, &category, viewer)
+            .await?;
+        Ok(self.paginate(
+            self.sort_items(quiz_sets, &page, quiz_sort_key(sort_by)),
+            &page,
+        ))
+    }
+
+    /// 游标分页的 quizzes_by_category
+    async fn quizzes_by_category_connection(
+        &self,
+        category: String,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<QuizSetView>> {
+        let quiz_sets = self
+            .collect_indexed_quiz_sets(&self.state.category_index, &category, viewer)
+            .await?;
+        let quiz_sets = self.sort_items(quiz_sets, &page.page(), quiz_sort_key(sort_by));
+        self.cursor_page(quiz_sets, &page, |quiz| quiz.id.to_string())
+    }
+
+    /// 可见Quiz的全部分类及其Quiz数，按分类排序，不含未分类的Quiz
+    async fn categories(&self, viewer: Option<AccountOwner>) -> Vec<CategoryCount> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for quiz in self.collect_quiz_sets(viewer).await {
+            if !quiz.category.is_empty() {
+                *counts.entry(quiz.category).or_default() += 1;
+            }
+        }
+        let mut categories: Vec<CategoryCount> = counts
+            .into_iter()
+            .map(|(category, quiz_count)| CategoryCount {
+                category,
+                quiz_count,
+            })
+            .collect();
+        categories.sort_by(|a, b| a.category.cmp(&b.category));
+        categories
+    }
+
+    /// 答题回顾：正确答案和解析只在该用户答题次数已用完或测验结束后返回；
+    /// 题库模式只返回该用户答卷中抽到的问题
+    async fn quiz_review(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+    ) -> async_graphql::Result<Vec<QuestionReview>> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        if !self.state.can_access_quiz(&quiz, Some(&user)).await {
+            return Err("Only organization members can view this quiz".into());
+        }
+        let now = self.runtime.system_time();
+        let Some(reviewable) = self.state.reviewable_questions(&quiz, &user, now).await? else {
+            return Err(
+                "Review is available once no attempts are left or the quiz has ended".into(),
+            );
+        };
+        if !quiz.results_visible(now) {
+            return Err("Quiz results have not been revealed yet".into());
+        }
+        Ok(quiz
+            .questions
+            .into_iter()
+            .filter(|question| reviewable.contains(&question.id))
+            .map(QuestionReview::from)
+            .collect())
+    }
+
+    /// 从订阅的链收到公告的Quiz目录，按所在链和Quiz ID排序；
+    /// 可按所在链筛选，open_only 时只列出尚未结束的Quiz
+    async fn remote_quizzes(
+        &self,
+        host_chain_id: Option<ChainId>,
+        #[graphql(default)] open_only: bool,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Vec<RemoteQuizView>> {
+        let quizzes = self
+            .collect_remote_quizzes(host_chain_id, open_only)
+            .await?;
+        let quizzes = self.sort_items(quizzes, &page, remote_quiz_key);
+        Ok(self.paginate(quizzes, &page))
+    }
+
+    /// 游标分页的 remote_quizzes
+    async fn remote_quizzes_connection(
+        &self,
+        host_chain_id: Option<ChainId>,
+        #[graphql(default)] open_only: bool,
+        #[graphql(default)] page: CursorArgs,
+    ) -> async_graphql::Result<Connection<RemoteQuizView>> {
+        let quizzes = self
+            .collect_remote_quizzes(host_chain_id, open_only)
+            .await?;
+        let quizzes = self.sort_items(quizzes, &page.page(), remote_quiz_key);
+        self.cursor_page(quizzes, &page, |quiz| {
+            format!("{}:{}", quiz.host_chain_id, quiz.quiz_id)
+        })
+    }
+
+    /// Quiz的托管奖池及发放记录，没有奖池时为空
+    async fn prize_pool(&self, quiz_id: u64) -> async_graphql::Result<Option<PrizePoolView>> {
+        let Some(pool) = self.state.prize_pools.get(&quiz_id).await? else {
+            return Ok(None);
+        };
+        // 发放前参赛费仍在托管记录中
+        let mut entry_fees = pool.entry_fees;
+        let mut unclaimed = Vec::new();
+        let mut sweeps = Vec::new();
+        for user in self.state.participants_of(quiz_id).await? {
+            let key = (quiz_id, user);
+            if let Some(fee) = self.state.escrow.get(&key).await? {
+                entry_fees = entry_fees.saturating_add(fee);
+            }
+            if let Some(prize) = self.state.unclaimed_prizes.get(&key).await? {
+                unclaimed.push(UnclaimedPrizeView {
+                    user,
+                    rank: prize.rank,
+                    amount: prize.amount,
+                    claim_deadline: prize.claim_deadline,
+                });
+            }
+            if let Some(sweep) = self.state.prize_sweeps.get(&key).await? {
+                sweeps.push(PrizeSweepView {
+                    user,
+                    rank: sweep.rank,
+                    amount: sweep.amount,
+                    recipient: sweep.recipient,
+                    swept_at: sweep.swept_at,
+                });
+            }
+        }
+        unclaimed.sort_by_key(|prize| prize.rank);
+        sweeps.sort_by_key(|sweep| sweep.rank);
+        Ok(Some(PrizePoolView {
+            quiz_id,
+            funder: pool.funder,
+            amount: pool.amount,
+            entry_fees,
+            splits: pool.splits,
+            distributed: pool.distribution.is_some(),
+            payouts: pool
+                .distribution
+                .map(|distribution| distribution.payouts)
+                .unwrap_or_default(),
+            token_prizes: pool.token_prizes,
+            unclaimed,
+            sweeps,
+        }))
+    }
+
+    /// Quiz最终结果的可验证摘要：确定时间、最终排行榜哈希、奖金发放和重新评分记录
+    async fn finalization(&self, quiz_id: u64) -> async_graphql::Result<Option<FinalizationView>> {
+        let Some(finalization) = self.state.finalizations.get(&quiz_id).await? else {
+            return Ok(None);
+        };
+        let prize_distribution = self
+            .state
+            .prize_pools
+            .get(&quiz_id)
+            .await?
+            .and_then(|pool| pool.distribution)
+            .map(|distribution| PrizeDistributionView {
+                payouts: distribution.payouts,
+                refunded: distribution.refunded,
+                distributed_at: distribution.distributed_at,
+                block_height: distribution.block_height.0,
+            });
+        let revisions = self
+            .state
+            .leaderboard_snapshots
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|snapshot| LeaderboardRevision {
+                revision: snapshot.revision,
+                frozen_at: snapshot.frozen_at,
+            })
+            .collect();
+        let question_count = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .map_or(0, |quiz| quiz.questions.len() as u32);
+        let mut regrades = Vec::new();
+        for question_id in 0..question_count {
+            if let Some(appeal) = self
+                .state
+                .question_appeals
+                .get(&(quiz_id, question_id))
+                .await?
+            {
+                regrades.push(RegradeRecord {
+                    question_id,
+                    votes: appeal.voters.len() as u32,
+                    status: appeal.status,
+                });
+            }
+        }
+        Ok(Some(FinalizationView {
+            quiz_id,
+            finalized_at: finalization.finalized_at,
+            block_height: finalization.block_height.0,
+            revision: finalization.revision,
+            standings_hash: finalization.standings_hash,
+            prize_distribution,
+            token_rewards: finalization.token_rewards,
+            revisions,
+            regrades,
+        }))
+    }
+
+    /// 创建者已归档的Quiz摘要，按Quiz ID升序
+    async fn archived_quizzes(
+        &self,
+        creator: AccountOwner,
+    ) -> async_graphql::Result<Vec<ArchivedQuizView>> {
+        let mut archived = Vec::new();
+        self.state
+            .archived_quizzes
+            .for_each_index_value(|quiz_id, archive| {
+                if archive.creator == creator {
+                    archived.push(ArchivedQuizView {
+                        quiz_id,
+                        title: archive.title.clone(),
+                        creator: archive.creator,
+                        category: archive.category.clone(),
+                        tags: archive.tags.clone(),
+                        start_time: archive.start_time,
+                        end_time: archive.end_time,
+                        cancelled: archive.cancelled,
+                        participants: archive.participants,
+                        attempts: archive.attempts,
+                        average_score: archive.average_score(),
+                        standings: archive.standings.clone(),
+                        standings_hash: archive.standings_hash,
+                        archived_at: archive.archived_at,
+                    });
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(archived)
+    }
+
+    /// 创建者的Quiz保留天数，未设置保留策略时为空
+    async fn retention_policy(&self, creator: AccountOwner) -> async_graphql::Result<Option<u32>> {
+        Ok(self.state.retention_policies.get(&creator).await?)
+    }
+
+    /// 奖项名次同分同时时为同分者创建的加赛
+    async fn playoff(&self, quiz_id: u64) -> async_graphql::Result<Option<PlayoffView>> {
+        Ok(self
+            .state
+            .playoffs
+            .get(&quiz_id)
+            .await?
+            .map(|playoff| PlayoffView {
+                quiz_id,
+                playoff_quiz_id: playoff.playoff_quiz_id,
+                tied_users: playoff.tied_users,
+                created_at: playoff.created_at,
+            }))
+    }
+
+    /// 附带链元数据的 quiz_sets
+    async fn quiz_sets_envelope(
+        &self,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] page: PageArgs,
+    ) -> async_graphql::Result<Envelope<QuizSetView>> {
+        Ok(Envelope {
+            metadata: self.metadata(),
+            data: self
+                .quiz_sets_page(viewer, &page, None, |_quiz| true, None)
+                .await?
+                .0,
+        })
+    }
+
+    /// 试运行创建Quiz的参数校验，返回全部违规项（为空表示可以提交）
+    async fn validate_quiz(&self, params: CreateQuizParams) -> Vec<Violation> {
+        let mut violations = validate_create_quiz(&params, self.runtime.system_time())
+            .err()
+            .unwrap_or_default();
+        violations.extend(validate_question_media(&params.questions, &self.parameters));
+        if let Some(org) = &params.org {
+            if let Ok(Some(organization)) = self.state.orgs.get(org).await {
+                violations.extend(validate_org_limits(&params, &organization.settings));
+            }
+        }
+        if params.start_mode == QuizStartMode::Scheduled {
+            let windows: Vec<_> = self
+                .blackout_windows_at(params.start_time)
+                .await
+                .into_iter()
+                .map(|(_, window)| window)
+                .collect();
+            violations.extend(validate_blackouts(params.start_time, &windows));
+        }
+        violations
+    }
+
+    /// 练习卷的候选问题：创建者往期Quiz中按标签和难度筛选后可以抽取的问题
+    async fn practice_exam_pool(
+        &self,
+        creator: AccountOwner,
+        #[graphql(default)] tags: Vec<String>,
+        difficulty: Option<QuestionDifficulty>,
+    ) -> async_graphql::Result<Vec<PracticePoolQuestion>> {
+        let pool = self
+            .state
+            .practice_pool(&creator, &tags, difficulty, self.runtime.system_time())
+            .await?;
+        Ok(pool.into_iter().map(PracticePoolQuestion::from).collect())
+    }
+
+    /// 试运行评分（不持久化），仅对已结束的测验或练习模式测验开放；加赛须结束后才能试运行
+    async fn score_preview(
+        &self,
+        quiz_id: u64,
+        answers: Vec<Answer>,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<ScorePreview> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        if !self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+            return Err("Only organization members can view this quiz".into());
+        }
+        let now = self.runtime.system_time();
+        if (!quiz.practice || quiz.playoff_of.is_some()) && now <= quiz.end_time {
+            return Err("Score preview is only available after the quiz has ended".into());
+        }
+        if !quiz.results_visible(now) {
+            return Err("Quiz results have not been revealed yet".into());
+        }
+        let score = total_score(&score_answers(&quiz.questions, &answers, quiz.scoring)?);
+        Ok(ScorePreview {
+            quiz_id,
+            score: self.parameters.display_score(score),
+            precise_score: self.parameters.format_score(score),
+            max_score: max_score(&quiz.questions),
+        })
+    }
+
+    /// 每个问题的申诉票数、争议状态、用时和答对率，答对率低的问题可能过难或有歧义
+    async fn question_stats(&self, quiz_id: u64) -> async_graphql::Result<Vec<QuestionStats>> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        let mut stats = Vec::new();
+        for question in &quiz.questions {
+            let appeal = self
+                .state
+                .question_appeals
+                .get(&(quiz_id, question.id))
+                .await?;
+            let samples = self
+                .state
+                .question_times
+                .get(&(quiz_id, question.id))
+                .await?
+                .unwrap_or_default();
+            let tally = self
+                .state
+                .question_tallies
+                .get(&(quiz_id, question.id))
+                .await?
+                .unwrap_or_default();
+            stats.push(QuestionStats {
+                question_id: question.id,
+                appeal_votes: appeal
+                    .as_ref()
+                    .map_or(0, |appeal| appeal.voters.len() as u32),
+                dispute_status: appeal.map(|appeal| appeal.status),
+                answer_time_samples: samples.len() as u32,
+                median_answer_time: median(samples),
+                answered: tally.answered,
+                correct: tally.correct,
+                skipped: tally.skipped,
+                accuracy: tally.accuracy(),
+                difficulty: tally.difficulty(),
+            });
+        }
+        Ok(stats)
+    }
+
+    /// 用户在分批开放中的批次、可以答题的时间窗口和（含延长时间的）答题时限
+    async fn submittable_by(
+        &self,
+        quiz_id: u64,
+        user: AccountOwner,
+    ) -> async_graphql::Result<SubmissionWindow> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        let now = self.runtime.system_time();
+        let opens_at = quiz.submittable_from(user);
+        let time_multiplier = self
+            .state
+            .accommodations
+            .get(&(quiz_id, user))
+            .await?
+            .unwrap_or(100);
+        Ok(SubmissionWindow {
+            quiz_id,
+            user,
+            wave: quiz.launch_wave(user),
+            wave_count: quiz.launch_wave_count(),
+            opens_at,
+            closes_at: quiz.end_time,
+            time_limit: quiz.time_limit_for(time_multiplier),
+            open: !quiz.cancelled
+                && quiz.has_started(now)
+                && now >= opens_at
+                && now <= quiz.end_time,
+        })
+    }
+
+    /// 相似度报告：同一问题下不同用户的文本答案指纹相似度达到阈值（默认80%）的答卷对，
+    /// 按相似度从高到低排序，仅创建者可查看
+    async fn similarity_report(
+        &self,
+        quiz_id: u64,
+        viewer: AccountOwner,
+        threshold: Option<u32>,
+    ) -> async_graphql::Result<Vec<SimilarityPair>> {
+        let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await? else {
+            return Err("Quiz not found".into());
+        };
+        if viewer != quiz.creator {
+            return Err("Only the quiz creator can view the similarity report".into());
+        }
+        let threshold = threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD);
+        let attempts = self
+            .state
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default();
+        let mut fingerprints = Vec::new();
+        for question in &quiz.questions {
+            for (user, attempt_no) in &attempts {
+                let key = (quiz_id, question.id, *user, *attempt_no);
+                if let Some(hashes) = self.state.answer_fingerprints.get(&key).await? {
+                    fingerprints.push((question.id, *user, *attempt_no, hashes));
+                }
+            }
+        }
+        let mut pairs = Vec::new();
+        for (index, (question_id, user_a, attempt_a, hashes_a)) in fingerprints.iter().enumerate() {
+            for (other_question_id, user_b, attempt_b, hashes_b) in &fingerprints[index + 1..] {
+                if other_question_id != question_id || user_a == user_b {
+                    continue;
+                }
+                let similarity = similarity(hashes_a, hashes_b);
+                if similarity >= threshold {
+                    pairs.push(SimilarityPair {
+                        question_id: *question_id,
+                        user_a: *user_a,
+                        attempt_a: *attempt_a,
+                        user_b: *user_b,
+                        attempt_b: *attempt_b,
+                        similarity,
+                    });
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.similarity.cmp(&a.similarity));
+        Ok(pairs)
+    }
+
+    /// Quiz统计，包括按逐题用时中位数估算的建议答题时限
+    async fn quiz_stats(&self, quiz_id: u64) -> async_graphql::Result<QuizStats> {
+        let quiz = self
+            .state
+            .quiz_sets
+            .get(&quiz_id)
+            .await?
+            .ok_or("QuizSet not found")?;
+        let mut medians = Vec::new();
+        for question in &quiz.questions {
+            let samples = self
+                .state
+                .question_times
+                .get(&(quiz_id, question.id))
+                .await?;
+            if let Some(median) = samples.and_then(median) {
+                medians.push(median);
+            }
+        }
+        // 每题取有样本问题的平均中位用时，按每次作答的题数乘以1.5倍余量，向上取整到秒
+        let recommended_time_limit = (!medians.is_empty()).then(|| {
+            let per_question = medians.iter().sum::<u64>() / medians.len() as u64;
+            let question_count = quiz
+                .questions_per_attempt
+                .map_or(quiz.questions.len() as u64, u64::from);
+            (per_question * question_count * 3 / 2).div_ceil(1000)
+        });
+        let unique_viewers = match self.state.quiz_viewers.try_load_entry(&quiz_id).await? {
+            Some(viewers) => viewers.count().await? as u64,
+            None => 0,
+        };
+        let starters = match self.state.quiz_starters.try_load_entry(&quiz_id).await? {
+            Some(starters) => starters.count().await? as u64,
+            None => 0,
+        };
+        let submitters: BTreeSet<AccountOwner> = self
+            .state
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(user, _attempt_no)| user)
+            .collect();
+        Ok(QuizStats {
+            quiz_id,
+            time_limit: quiz.time_limit,
+            recommended_time_limit,
+            views: self
+                .state
+                .quiz_views
+                .get(&quiz_id)
+                .await?
+                .unwrap_or_default(),
+            unique_viewers,
+            starters,
+            submitters: submitters.len() as u64,
+        })
+    }
+
+    /// 创建者所有Quiz中重复出现的问题
+    async fn duplicate_questions(&self, creator: AccountOwner) -> Vec<DuplicateQuestionGroup> {
+        let mut groups = Vec::new();
+        let _ = self
+            .state
+            .question_fingerprints
+            .for_each_index_value(|(owner, fingerprint), refs| {
+                if owner == creator && refs.len() > 1 {
+                    groups.push(DuplicateQuestionGroup {
+                        fingerprint: fingerprint.to_string(),
+                        questions: refs.into_owned(),
+                    });
+                }
+                Ok(())
+            })
+            .await;
+        groups
+    }
+}
+
+/// 中位数，偶数个样本时取中间两个的平均值
+fn median(mut samples: Vec<u64>) -> Option<u64> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable();
+    let middle = samples.len() / 2;
+    Some(if samples.len() % 2 == 0 {
+        (samples[middle - 1] + samples[middle]) / 2
+    } else {
+        samples[middle]
+    })
+}
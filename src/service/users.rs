@@ -0,0 +1,249 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 用户的查询：资料、通知、连胜和参与记录 */
+
+use linera_sdk::linera_base_types::AccountOwner;
+use quiz::state::nickname_key;
+use quiz::{
+    NotificationView, QuizSetView, QuizSortField, TrophyView, UserStatsView, UserStreakView,
+    UserView,
+};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{quiz_sort_key, PageArgs, QueryContext};
+
+/// 用户相关查询
+pub(super) struct UserQueries(pub(super) Arc<QueryContext>);
+
+impl Deref for UserQueries {
+    type Target = QueryContext;
+
+    fn deref(&self) -> &QueryContext {
+        &self.0
+    }
+}
+
+#[async_graphql::Object]
+impl UserQueries {
+    /// 用户的通知，最新的在前；只有用户本人可以查看
+    async fn user_notifications(
+        &self,
+        user: AccountOwner,
+        unread_only: Option<bool>,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<Vec<NotificationView>> {
+        if viewer != Some(user) {
+            return Err("Notifications are only visible to their owner".into());
+        }
+        let unread_only = unread_only.unwrap_or(false);
+        let mut notifications = Vec::new();
+        let Some(inbox) = self.state.notifications.try_load_entry(&user).await? else {
+            return Ok(notifications);
+        };
+        inbox
+            .for_each_index_value(|id, notification| {
+                if !(unread_only && notification.read) {
+                    notifications.push(NotificationView {
+                        id,
+                        kind: notification.kind,
+                        quiz_id: notification.quiz_id,
+                        message: notification.message.clone(),
+                        created_at: notification.created_at,
+                        read: notification.read,
+                    });
+                }
+                Ok(())
+            })
+            .await?;
+        notifications.reverse();
+        Ok(notifications)
+    }
+
+    /// 用户的每日连续完成天数、赛季积分加成和当前赛季积分；私密用户只对本人可见
+    async fn user_streak(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<UserStreakView> {
+        if viewer != Some(user) && self.is_private(&user).await {
+            return Err("This user's streak is private".into());
+        }
+        let now = self.runtime.system_time();
+        let streak = self
+            .state
+            .user_profiles
+            .get(&user)
+            .await?
+            .unwrap_or_default()
+            .streak;
+        let current_streak = streak.active_at(now);
+        let bonus = self.parameters.streak_bonus(current_streak);
+        let season = self.parameters.season_at(now);
+        Ok(UserStreakView {
+            user,
+            current_streak,
+            longest_streak: streak.longest,
+            last_completed_day: streak.last_day,
+            bonus_multiplier: format!("{}.{:02}", bonus / 100, bonus % 100),
+            season,
+            season_points: self
+                .state
+                .season_points
+                .get(&(season, user))
+                .await?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// 用户的累计统计：参加和创建的Quiz数、总得分、平均分和最好名次；私密用户只对本人可见
+    async fn user_stats(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<UserStatsView> {
+        if viewer != Some(user) && self.is_private(&user).await {
+            return Err("This user's statistics are private".into());
+        }
+        let stats = self
+            .state
+            .user_profiles
+            .get(&user)
+            .await?
+            .unwrap_or_default()
+            .stats;
+        Ok(UserStatsView {
+            user,
+            quizzes_taken: stats.quizzes_taken,
+            quizzes_created: stats.quizzes_created,
+            graded_attempts: stats.graded_attempts,
+            total_points: stats.total_points,
+            average_score: stats.average_score(),
+            best_rank: stats.best_rank,
+        })
+    }
+
+    /// 用户档案，包括排行榜前三名奖杯；私密用户的奖杯只对本人可见
+    async fn user(
+        &self,
+        address: AccountOwner,
+        viewer: Option<AccountOwner>,
+    ) -> async_graphql::Result<UserView> {
+        let mut trophies = Vec::new();
+        let profile = self
+            .state
+            .user_profiles
+            .get(&address)
+            .await?
+            .unwrap_or_default();
+        if viewer == Some(address) || !self.is_private(&address).await {
+            let now = self.runtime.system_time();
+            for trophy in profile.trophies {
+                let quiz = self.state.quiz_sets.get(&trophy.quiz_id).await?;
+                // 结果尚未公布的Quiz不展示奖杯
+                if quiz.as_ref().is_some_and(|quiz| !quiz.results_visible(now)) {
+                    continue;
+                }
+                // 已归档的Quiz沿用归档摘要中的标题
+                let quiz_title = match quiz {
+                    Some(quiz) => Some(quiz.title),
+                    None => self
+                        .state
+                        .archived_quizzes
+                        .get(&trophy.quiz_id)
+                        .await?
+                        .map(|archive| archive.title),
+                };
+                trophies.push(TrophyView {
+                    quiz_id: trophy.quiz_id,
+                    quiz_title,
+                    place: trophy.place,
+                    score: trophy.score,
+                    awarded_at: trophy.awarded_at,
+                });
+            }
+        }
+        Ok(UserView {
+            user: address,
+            nick_name: profile.nick_name,
+            trophies,
+        })
+    }
+
+    /// 按昵称（不区分大小写）查找登记该昵称的用户
+    async fn user_by_nickname(
+        &self,
+        nick_name: String,
+    ) -> async_graphql::Result<Option<AccountOwner>> {
+        Ok(self.state.nicknames.get(&nickname_key(&nick_name)).await?)
+    }
+
+    async fn user_participations(&self, user: AccountOwner) -> Vec<u64> {
+        match self.state.user_participations.get(&user).await {
+            Ok(Some(v)) => v,
+            Ok(None) => Vec::default(),
+            Err(_) => Vec::default(),
+        }
+    }
+
+    /// 用户创建的Quiz，不列出 viewer 无权查看的Quiz
+    async fn get_user_created_quizzes(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> Vec<QuizSetView> {
+        let now = self.runtime.system_time();
+        let mut created_quizzes = Vec::new();
+        let quiz_ids = self
+            .state
+            .creator_quizzes
+            .get(&user)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        for quiz_id in quiz_ids {
+            if let Some(quiz) = self.state.quiz_sets.get(&quiz_id).await.unwrap() {
+                if self.state.can_access_quiz(&quiz, viewer.as_ref()).await {
+                    created_quizzes.push(QuizSetView::from((&quiz, now)));
+                }
+            }
+        }
+        self.paginate(
+            self.sort_items(created_quizzes, &page, quiz_sort_key(sort_by)),
+            &page,
+        )
+    }
+
+    /// 用户参加过的Quiz，不列出 viewer 无权查看的Quiz
+    async fn get_user_participated_quizzes(
+        &self,
+        user: AccountOwner,
+        viewer: Option<AccountOwner>,
+        #[graphql(default)] sort_by: QuizSortField,
+        #[graphql(default)] page: PageArgs,
+    ) -> Vec<QuizSetView> {
+        let now = self.runtime.system_time();
+        let mut participated_quizzes = Vec::new();
+        let quiz_ids = self
+            .state
+            .user_participations
+            .get(&user)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        for &quiz_id in &quiz_ids {
+            if let Some(quiz_set) = self.state.quiz_sets.get(&quiz_id).await.unwrap() {
+                if self.state.can_access_quiz(&quiz_set, viewer.as_ref()).await {
+                    participated_quizzes.push(QuizSetView::from((&quiz_set, now)));
+                }
+            }
+        }
+        self.paginate(
+            self.sort_items(participated_quizzes, &page, quiz_sort_key(sort_by)),
+            &page,
+        )
+    }
+}
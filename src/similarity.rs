@@ -0,0 +1,57 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 合约与服务共用的文本答案指纹，用于发现相似的答卷 */
+
+/// 每个片段包含的连续单词数
+pub const SHINGLE_WORDS: usize = 3;
+/// 指纹保留的最小片段哈希数（bottom-k 草图）
+pub const FINGERPRINT_SIZE: usize = 64;
+/// 相似度报告默认的标记阈值（百分比）
+pub const DEFAULT_SIMILARITY_THRESHOLD: u32 = 80;
+
+/// 64位 FNV-1a 哈希，合约与服务的结果必须一致
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 规范化文本：转小写，标点和空白都视为单词分隔
+fn normalized_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 计算文本答案的指纹：连续单词片段的哈希中最小的 FINGERPRINT_SIZE 个，升序排列；
+/// 单词数不足一个片段的短答案没有指纹
+pub fn fingerprint(text: &str) -> Vec<u64> {
+    let words = normalized_words(text);
+    let mut hashes: Vec<u64> = words
+        .windows(SHINGLE_WORDS)
+        .map(|shingle| fnv1a(shingle.join(" ").as_bytes()))
+        .collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes.truncate(FINGERPRINT_SIZE);
+    hashes
+}
+
+/// 两个指纹的 Jaccard 相似度（百分比），按两者合并后最小的 FINGERPRINT_SIZE 个哈希估算
+pub fn similarity(a: &[u64], b: &[u64]) -> u32 {
+    let mut union: Vec<u64> = a.iter().chain(b).copied().collect();
+    union.sort_unstable();
+    union.dedup();
+    union.truncate(FINGERPRINT_SIZE);
+    if union.is_empty() {
+        return 0;
+    }
+    let shared = union
+        .iter()
+        .filter(|hash| a.binary_search(hash).is_ok() && b.binary_search(hash).is_ok())
+        .count();
+    (shared * 100 / union.len()) as u32
+}
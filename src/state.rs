@@ -1,12 +1,26 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::linera_base_types::Timestamp;
+use std::collections::BTreeSet;
+
+use linera_sdk::linera_base_types::{
+    AccountOwner, Amount, ApplicationId, BcsHashable, BlockHeight, ChainId, CryptoHash, TimeDelta,
+    Timestamp,
+};
 use linera_sdk::views::{
-    linera_views, LogView, MapView, RegisterView, RootView, ViewStorageContext,
+    linera_views, CollectionView, LogView, MapView, RegisterView, RootView, SetView, ViewError,
+    ViewStorageContext,
 };
 use serde::{Deserialize, Serialize};
 
+use super::{
+    scoring::FixedScore, AdminAction, Answer, AttemptPolicy, BlackoutWindow, ContentEmbargo,
+    CreatorAnnouncement, DisputeStatus, MetadataEntry, NotificationKind, OrgRole, OrgSettings,
+    Principal, QuestionDifficulty, QuestionKind, QuestionMedia, QuestionParams, QuestionRef,
+    QuestionType, QuestionView, QuizAnnouncement, QuizStartMode, QuizStatus, ScoringStrategy,
+    SoftLaunch,
+};
+
 /// 问题结构
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Question {
@@ -15,6 +29,52 @@ pub struct Question {
     pub options: Vec<String>,
     pub correct_options: Vec<u32>,
     pub points: u32,
+    pub kind: QuestionKind,
+    pub explanation: Option<String>, // 答案解析
+    pub media: Option<QuestionMedia>,
+    pub screen_reader_hint: Option<String>, // 读屏提示
+}
+
+/// 计算问题指纹时使用的规范化内容
+#[derive(Debug, Serialize, Deserialize)]
+struct QuestionFingerprint {
+    text: String,
+    options: Vec<String>,
+}
+
+impl BcsHashable<'_> for QuestionFingerprint {}
+
+impl Question {
+    /// 按创建参数构造问题，判断题未给出选项时使用默认的 True / False
+    pub fn new(id: u32, params: QuestionParams) -> Self {
+        let options = if params.kind == QuestionKind::TrueFalse && params.options.is_empty() {
+            vec!["True".to_string(), "False".to_string()]
+        } else {
+            params.options
+        };
+        Question {
+            id,
+            text: params.text,
+            options,
+            correct_options: params.correct_options,
+            points: params.points,
+            kind: params.kind,
+            explanation: params.explanation,
+            media: params.media,
+            screen_reader_hint: params.screen_reader_hint,
+        }
+    }
+
+    /// 问题指纹：忽略大小写、首尾空白和选项顺序，题干与选项相同即视为同一问题
+    pub fn fingerprint(&self) -> CryptoHash {
+        let normalize = |value: &str| value.trim().to_lowercase();
+        let mut options: Vec<String> = self.options.iter().map(|o| normalize(o)).collect();
+        options.sort();
+        CryptoHash::new(&QuestionFingerprint {
+            text: normalize(&self.text),
+            options,
+        })
+    }
 }
 
 /// Quiz集合结构
@@ -23,23 +83,861 @@ pub struct QuizSet {
     pub id: u64,
     pub title: String,
     pub description: String,
-    pub creator: String,
+    pub creator: AccountOwner,
+    pub creator_name: String, // 创建者显示名
     pub questions: Vec<Question>,
-    pub time_limit: u64, // 秒
+    pub time_limit: u64, // 秒，0 表示不限时
     pub start_time: Timestamp,
     pub end_time: Timestamp,
     pub created_at: Timestamp,
+    pub practice: bool,
+    pub playoff_of: Option<u64>, // 加赛所属的原Quiz
+    pub hide_results: bool,
+    pub reveal_delay: Option<u64>, // 秒
+    pub results_revealed: bool,
+    pub proctor: Option<AccountOwner>, // 考试模式监考账户
+    pub owner: Option<Principal>,      // 创建者权限持有者
+    pub metadata: Vec<(String, String)>,
+    pub start_mode: QuizStartMode,
+    pub scoring: ScoringStrategy,               // 评分方式
+    pub allow_dont_know: bool,                  // 是否可以明确表示不会
+    pub org: Option<String>,                    // 所属组织
+    pub members_only: bool,                     // 仅组织成员可见
+    pub shuffle_questions: bool,                // 每位答题者的问题顺序随机打乱
+    pub questions_per_attempt: Option<u32>,     // 题库模式下每次答题抽取的题数
+    pub embargo: Option<ContentEmbargo>,        // 问题内容封存承诺
+    pub max_attempts: u32,                      // 每位答题者最多可以答题的次数
+    pub attempt_policy: AttemptPolicy,          // 多次答题时计入排行榜的成绩
+    pub attestation_app: Option<ApplicationId>, // 身份认证应用，每个已验证身份只能用一个账户参加
+    pub entry_fee: Option<Amount>,              // 参赛费，首次开始答题时收取
+    pub category: String,                       // 主分类，为空表示未分类
+    pub tags: Vec<String>,                      // 分类标签
+    pub pass_score: Option<u32>,                // 及格分，达到后可获得完成证书
+    pub soft_launch: Option<SoftLaunch>,        // 分批开放设置
+    pub answer_key_hash: Option<CryptoHash>,    // 创建者提交的加盐答案校验和
+    pub answer_key_salt: Option<String>,        // Quiz结束后公开并经校验的盐
+    pub content_revealed: bool,                 // 封存的问题内容是否已公开
+    pub is_started: bool,                       // 手动开始方式下创建者是否已开始
+    pub cancelled: bool,
+    pub finalized: bool, // 结果已最终确定，Quiz不能再修改
+}
+
+/// 搜索词的最大长度（字节），更长的词不进入索引
+const MAX_SEARCH_TERM_LEN: usize = 64;
+
+/// 全文搜索分词：按非字母数字字符切分并转为小写，去重后按字典序排列
+pub fn search_terms(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty() && term.len() <= MAX_SEARCH_TERM_LEN)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl QuizSet {
+    /// 标题和描述中的搜索词
+    pub fn indexed_terms(&self) -> BTreeSet<String> {
+        let mut terms = search_terms(&self.title);
+        terms.extend(search_terms(&self.description));
+        terms
+    }
+
+    /// Quiz所处的阶段，由开始方式、开始和结束时间及当前时间计算
+    pub fn status(&self, now: Timestamp) -> QuizStatus {
+        if self.cancelled {
+            QuizStatus::Cancelled
+        } else if now > self.end_time {
+            QuizStatus::Ended
+        } else if self.has_started(now) {
+            QuizStatus::Active
+        } else if self.start_mode == QuizStartMode::Manual {
+            QuizStatus::Draft
+        } else {
+            QuizStatus::Upcoming
+        }
+    }
+
+    /// 向订阅链公告的目录摘要
+    pub fn announcement(&self) -> QuizAnnouncement {
+        QuizAnnouncement {
+            quiz_id: self.id,
+            title: self.title.clone(),
+            creator: self.creator,
+            start_time: self.start_time,
+            end_time: self.end_time,
+        }
+    }
+
+    /// 当前是否已开始：手动开始方式看创建者是否已开始，否则看开始时间
+    pub fn has_started(&self, now: Timestamp) -> bool {
+        match self.start_mode {
+            QuizStartMode::Manual => self.is_started,
+            QuizStartMode::Scheduled => now >= self.start_time,
+        }
+    }
+
+    /// 分批开放时用户所在的批次，未分批开放时为0
+    pub fn launch_wave(&self, user: AccountOwner) -> u32 {
+        let Some(soft_launch) = self.soft_launch else {
+            return 0;
+        };
+        let hash = CryptoHash::new(&LaunchWaveDigest {
+            quiz_id: self.id,
+            user,
+        });
+        let bucket = (<[u64; 4]>::from(hash)[0] % 100) as u32;
+        bucket / soft_launch.percent_per_wave.max(1)
+    }
+
+    /// 分批开放的总批次数
+    pub fn launch_wave_count(&self) -> u32 {
+        self.soft_launch.map_or(1, |soft_launch| {
+            100u32.div_ceil(soft_launch.percent_per_wave.max(1))
+        })
+    }
+
+    /// 用户可以开始答题的时间：开始时间加上所在批次之前各批的间隔
+    pub fn submittable_from(&self, user: AccountOwner) -> Timestamp {
+        let delay = self.soft_launch.map_or(0, |soft_launch| {
+            soft_launch
+                .wave_secs
+                .saturating_mul(u64::from(self.launch_wave(user)))
+        });
+        self.start_time.saturating_add(TimeDelta::from_secs(delay))
+    }
+
+    /// 分数是否达到及格分，未设置及格分时为空
+    pub fn passed(&self, score: u32) -> Option<bool> {
+        self.pass_score.map(|pass_score| score >= pass_score)
+    }
+
+    /// 自定义元数据的视图形式
+    pub fn metadata_entries(&self) -> Vec<MetadataEntry> {
+        self.metadata
+            .iter()
+            .map(|(key, value)| MetadataEntry {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+
+    /// 当前是否可以公开分数和排行榜
+    pub fn results_visible(&self, now: Timestamp) -> bool {
+        if !self.hide_results || self.results_revealed {
+            return true;
+        }
+        self.reveal_delay
+            .is_some_and(|delay| now >= self.end_time.saturating_add(TimeDelta::from_secs(delay)))
+    }
+
+    /// 答案是否已可公开：Quiz已结束且结果可见
+    pub fn answer_key_public(&self, now: Timestamp) -> bool {
+        now > self.end_time && self.results_visible(now)
+    }
+
+    /// 按延长倍数（百分比）计算的答题时限（秒），0 表示不限时
+    pub fn time_limit_for(&self, time_multiplier: u32) -> u64 {
+        self.time_limit.saturating_mul(u64::from(time_multiplier)) / 100
+    }
+
+    /// 从开始时间算起是否已超过（按倍数延长后的）答题时限加宽限时长
+    pub fn time_limit_exceeded(
+        &self,
+        started_at: Timestamp,
+        now: Timestamp,
+        grace_secs: u64,
+        time_multiplier: u32,
+    ) -> bool {
+        let time_limit = self.time_limit_for(time_multiplier);
+        time_limit > 0
+            && now.delta_since(started_at)
+                > TimeDelta::from_secs(time_limit.saturating_add(grace_secs))
+    }
+
+    /// 问题内容是否仍处于封存中
+    pub fn is_embargoed(&self) -> bool {
+        self.embargo.is_some() && !self.content_revealed
+    }
+
+    /// 问题的视图形式，封存期间返回不含内容的占位问题
+    pub fn question_views(&self) -> Vec<QuestionView> {
+        if let Some(embargo) = self.embargo.as_ref().filter(|_| !self.content_revealed) {
+            return (0..embargo.question_count)
+                .map(|id| QuestionView {
+                    id,
+                    text: String::new(),
+                    options: Vec::new(),
+                    points: 0,
+                    kind: QuestionType::MultipleChoice,
+                    media: None,
+                    screen_reader_hint: None,
+                })
+                .collect();
+        }
+        self.questions
+            .iter()
+            .map(|q| QuestionView {
+                id: q.id,
+                text: q.text.clone(),
+                options: q.options.clone(),
+                points: q.points,
+                kind: q.kind.question_type(),
+                media: q.media.clone(),
+                screen_reader_hint: q.screen_reader_hint.clone(),
+            })
+            .collect()
+    }
+
+    /// 是否为题库模式（每次答题只抽取部分问题）
+    pub fn is_pooled(&self) -> bool {
+        self.questions_per_attempt.is_some()
+    }
+
+    /// 答题者看到的问题顺序（原始问题ID）：打乱或抽题时由Quiz、用户和区块高度确定性地生成
+    pub fn question_order(&self, user: AccountOwner, block_height: BlockHeight) -> Vec<u32> {
+        let mut order: Vec<u32> = (0..self.questions.len() as u32).collect();
+        if !self.shuffle_questions && !self.is_pooled() {
+            return order;
+        }
+        let seed = CryptoHash::new(&QuestionOrderDigest {
+            quiz_id: self.id,
+            user,
+            block_height,
+        });
+        shuffle(&mut order, seed);
+        if let Some(count) = self.questions_per_attempt {
+            order.truncate(count as usize);
+            if !self.shuffle_questions {
+                order.sort_unstable();
+            }
+        }
+        order
+    }
+
+    /// 按原始顺序取出答题者抽到的问题
+    pub fn drawn_questions(&self, question_order: &[u32]) -> Vec<Question> {
+        let mut ids = question_order.to_vec();
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| self.questions.get(id as usize).cloned())
+            .collect()
+    }
+}
+
+/// 由种子确定性地打乱顺序：splitmix64 驱动的 Fisher-Yates 洗牌
+fn shuffle<T>(items: &mut [T], seed: CryptoHash) {
+    let mut state = <[u64; 4]>::from(seed)[0];
+    for i in (1..items.len()).rev() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        items.swap(i, (z % (i as u64 + 1)) as usize);
+    }
+}
+
+/// 练习卷的候选问题：来源Quiz、问题和按历史答对率划分的难度
+#[derive(Debug, Clone)]
+pub struct PoolQuestion {
+    pub quiz_id: u64,
+    pub question: Question,
+    pub difficulty: Option<QuestionDifficulty>, // 尚无答卷时为空
+}
+
+/// 从候选问题中为新练习卷抽取 count 道题，由练习卷ID、组卷者和区块高度确定性地抽取，
+/// 问题按抽取顺序重新编号
+pub fn sample_questions(
+    mut pool: Vec<PoolQuestion>,
+    count: usize,
+    quiz_id: u64,
+    user: AccountOwner,
+    block_height: BlockHeight,
+) -> Vec<Question> {
+    let seed = CryptoHash::new(&QuestionOrderDigest {
+        quiz_id,
+        user,
+        block_height,
+    });
+    shuffle(&mut pool, seed);
+    pool.into_iter()
+        .take(count)
+        .enumerate()
+        .map(|(index, entry)| Question {
+            id: index as u32,
+            ..entry.question
+        })
+        .collect()
+}
+
+/// 计算封存问题内容承诺哈希时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct QuizContentDigest {
+    questions: Vec<QuestionParams>,
+    salt: String,
+}
+
+impl BcsHashable<'_> for QuizContentDigest {}
+
+/// 封存问题内容的承诺哈希，创建者在本地计算后随 CreateQuizParams 提交
+pub fn content_commitment(questions: &[QuestionParams], salt: &str) -> CryptoHash {
+    CryptoHash::new(&QuizContentDigest {
+        questions: questions.to_vec(),
+        salt: salt.to_string(),
+    })
+}
+
+/// 计算答案校验和时使用的内容，每个问题依次为ID、正确选项和判分数据
+#[derive(Debug, Serialize, Deserialize)]
+struct AnswerKeyDigest {
+    answers: Vec<(u32, Vec<u32>, QuestionKind)>,
+    salt: String,
+}
+
+impl BcsHashable<'_> for AnswerKeyDigest {}
+
+/// 加盐的答案校验和：盐由创建者保密，否则选项数有限的问题可以被穷举；
+/// 盐公开后任何人都可以按公开的答案重新计算，确认答案在Quiz期间未被修改
+pub fn answer_key_hash(questions: &[Question], salt: &str) -> CryptoHash {
+    CryptoHash::new(&AnswerKeyDigest {
+        salt: salt.to_string(),
+        answers: questions
+            .iter()
+            .map(|question| {
+                (
+                    question.id,
+                    question.correct_options.clone(),
+                    question.kind.clone(),
+                )
+            })
+            .collect(),
+    })
+}
+
+/// 创建者在本地按创建参数计算答案校验和，随 CreateQuizParams 提交
+pub fn answer_key_commitment(questions: &[QuestionParams], salt: &str) -> CryptoHash {
+    let questions: Vec<Question> = questions
+        .iter()
+        .enumerate()
+        .map(|(i, q)| Question::new(i as u32, q.clone()))
+        .collect();
+    answer_key_hash(&questions, salt)
+}
+
+/// 生成问题顺序时使用的随机种子内容
+#[derive(Debug, Serialize, Deserialize)]
+struct QuestionOrderDigest {
+    quiz_id: u64,
+    user: AccountOwner,
+    block_height: BlockHeight,
+}
+
+impl BcsHashable<'_> for QuestionOrderDigest {}
+
+/// 计算分批开放批次时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct LaunchWaveDigest {
+    quiz_id: u64,
+    user: AccountOwner,
 }
 
+impl BcsHashable<'_> for LaunchWaveDigest {}
+
 /// 用户答题尝试
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserAttempt {
     pub quiz_id: u64,
-    pub user: String,
-    pub answers: Vec<Vec<u32>>, // 每个问题的答案选项索引列表，支持多选
-    pub score: u32,
-    pub time_taken: u64, // 毫秒
+    pub user: AccountOwner,
+    pub nick_name: String,                     // 答题者显示名
+    pub answers: Vec<Answer>,                  // 抽到的问题按原始顺序的答案
+    pub question_order: Vec<u32>,              // 答题者看到的问题顺序（原始问题ID）
+    pub score: u32,                            // 按舍入策略取整后的展示分数
+    pub fixed_score: FixedScore,               // 定点精确分数
+    pub question_results: Vec<QuestionResult>, // 每个问题的作答结果
+    pub time_taken: u64,                       // 毫秒
     pub completed_at: Timestamp,
+    pub attempt_no: u32,          // 该用户在此Quiz上的第几次答题，从1开始
+    pub receipt: CryptoHash,      // 提交回执ID
+    pub answer_changes: Vec<u32>, // 逐题保存时各问题答案的修改次数，与 answers 对应，整份提交时为空
+    pub passed: Option<bool>,     // 是否达到及格分，Quiz未设置及格分时为空
+}
+
+/// 问题的累计作答结果，生效答卷计入后更新
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct QuestionTally {
+    /// 抽到该问题的答卷数
+    pub answered: u64,
+    /// 答对的答卷数
+    pub correct: u64,
+    /// 跳过的答卷数
+    pub skipped: u64,
+}
+
+impl QuestionTally {
+    /// 答对率（百分比，向下取整），没有答卷时为空
+    pub fn accuracy(&self) -> Option<u32> {
+        (self.answered > 0).then(|| (self.correct * 100 / self.answered) as u32)
+    }
+
+    /// 按答对率划分的难度，没有答卷时为空
+    pub fn difficulty(&self) -> Option<QuestionDifficulty> {
+        (self.answered > 0).then(|| QuestionDifficulty::from_results(self.correct, self.answered))
+    }
+}
+
+/// 单个问题的作答结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuestionResult {
+    pub question_id: u32,
+    /// 答案是否与正确选项完全一致
+    pub correct: bool,
+    /// 未作答、留空或明确表示不会
+    pub skipped: bool,
+    /// 实际得分（定点），申诉成立后可能被调整
+    pub points_awarded: FixedScore,
+    /// 问答题人工评分时每条评分标准选中的等级，未评分时为空
+    pub rubric_levels: Vec<u32>,
+}
+
+/// 尚未开始的Quiz的审阅预览令牌
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PreviewToken {
+    pub quiz_id: u64,
+    pub label: String,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+impl PreviewToken {
+    /// 令牌在给定时间是否仍可用于该Quiz：未过期且Quiz尚未开始
+    pub fn is_valid_for(&self, quiz: &QuizSet, now: Timestamp) -> bool {
+        now <= self.expires_at && !quiz.cancelled && !quiz.has_started(now)
+    }
+}
+
+/// 计算预览令牌时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviewTokenDigest {
+    quiz_id: u64,
+    label: String,
+    block_height: BlockHeight,
+}
+
+impl BcsHashable<'_> for PreviewTokenDigest {}
+
+/// 预览令牌：由Quiz、审阅者标签和区块高度确定性地计算
+pub fn preview_token(quiz_id: u64, label: &str, block_height: BlockHeight) -> CryptoHash {
+    CryptoHash::new(&PreviewTokenDigest {
+        quiz_id,
+        label: label.to_string(),
+        block_height,
+    })
+}
+
+/// 计算预览令牌哈希时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviewTokenHashDigest {
+    token: CryptoHash,
+}
+
+impl BcsHashable<'_> for PreviewTokenHashDigest {}
+
+/// 预览令牌的哈希，用于在不泄露令牌本身的情况下标识令牌
+pub fn preview_token_hash(token: &CryptoHash) -> CryptoHash {
+    CryptoHash::new(&PreviewTokenHashDigest { token: *token })
+}
+
+/// 计算提交回执ID时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct AttemptReceiptDigest {
+    quiz_id: u64,
+    user: AccountOwner,
+    attempt_no: u32,
+    block_height: BlockHeight,
+}
+
+impl BcsHashable<'_> for AttemptReceiptDigest {}
+
+impl UserAttempt {
+    /// 提交回执ID：由Quiz、用户、答题序号和区块高度确定性地计算
+    pub fn receipt_for(
+        quiz_id: u64,
+        user: AccountOwner,
+        attempt_no: u32,
+        block_height: BlockHeight,
+    ) -> CryptoHash {
+        CryptoHash::new(&AttemptReceiptDigest {
+            quiz_id,
+            user,
+            attempt_no,
+            block_height,
+        })
+    }
+}
+
+/// 进行中的答题会话
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttemptSession {
+    pub quiz_id: u64,
+    pub user: AccountOwner,
+    pub nick_name: String,            // 答题者显示名
+    pub answers: Vec<Option<Answer>>, // 按原始问题位置保存，未作答为 None
+    pub question_order: Vec<u32>,     // 答题者看到的问题顺序（原始问题ID）
+    pub started_at: Timestamp,
+    pub last_activity: Timestamp,
+    pub last_answer_at: Timestamp, // 最近一次保存答案的时间，未保存过时为开始时间
+    pub answer_times: Vec<u64>,    // 按原始问题位置累计的答题用时（毫秒）
+    pub answer_changes: Vec<u32>,  // 按原始问题位置记录已保存的答案被修改的次数
+}
+
+/// 破坏性管理操作提案
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdminProposal {
+    pub id: u64,
+    pub action: AdminAction,
+    pub confirmations: Vec<Principal>,
+    pub executed: bool,
+    pub created_at: Timestamp,
+}
+
+/// 组织
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Organization {
+    pub slug: String,
+    pub name: String,
+    pub owner: AccountOwner,
+    pub branding: Vec<(String, String)>,
+    pub settings: OrgSettings,
+    pub created_at: Timestamp,
+}
+
+impl Organization {
+    /// 品牌元数据的视图形式
+    pub fn branding_entries(&self) -> Vec<MetadataEntry> {
+        self.branding
+            .iter()
+            .map(|(key, value)| MetadataEntry {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    }
+}
+
+/// 冻结的排行榜修订版，写入后不再修改
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaderboardSnapshot {
+    pub revision: u32,
+    pub frozen_at: Timestamp,
+    pub entries: Vec<super::LeaderboardEntry>,
+}
+
+/// 应用账户托管的Quiz奖池
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrizePool {
+    pub funder: AccountOwner,
+    pub amount: Amount,                          // 创建者出资的金额
+    pub entry_fees: Amount,                      // 发奖时计入的参赛费，发放前为0
+    pub splits: Vec<u32>,                        // 按最终排名分配的百分比
+    pub distribution: Option<PrizeDistribution>, // 发放记录，发放前为空
+    pub token_prizes: Vec<super::TokenPrize>,    // 赞助者追加的代币奖金
+}
+
+/// 等待获奖者领取的奖金
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UnclaimedPrize {
+    pub rank: u32,
+    pub amount: Amount,
+    pub claim_deadline: Timestamp,
+}
+
+/// 逾期未领奖金的清扫记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrizeSweep {
+    pub rank: u32,
+    pub amount: Amount,
+    pub recipient: AccountOwner, // 收到清扫奖金的金库或出资者
+    pub swept_at: Timestamp,
+}
+
+/// 奖池的发放记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrizeDistribution {
+    pub payouts: Vec<super::PrizePayout>,
+    pub refunded: Amount, // 退回给出资者的金额
+    pub distributed_at: Timestamp,
+    pub block_height: BlockHeight,
+}
+
+/// Quiz结果最终确定的记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Finalization {
+    pub finalized_at: Timestamp,
+    pub block_height: BlockHeight,
+    pub revision: u32,                          // 最终结果所在的排行榜修订版
+    pub standings_hash: CryptoHash,             // 最终排行榜的哈希，见 standings_hash
+    pub token_rewards: Vec<super::PrizePayout>, // 转给前几名的奖励代币
+}
+
+/// 已归档Quiz的摘要，Quiz本身和答题记录已被删除
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchivedQuiz {
+    pub title: String,
+    pub creator: AccountOwner,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub cancelled: bool,
+    pub participants: u32,
+    pub attempts: u32,
+    pub total_score: u64,                        // 各份答卷得分之和
+    pub standings: Vec<super::LeaderboardEntry>, // 最终排行榜
+    pub standings_hash: Option<CryptoHash>,      // 见 standings_hash
+    pub archived_at: Timestamp,
+}
+
+impl ArchivedQuiz {
+    /// 每份答卷的平均得分（向下取整），没有答卷时为 None
+    pub fn average_score(&self) -> Option<u32> {
+        (self.attempts > 0).then(|| (self.total_score / u64::from(self.attempts)) as u32)
+    }
+}
+
+/// 奖项名次同分同时的答题者之间的加赛
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Playoff {
+    pub playoff_quiz_id: u64,
+    pub tied_users: Vec<AccountOwner>, // 按原排行榜顺序
+    pub created_at: Timestamp,
+}
+
+/// 用户通知
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub quiz_id: u64,
+    pub message: String,
+    pub created_at: Timestamp,
+    pub read: bool,
+}
+
+/// 从订阅的链收到的Quiz公告
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoteQuiz {
+    pub announcement: QuizAnnouncement,
+    pub standings_hash: Option<CryptoHash>, // 收到最终结果公告后的排行榜哈希
+    pub refreshed_at: Timestamp,            // 最近一次收到公告的本链时间
+}
+
+/// 计算最终排行榜哈希时使用的内容
+#[derive(Debug, Serialize, Deserialize)]
+struct StandingsDigest {
+    quiz_id: u64,
+    revision: u32,
+    entries: Vec<super::LeaderboardEntry>,
+}
+
+impl BcsHashable<'_> for StandingsDigest {}
+
+/// 最终排行榜的哈希，验证者可以用公开的排行榜修订版重新计算
+pub fn standings_hash(
+    quiz_id: u64,
+    revision: u32,
+    entries: &[super::LeaderboardEntry],
+) -> CryptoHash {
+    CryptoHash::new(&StandingsDigest {
+        quiz_id,
+        revision,
+        entries: entries.to_vec(),
+    })
+}
+
+/// 前 places 个名次中同分且用时相同的答题者，按排行榜顺序排列；
+/// 从奖项名次开始的同分组整组计入，即使延伸到奖项名次之外
+pub fn prize_ties(entries: &[super::LeaderboardEntry], places: usize) -> Vec<AccountOwner> {
+    let mut tied = Vec::new();
+    let mut start = 0;
+    while start < entries.len().min(places) {
+        let head = &entries[start];
+        let end = entries[start..]
+            .iter()
+            .position(|entry| entry.score != head.score || entry.time_taken != head.time_taken)
+            .map_or(entries.len(), |offset| start + offset);
+        if end - start > 1 {
+            tied.extend(entries[start..end].iter().map(|entry| entry.user));
+        }
+        start = end;
+    }
+    tied
+}
+
+/// 按加赛成绩重排同分且用时相同的答题者：加赛分数高者在前，同分时用时短者在前，
+/// 未参加加赛的排在参加者之后，其余情况保持原顺序
+pub fn playoff_standings(
+    entries: &[super::LeaderboardEntry],
+    playoff_entries: &[super::LeaderboardEntry],
+) -> Vec<super::LeaderboardEntry> {
+    let playoff_key = |user: &AccountOwner| {
+        playoff_entries
+            .iter()
+            .find(|entry| entry.user == *user)
+            .map(|entry| (std::cmp::Reverse(entry.score), entry.time_taken))
+    };
+    let mut standings = entries.to_vec();
+    let mut start = 0;
+    while start < standings.len() {
+        let (score, time_taken) = (standings[start].score, standings[start].time_taken);
+        let end = standings[start..]
+            .iter()
+            .position(|entry| entry.score != score || entry.time_taken != time_taken)
+            .map_or(standings.len(), |offset| start + offset);
+        standings[start..end].sort_by_key(|entry| {
+            let key = playoff_key(&entry.user);
+            (key.is_none(), key)
+        });
+        start = end;
+    }
+    standings
+}
+
+/// 用户档案
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserProfile {
+    pub trophies: Vec<Trophy>, // 排行榜冻结时获得的前三名奖杯
+    pub streak: CompletionStreak,
+    pub nick_name: Option<String>, // 登记的唯一昵称
+    pub stats: UserStats,
+}
+
+/// 用户的累计统计，随答卷评分和创建Quiz更新；练习卷不计入，删除Quiz后不回退
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserStats {
+    pub quizzes_taken: u32, // 有已评分答卷的不同Quiz数
+    pub quizzes_created: u32,
+    pub graded_attempts: u32,
+    pub total_points: u64,      // 各次答卷得分之和
+    pub best_rank: Option<u32>, // 提交答卷时在Quiz排行榜上取得的最好名次
+}
+
+impl UserStats {
+    /// 记录一份已评分的答卷，first_in_quiz 表示用户第一次在该Quiz有答卷
+    pub fn record_attempt(&mut self, first_in_quiz: bool, score: u32, rank: u32) {
+        if first_in_quiz {
+            self.quizzes_taken += 1;
+        }
+        self.graded_attempts += 1;
+        self.total_points += u64::from(score);
+        self.best_rank = Some(self.best_rank.map_or(rank, |best| best.min(rank)));
+    }
+
+    /// 每份答卷的平均得分（向下取整），没有答卷时为 None
+    pub fn average_score(&self) -> Option<u32> {
+        (self.graded_attempts > 0)
+            .then(|| (self.total_points / u64::from(self.graded_attempts)) as u32)
+    }
+}
+
+/// 昵称索引的键：不区分大小写
+pub fn nickname_key(nick_name: &str) -> String {
+    nick_name.to_lowercase()
+}
+
+/// 一天的微秒数
+pub const DAY_MICROS: u64 = 24 * 60 * 60 * 1_000_000;
+
+/// 每日完成Quiz的连续天数
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CompletionStreak {
+    pub current: u32,
+    pub longest: u32,
+    pub last_day: Option<u64>, // 最近一次完成Quiz的日期（自Unix纪元起的天数）
+}
+
+impl CompletionStreak {
+    /// 时间点所在的日期（自Unix纪元起的天数）
+    pub fn day_of(time: Timestamp) -> u64 {
+        time.micros() / DAY_MICROS
+    }
+
+    /// 记录一次完成，返回记录后的连续天数；同一天内多次完成只计一天
+    pub fn record_completion(&mut self, time: Timestamp) -> u32 {
+        let day = Self::day_of(time);
+        match self.last_day {
+            Some(last_day) if last_day == day => {}
+            Some(last_day) if last_day + 1 == day => self.current += 1,
+            _ => self.current = 1,
+        }
+        self.last_day = Some(day);
+        self.longest = self.longest.max(self.current);
+        self.current
+    }
+
+    /// 截至 now 仍未中断的连续天数：最近一次完成不在今天或昨天时为0
+    pub fn active_at(&self, now: Timestamp) -> u32 {
+        match self.last_day {
+            Some(last_day) if last_day + 1 >= Self::day_of(now) => self.current,
+            _ => 0,
+        }
+    }
+}
+
+/// 跨Quiz的累计积分，随每个Quiz计入排行榜的成绩变化增量维护
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GlobalStanding {
+    pub points: FixedScore,     // 各Quiz计入排行榜的成绩之和
+    pub quizzes_completed: u32, // 计入积分的Quiz数
+    pub nick_name: String,
+    pub updated_at: Timestamp,
+}
+
+/// 按时间衰减的全局积分，每次提交时先衰减到当天再累加
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DecayedScore {
+    pub points: FixedScore, // 衰减到 day 当天的积分
+    pub day: u64,           // 最近一次更新的日期（自Unix纪元起的天数）
+    pub nick_name: String,
+    pub updated_at: Timestamp,
+}
+
+impl DecayedScore {
+    /// 衰减到指定日期的积分：每经过一个半衰期减半，半衰期之内按线性插值
+    pub fn points_at(&self, day: u64, half_life_days: u32) -> FixedScore {
+        let half_life = u64::from(half_life_days.max(1));
+        let elapsed = day.saturating_sub(self.day);
+        let halvings = elapsed / half_life;
+        if halvings >= u64::from(FixedScore::BITS) {
+            return 0;
+        }
+        let halved = i128::from(self.points >> halvings);
+        let remainder = i128::from(elapsed % half_life);
+        let decayed = halved - halved * remainder / (2 * i128::from(half_life));
+        decayed as FixedScore
+    }
+
+    /// 把积分衰减到 time 所在的日期后累加本次获得的积分
+    pub fn add(&mut self, points: FixedScore, time: Timestamp, half_life_days: u32) {
+        let day = CompletionStreak::day_of(time).max(self.day);
+        self.points = self.points_at(day, half_life_days).saturating_add(points);
+        self.day = day;
+        self.updated_at = time;
+    }
+}
+
+/// Quiz排行榜前三名的奖杯
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Trophy {
+    pub quiz_id: u64,
+    pub place: u32, // 名次，1到3
+    pub score: u32,
+    pub awarded_at: Timestamp,
+}
+
+/// 问题申诉记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuestionAppeal {
+    pub voters: Vec<AccountOwner>,
+    pub status: DisputeStatus,
 }
 
 /// Quiz应用状态
@@ -48,14 +946,683 @@ pub struct UserAttempt {
 pub struct QuizState {
     /// 存储所有Quiz集合 (QuizId -> QuizSet)
     pub quiz_sets: MapView<u64, QuizSet>,
-    /// 存储用户答题尝试 ((QuizId, User) -> UserAttempt)
-    pub user_attempts: MapView<(u64, String), UserAttempt>,
+    /// 存储用户答题尝试 ((QuizId, User, AttemptNo) -> UserAttempt)
+    pub user_attempts: MapView<(u64, AccountOwner, u32), UserAttempt>,
+    /// 每个Quiz已记录的答卷 (QuizId -> Vec<(User, AttemptNo)>)，按记录顺序
+    pub quiz_attempt_index: MapView<u64, Vec<(AccountOwner, u32)>>,
+    /// 每个用户已记录的答卷 (User -> Vec<(QuizId, AttemptNo)>)，按记录顺序
+    pub user_attempt_index: MapView<AccountOwner, Vec<(u64, u32)>>,
     /// 记录答题事件用于排行榜计算
     pub quiz_events: LogView<UserAttempt>,
     /// 下一个可用的Quiz ID
     pub next_quiz_id: RegisterView<u64>,
     /// 用户参与的测验集合 (User -> Vec<QuizId>)
-    pub user_participations: MapView<String, Vec<u64>>,
+    pub user_participations: MapView<AccountOwner, Vec<u64>>,
+    /// 用户创建的测验集合 (Creator -> Vec<QuizId>)
+    pub creator_quizzes: MapView<AccountOwner, Vec<u64>>,
     /// 测验排行榜 (QuizId -> Vec<super::LeaderboardEntry>)
     pub leaderboard: MapView<u64, Vec<super::LeaderboardEntry>>,
+    /// 开启答题隐私的用户
+    pub private_users: SetView<AccountOwner>,
+    /// 等待监考人确认的答卷 ((QuizId, User) -> UserAttempt)
+    pub pending_attempts: MapView<(u64, AccountOwner), UserAttempt>,
+    /// 进行中的答题会话 ((QuizId, User) -> AttemptSession)
+    pub attempt_sessions: MapView<(u64, AccountOwner), AttemptSession>,
+    /// 应用事件流中下一个事件的序号
+    pub next_event_index: RegisterView<u32>,
+    /// 管理员
+    pub admins: SetView<Principal>,
+    /// 管理操作提案 (ProposalId -> AdminProposal)
+    pub admin_proposals: MapView<u64, AdminProposal>,
+    /// 下一个可用的提案ID
+    pub next_proposal_id: RegisterView<u64>,
+    /// 答题者的私人笔记 ((QuizId, User) -> Note)
+    pub attempt_notes: MapView<(u64, AccountOwner), String>,
+    /// 问题申诉 ((QuizId, QuestionId) -> QuestionAppeal)
+    pub question_appeals: MapView<(u64, u32), QuestionAppeal>,
+    /// 创建者的问题指纹索引 ((Creator, Fingerprint) -> Vec<QuestionRef>)
+    pub question_fingerprints: MapView<(AccountOwner, CryptoHash), Vec<QuestionRef>>,
+    /// 提交回执索引 (Receipt -> (QuizId, User, AttemptNo))
+    pub attempt_receipts: MapView<CryptoHash, (u64, AccountOwner, u32)>,
+    /// 冻结的排行榜修订版 (QuizId -> Vec<LeaderboardSnapshot>)
+    pub leaderboard_snapshots: MapView<u64, Vec<LeaderboardSnapshot>>,
+    /// 组织 (Slug -> Organization)
+    pub orgs: MapView<String, Organization>,
+    /// 组织成员 (Slug, Member)
+    pub org_members: SetView<(String, AccountOwner)>,
+    /// 组织管理员 (Slug, Admin)
+    pub org_admins: SetView<(String, AccountOwner)>,
+    /// 用户档案 (User -> UserProfile)
+    pub user_profiles: MapView<AccountOwner, UserProfile>,
+    /// 昵称索引 (小写昵称 -> User)
+    pub nicknames: MapView<String, AccountOwner>,
+    /// 平台禁排时段 (WindowId -> BlackoutWindow)
+    pub blackout_windows: MapView<u64, BlackoutWindow>,
+    /// 下一个禁排时段ID
+    pub next_blackout_id: RegisterView<u64>,
+    /// 订阅了本链Quiz公告的链
+    pub subscribers: SetView<ChainId>,
+    /// 从订阅的链收到的Quiz公告 ((HostChainId, QuizId) -> RemoteQuiz)
+    pub remote_quizzes: MapView<(ChainId, u64), RemoteQuiz>,
+    /// 收藏的问题 ((User, QuizId, QuestionId) -> 收藏时间)
+    pub bookmarks: MapView<(AccountOwner, u64, u32), Timestamp>,
+    /// 每个用户收藏的问题 (User -> Vec<(QuizId, QuestionId)>)
+    pub user_bookmark_index: MapView<AccountOwner, Vec<(u64, u32)>>,
+    /// Quiz浏览次数 (QuizId -> Count)
+    pub quiz_views: MapView<u64, u64>,
+    /// 浏览过Quiz的已认证用户 (QuizId -> Users)
+    pub quiz_viewers: CollectionView<u64, SetView<AccountOwner>>,
+    /// 开始过答题的用户 (QuizId -> Users)
+    pub quiz_starters: CollectionView<u64, SetView<AccountOwner>>,
+    /// 在Quiz上留有 (QuizId, User) 记录的用户 (QuizId -> Users)：会话、答卷、托管的参赛费、
+    /// 笔记、收藏、额外次数和时间延长等，按Quiz读取或清理这些记录时只访问这些用户的键
+    pub quiz_participants: CollectionView<u64, SetView<AccountOwner>>,
+    /// 文本答案的指纹 ((QuizId, QuestionId, User, AttemptNo) -> 片段哈希)
+    pub answer_fingerprints: MapView<(u64, u32, AccountOwner, u32), Vec<u64>>,
+    /// 用户通知 (User -> NotificationId -> Notification)
+    pub notifications: CollectionView<AccountOwner, MapView<u64, Notification>>,
+    /// 下一个通知ID
+    pub next_notification_id: RegisterView<u64>,
+    /// 已铸造的完成证书 ((QuizId, User) -> 铸造时间)
+    pub certificates: MapView<(u64, AccountOwner), Timestamp>,
+    /// 标签索引 (Tag -> 按ID升序的QuizId)
+    pub tag_index: MapView<String, Vec<u64>>,
+    /// 分类索引 (Category -> 按ID升序的QuizId)，未分类的Quiz不登记
+    pub category_index: MapView<String, Vec<u64>>,
+    /// 标题和描述的倒排索引 (搜索词 -> 按ID升序的QuizId)
+    pub search_index: MapView<String, Vec<u64>>,
+    /// 结果最终确定的记录 (QuizId -> Finalization)
+    pub finalizations: MapView<u64, Finalization>,
+    /// 奖项名次同分同时时创建的加赛 (QuizId -> Playoff)
+    pub playoffs: MapView<u64, Playoff>,
+    /// 已归档并删除的Quiz摘要 (QuizId -> ArchivedQuiz)
+    pub archived_quizzes: MapView<u64, ArchivedQuiz>,
+    /// 审阅预览令牌 (Token -> PreviewToken)
+    pub preview_tokens: MapView<CryptoHash, PreviewToken>,
+    /// 每个Quiz的预览令牌 (QuizId -> Vec<Token>)
+    pub quiz_preview_tokens: MapView<u64, Vec<CryptoHash>>,
+    /// 创建者的Quiz保留天数 (Creator -> Days)
+    pub retention_policies: MapView<AccountOwner, u32>,
+    /// 托管的参赛费 ((QuizId, User) -> Amount)
+    pub escrow: MapView<(u64, AccountOwner), Amount>,
+    /// 托管的奖池 (QuizId -> PrizePool)
+    pub prize_pools: MapView<u64, PrizePool>,
+    /// 待领取的奖池奖金 ((QuizId, User) -> UnclaimedPrize)
+    pub unclaimed_prizes: MapView<(u64, AccountOwner), UnclaimedPrize>,
+    /// 逾期奖金的清扫记录 ((QuizId, User) -> PrizeSweep)
+    pub prize_sweeps: MapView<(u64, AccountOwner), PrizeSweep>,
+    /// 创建者额外增加的答题次数 ((QuizId, User) -> Count)
+    pub extra_attempts: MapView<(u64, AccountOwner), u32>,
+    /// 创建者公告 (QuizId -> 按发布时间排列的公告)
+    pub announcements: MapView<u64, Vec<CreatorAnnouncement>>,
+    /// 延长答题时间的倍数 ((QuizId, User) -> 百分比)，未登记时为100
+    pub accommodations: MapView<(u64, AccountOwner), u32>,
+    /// 已参加Quiz的已验证身份 (QuizId -> IdentityId -> User)
+    pub quiz_identities: CollectionView<u64, MapView<String, AccountOwner>>,
+    /// 逐题答题用时样本 ((QuizId, QuestionId) -> Vec<毫秒>)
+    pub question_times: MapView<(u64, u32), Vec<u64>>,
+    /// 逐题累计作答结果 ((QuizId, QuestionId) -> QuestionTally)
+    pub question_tallies: MapView<(u64, u32), QuestionTally>,
+    /// 赛季积分 ((Season, User) -> Points)
+    pub season_points: MapView<(u64, AccountOwner), u64>,
+    /// 按时间衰减的全局积分 (User -> DecayedScore)
+    pub decayed_ranking: MapView<AccountOwner, DecayedScore>,
+    /// 跨Quiz的累计积分 (User -> GlobalStanding)
+    pub global_standings: MapView<AccountOwner, GlobalStanding>,
+    /// 每个Quiz计入累计积分的成绩 ((QuizId, User) -> FixedScore)
+    pub global_contributions: MapView<(u64, AccountOwner), FixedScore>,
+}
+
+impl QuizState {
+    /// 用户在组织中的最高角色，不属于该组织或组织不存在时为空
+    pub async fn org_role(&self, org: &str, user: &AccountOwner) -> Option<OrgRole> {
+        let organization = self.orgs.get(org).await.ok()??;
+        let key = (org.to_string(), *user);
+        if organization.owner == *user {
+            Some(OrgRole::Owner)
+        } else if self.org_admins.contains(&key).await.unwrap_or(false) {
+            Some(OrgRole::Admin)
+        } else if self.org_members.contains(&key).await.unwrap_or(false) {
+            Some(OrgRole::Member)
+        } else {
+            None
+        }
+    }
+
+    /// 按Quiz分组的用户集合（如浏览者、开始答题者）中某个Quiz的前 limit 个用户
+    pub async fn quiz_users(
+        users: &CollectionView<u64, SetView<AccountOwner>>,
+        quiz_id: u64,
+        limit: usize,
+    ) -> Result<Vec<AccountOwner>, ViewError> {
+        let mut found = Vec::new();
+        if let Some(users) = users.try_load_entry(&quiz_id).await? {
+            users
+                .for_each_index_while(|user| {
+                    if found.len() == limit {
+                        return Ok(false);
+                    }
+                    found.push(user);
+                    Ok(true)
+                })
+                .await?;
+        }
+        Ok(found)
+    }
+
+    /// 用户在Quiz上已生效的全部答卷，按答题序号排列
+    pub async fn user_quiz_attempts(
+        &self,
+        quiz_id: u64,
+        user: &AccountOwner,
+    ) -> Result<Vec<UserAttempt>, ViewError> {
+        let mut attempts = Vec::new();
+        for (u, attempt_no) in self
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default()
+        {
+            if u == *user {
+                if let Some(attempt) = self.user_attempts.get(&(quiz_id, u, attempt_no)).await? {
+                    attempts.push(attempt);
+                }
+            }
+        }
+        attempts.sort_by_key(|attempt| attempt.attempt_no);
+        Ok(attempts)
+    }
+
+    /// 用户可以回顾（查看答案、收藏）的问题ID：答题次数已用完或Quiz已结束后才可回顾，
+    /// 题库模式只含该用户答卷中抽到的问题；尚不能回顾时为空
+    pub async fn reviewable_questions(
+        &self,
+        quiz: &QuizSet,
+        user: &AccountOwner,
+        now: Timestamp,
+    ) -> Result<Option<BTreeSet<u32>>, ViewError> {
+        let mut attempts = self.user_quiz_attempts(quiz.id, user).await?;
+        let extra_attempts = self
+            .extra_attempts
+            .get(&(quiz.id, *user))
+            .await?
+            .unwrap_or_default();
+        let attempts_left =
+            attempts.len() < quiz.max_attempts.saturating_add(extra_attempts) as usize;
+        if attempts_left && now <= quiz.end_time {
+            return Ok(None);
+        }
+        if !quiz.is_pooled() {
+            return Ok(Some((0..quiz.questions.len() as u32).collect()));
+        }
+        attempts.extend(self.pending_attempts.get(&(quiz.id, *user)).await?);
+        Ok(Some(
+            attempts
+                .iter()
+                .flat_map(|attempt| attempt.question_order.iter().copied())
+                .collect(),
+        ))
+    }
+
+    /// 创建者往期Quiz中可用于练习卷的问题，按Quiz和问题ID排列。只取已结束、结果已公布、
+    /// 内容已公开的公开Quiz：练习卷对所有人开放，组织和仅限成员的Quiz不纳入；
+    /// 问答题需要人工评分，不纳入练习卷。tags 为空时不限标签，否则Quiz须带有其中任一标签
+    pub async fn practice_pool(
+        &self,
+        creator: &AccountOwner,
+        tags: &[String],
+        difficulty: Option<QuestionDifficulty>,
+        now: Timestamp,
+    ) -> Result<Vec<PoolQuestion>, ViewError> {
+        let mut quiz_ids = self.creator_quizzes.get(creator).await?.unwrap_or_default();
+        quiz_ids.sort_unstable();
+        let mut pool = Vec::new();
+        for quiz_id in quiz_ids {
+            let Some(quiz) = self.quiz_sets.get(&quiz_id).await? else {
+                continue;
+            };
+            if quiz.cancelled
+                || quiz.playoff_of.is_some()
+                || now <= quiz.end_time
+                || !quiz.results_visible(now)
+                || quiz.is_embargoed()
+                || quiz.members_only
+                || quiz.org.is_some()
+                || (!tags.is_empty() && !quiz.tags.iter().any(|tag| tags.contains(tag)))
+            {
+                continue;
+            }
+            for question in quiz.questions {
+                if matches!(question.kind, QuestionKind::Essay { .. }) {
+                    continue;
+                }
+                let question_difficulty = self
+                    .question_tallies
+                    .get(&(quiz_id, question.id))
+                    .await?
+                    .and_then(|tally| tally.difficulty());
+                if difficulty.is_some_and(|difficulty| question_difficulty != Some(difficulty)) {
+                    continue;
+                }
+                pool.push(PoolQuestion {
+                    quiz_id,
+                    question,
+                    difficulty: question_difficulty,
+                });
+            }
+        }
+        Ok(pool)
+    }
+
+    /// 在Quiz上留有记录的全部用户
+    pub async fn participants_of(&self, quiz_id: u64) -> Result<Vec<AccountOwner>, ViewError> {
+        Self::quiz_users(&self.quiz_participants, quiz_id, usize::MAX).await
+    }
+
+    /// Quiz中等待监考人确认的答卷，只读取该Quiz参与者的键
+    pub async fn quiz_pending_attempts(&self, quiz_id: u64) -> Result<Vec<UserAttempt>, ViewError> {
+        let mut attempts = Vec::new();
+        for user in self.participants_of(quiz_id).await? {
+            attempts.extend(self.pending_attempts.get(&(quiz_id, user)).await?);
+        }
+        Ok(attempts)
+    }
+
+    /// Quiz的全部已记录答卷，只读取该Quiz的索引而不扫描所有答卷
+    pub async fn quiz_attempts(&self, quiz_id: u64) -> Result<Vec<UserAttempt>, ViewError> {
+        let mut attempts = Vec::new();
+        for (user, attempt_no) in self
+            .quiz_attempt_index
+            .get(&quiz_id)
+            .await?
+            .unwrap_or_default()
+        {
+            if let Some(attempt) = self.user_attempts.get(&(quiz_id, user, attempt_no)).await? {
+                attempts.push(attempt);
+            }
+        }
+        Ok(attempts)
+    }
+
+    /// 全部平台禁排时段
+    pub async fn blackout_window_list(&self) -> Result<Vec<(u64, BlackoutWindow)>, ViewError> {
+        let mut windows = Vec::new();
+        self.blackout_windows
+            .for_each_index_value(|id, window| {
+                windows.push((id, window.into_owned()));
+                Ok(())
+            })
+            .await?;
+        Ok(windows)
+    }
+
+    /// 用户是否属于组织，组织所有者和管理员也算作成员
+    pub async fn is_org_member(&self, org: &str, user: &AccountOwner) -> bool {
+        self.org_role(org, user).await.is_some()
+    }
+
+    /// 用户是否为组织管理员，组织所有者也算作管理员
+    pub async fn is_org_admin(&self, org: &str, user: &AccountOwner) -> bool {
+        self.org_role(org, user).await >= Some(OrgRole::Admin)
+    }
+
+    /// 预览令牌对应的Quiz，令牌不存在、已过期或Quiz已开始时为 None
+    pub async fn preview_quiz(
+        &self,
+        token: &CryptoHash,
+        now: Timestamp,
+    ) -> Result<Option<QuizSet>, ViewError> {
+        let Some(preview) = self.preview_tokens.get(token).await? else {
+            return Ok(None);
+        };
+        let quiz = self.quiz_sets.get(&preview.quiz_id).await?;
+        Ok(quiz.filter(|quiz| preview.is_valid_for(quiz, now)))
+    }
+
+    /// 用户能否查看和参加Quiz：仅成员可见的Quiz只对创建者和组织成员开放，
+    /// 加赛只对创建者和原Quiz中同分同时的答题者开放
+    pub async fn can_access_quiz(&self, quiz_set: &QuizSet, user: Option<&AccountOwner>) -> bool {
+        if let Some(quiz_id) = quiz_set.playoff_of {
+            let Some(user) = user else {
+                return false;
+            };
+            return *user == quiz_set.creator
+                || self
+                    .playoffs
+                    .get(&quiz_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some_and(|playoff| playoff.tied_users.contains(user));
+        }
+        if !quiz_set.members_only {
+            return true;
+        }
+        let (Some(user), Some(org)) = (user, quiz_set.org.as_deref()) else {
+            return false;
+        };
+        *user == quiz_set.creator || self.is_org_member(org, user).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000_000;
+    const SECOND: u64 = 1_000_000;
+
+    fn at(micros: u64) -> Timestamp {
+        Timestamp::from(micros)
+    }
+
+    fn owner(seed: u64) -> AccountOwner {
+        AccountOwner::Address32(CryptoHash::from([seed; 4]))
+    }
+
+    fn quiz() -> QuizSet {
+        QuizSet {
+            id: 7,
+            title: "Quiz".to_string(),
+            description: String::new(),
+            creator: owner(0),
+            creator_name: "Creator".to_string(),
+            questions: Vec::new(),
+            time_limit: 0,
+            start_time: at(NOW),
+            end_time: at(NOW + 3_600 * SECOND),
+            created_at: at(NOW - 3_600 * SECOND),
+            practice: false,
+            playoff_of: None,
+            hide_results: false,
+            reveal_delay: None,
+            results_revealed: false,
+            proctor: None,
+            owner: None,
+            metadata: Vec::new(),
+            start_mode: QuizStartMode::Scheduled,
+            scoring: ScoringStrategy::default(),
+            allow_dont_know: false,
+            org: None,
+            members_only: false,
+            shuffle_questions: false,
+            questions_per_attempt: None,
+            embargo: None,
+            max_attempts: 1,
+            attempt_policy: AttemptPolicy::default(),
+            attestation_app: None,
+            entry_fee: None,
+            category: String::new(),
+            tags: Vec::new(),
+            pass_score: None,
+            soft_launch: None,
+            answer_key_hash: None,
+            answer_key_salt: None,
+            content_revealed: false,
+            is_started: false,
+            cancelled: false,
+            finalized: false,
+        }
+    }
+
+    #[test]
+    fn results_are_visible_unless_hidden() {
+        let quiz = quiz();
+        assert!(quiz.results_visible(at(NOW)));
+        assert!(!quiz.answer_key_public(quiz.end_time));
+        assert!(quiz.answer_key_public(at(NOW + 3_601 * SECOND)));
+    }
+
+    #[test]
+    fn hidden_results_wait_for_manual_reveal() {
+        let mut quiz = quiz();
+        quiz.hide_results = true;
+        let long_after = at(NOW + 365 * 24 * 3_600 * SECOND);
+        assert!(!quiz.results_visible(long_after));
+        assert!(!quiz.answer_key_public(long_after));
+        quiz.results_revealed = true;
+        assert!(quiz.results_visible(at(NOW)));
+        assert!(quiz.answer_key_public(long_after));
+    }
+
+    #[test]
+    fn hidden_results_reveal_after_the_delay() {
+        let mut quiz = quiz();
+        quiz.hide_results = true;
+        quiz.reveal_delay = Some(60);
+        let reveal_at = quiz.end_time.saturating_add(TimeDelta::from_secs(60));
+        assert!(!quiz.results_visible(quiz.end_time));
+        assert!(!quiz.results_visible(at(reveal_at.micros() - 1)));
+        assert!(quiz.results_visible(reveal_at));
+        assert!(quiz.answer_key_public(reveal_at));
+    }
+
+    fn question(id: u32) -> Question {
+        Question {
+            id,
+            text: format!("Question {id}"),
+            options: vec!["A".to_string(), "B".to_string()],
+            correct_options: vec![0],
+            points: 1,
+            kind: QuestionKind::MultipleChoice,
+            explanation: None,
+            media: None,
+            screen_reader_hint: None,
+        }
+    }
+
+    fn quiz_with_questions(count: u32) -> QuizSet {
+        QuizSet {
+            questions: (0..count).map(question).collect(),
+            ..quiz()
+        }
+    }
+
+    #[test]
+    fn question_order_is_identity_without_shuffle_or_pool() {
+        let quiz = quiz_with_questions(5);
+        assert_eq!(
+            quiz.question_order(owner(1), BlockHeight(3)),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn shuffled_order_is_a_deterministic_permutation() {
+        let mut quiz = quiz_with_questions(20);
+        quiz.shuffle_questions = true;
+        let order = quiz.question_order(owner(1), BlockHeight(3));
+        assert_eq!(order, quiz.question_order(owner(1), BlockHeight(3)));
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+        assert_ne!(order, sorted);
+        assert_ne!(order, quiz.question_order(owner(2), BlockHeight(3)));
+        assert_ne!(order, quiz.question_order(owner(1), BlockHeight(4)));
+    }
+
+    #[test]
+    fn pooled_order_draws_distinct_questions() {
+        let mut quiz = quiz_with_questions(20);
+        quiz.questions_per_attempt = Some(5);
+        let order = quiz.question_order(owner(1), BlockHeight(3));
+        assert_eq!(order.len(), 5);
+        assert!(order.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(order.iter().all(|id| *id < 20));
+
+        quiz.shuffle_questions = true;
+        let shuffled = quiz.question_order(owner(1), BlockHeight(3));
+        let mut drawn = shuffled.clone();
+        drawn.sort_unstable();
+        drawn.dedup();
+        assert_eq!(drawn.len(), 5);
+    }
+
+    #[test]
+    fn drawn_questions_keep_the_original_order() {
+        let quiz = quiz_with_questions(5);
+        let ids: Vec<u32> = quiz
+            .drawn_questions(&[4, 1, 9, 2])
+            .iter()
+            .map(|question| question.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn decayed_points_halve_each_half_life() {
+        let score = DecayedScore {
+            points: 8_000,
+            day: 100,
+            ..DecayedScore::default()
+        };
+        assert_eq!(score.points_at(100, 10), 8_000);
+        assert_eq!(score.points_at(110, 10), 4_000);
+        assert_eq!(score.points_at(120, 10), 2_000);
+        // 半衰期之内线性插值
+        assert_eq!(score.points_at(105, 10), 6_000);
+        assert_eq!(score.points_at(115, 10), 3_000);
+        // 早于最近更新日期的查询不衰减
+        assert_eq!(score.points_at(90, 10), 8_000);
+        assert_eq!(score.points_at(100 + 10 * 64, 10), 0);
+        // 半衰期为0时按1天处理
+        assert_eq!(score.points_at(101, 0), 4_000);
+    }
+
+    #[test]
+    fn decayed_add_decays_before_accumulating() {
+        const DAY: u64 = 24 * 3_600 * SECOND;
+        let mut score = DecayedScore::default();
+        score.add(4_000, at(10 * DAY), 5);
+        assert_eq!((score.points, score.day), (4_000, 10));
+        score.add(1_000, at(15 * DAY + 1), 5);
+        assert_eq!((score.points, score.day), (3_000, 15));
+        // 较早时间的成绩按当前日期累加，日期不回退
+        score.add(500, at(12 * DAY), 5);
+        assert_eq!((score.points, score.day), (3_500, 15));
+        assert_eq!(score.updated_at, at(12 * DAY));
+    }
+
+    #[test]
+    fn difficulty_follows_accuracy_thresholds() {
+        let tally = |answered, correct| QuestionTally {
+            answered,
+            correct,
+            skipped: 0,
+        };
+        assert_eq!(tally(0, 0).accuracy(), None);
+        assert_eq!(tally(0, 0).difficulty(), None);
+        assert_eq!(tally(3, 2).accuracy(), Some(66));
+        assert_eq!(tally(10, 7).difficulty(), Some(QuestionDifficulty::Easy));
+        assert_eq!(
+            tally(100, 69).difficulty(),
+            Some(QuestionDifficulty::Medium)
+        );
+        assert_eq!(tally(10, 4).difficulty(), Some(QuestionDifficulty::Medium));
+        assert_eq!(tally(100, 39).difficulty(), Some(QuestionDifficulty::Hard));
+        assert_eq!(tally(5, 0).difficulty(), Some(QuestionDifficulty::Hard));
+        assert_eq!(
+            QuestionDifficulty::from_results(0, 0),
+            QuestionDifficulty::Hard
+        );
+    }
+
+    fn entry(user: u64, score: u32, time_taken: u64) -> crate::LeaderboardEntry {
+        crate::LeaderboardEntry {
+            user: owner(user),
+            nick_name: format!("User {user}"),
+            score,
+            time_taken,
+            completed_at: at(NOW),
+        }
+    }
+
+    fn users(entries: &[crate::LeaderboardEntry]) -> Vec<AccountOwner> {
+        entries.iter().map(|entry| entry.user).collect()
+    }
+
+    #[test]
+    fn prize_ties_need_equal_score_and_time() {
+        let entries = [entry(1, 10, 5), entry(2, 10, 6), entry(3, 9, 5)];
+        assert!(prize_ties(&entries, 3).is_empty());
+        let entries = [entry(1, 10, 5), entry(2, 10, 5), entry(3, 9, 5)];
+        assert_eq!(prize_ties(&entries, 3), vec![owner(1), owner(2)]);
+        assert!(prize_ties(&entries, 0).is_empty());
+        assert!(prize_ties(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn prize_ties_include_groups_crossing_the_last_place() {
+        let entries = [
+            entry(1, 10, 5),
+            entry(2, 9, 5),
+            entry(3, 9, 5),
+            entry(4, 9, 5),
+            entry(5, 8, 5),
+            entry(6, 8, 5),
+        ];
+        assert_eq!(prize_ties(&entries, 2), vec![owner(2), owner(3), owner(4)]);
+        assert!(prize_ties(&entries, 1).is_empty());
+    }
+
+    #[test]
+    fn playoff_standings_reorder_only_tied_groups() {
+        let entries = [
+            entry(1, 10, 5),
+            entry(2, 10, 5),
+            entry(3, 10, 5),
+            entry(4, 9, 5),
+            entry(5, 9, 6),
+        ];
+        // 用户1未参加加赛，用户3加赛得分更高，用户5不在同分组内
+        let playoff = [entry(2, 3, 40), entry(3, 4, 50), entry(5, 9, 1)];
+        assert_eq!(
+            users(&playoff_standings(&entries, &playoff)),
+            vec![owner(3), owner(2), owner(1), owner(4), owner(5)]
+        );
+        // 加赛同分时用时短者在前
+        let playoff = [entry(1, 3, 40), entry(2, 3, 30), entry(3, 3, 50)];
+        assert_eq!(
+            users(&playoff_standings(&entries, &playoff)),
+            vec![owner(2), owner(1), owner(3), owner(4), owner(5)]
+        );
+        assert_eq!(users(&playoff_standings(&entries, &[])), users(&entries));
+    }
+
+    #[test]
+    fn preview_tokens_expire_and_close_when_the_quiz_starts() {
+        let mut quiz = quiz();
+        quiz.start_time = at(NOW + 600 * SECOND);
+        let token = PreviewToken {
+            quiz_id: quiz.id,
+            label: "Reviewer".to_string(),
+            created_at: at(NOW - 60 * SECOND),
+            expires_at: at(NOW + 300 * SECOND),
+        };
+        assert!(token.is_valid_for(&quiz, at(NOW)));
+        assert!(token.is_valid_for(&quiz, token.expires_at));
+        assert!(!token.is_valid_for(&quiz, at(NOW + 301 * SECOND)));
+
+        let mut started = quiz.clone();
+        started.start_time = at(NOW);
+        assert!(!token.is_valid_for(&started, at(NOW)));
+
+        let mut manual = quiz.clone();
+        manual.start_mode = QuizStartMode::Manual;
+        assert!(token.is_valid_for(&manual, at(NOW)));
+        manual.is_started = true;
+        assert!(!token.is_valid_for(&manual, at(NOW)));
+
+        quiz.cancelled = true;
+        assert!(!token.is_valid_for(&quiz, at(NOW)));
+    }
+
+    #[test]
+    fn preview_token_hash_does_not_reveal_the_token() {
+        let token = preview_token(7, "Reviewer", BlockHeight(3));
+        assert_eq!(token, preview_token(7, "Reviewer", BlockHeight(3)));
+        assert_ne!(token, preview_token(7, "Reviewer", BlockHeight(4)));
+        assert_ne!(token, preview_token(7, "Other", BlockHeight(3)));
+        assert_ne!(preview_token_hash(&token), token);
+    }
 }
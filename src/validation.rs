@@ -0,0 +1,1195 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 合约与服务共用的参数校验逻辑 */
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::{Amount, TimeDelta, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scoring::parse_decimal, AddBlackoutWindowParams, BlackoutWindow, BuildPracticeExamParams,
+    CreatePreviewTokenParams, CreateQuizParams, MediaKind, MetadataEntry, OrgSettings,
+    QuestionKind, QuestionParams, QuizFilter, QuizParameters,
+};
+
+/// 测验时间范围上限（100年）
+const MAX_QUIZ_DURATION_SECS: u64 = 3600 * 24 * 365 * 100;
+
+/// 每个Quiz的元数据条目上限
+pub const MAX_METADATA_ENTRIES: usize = 32;
+/// 元数据键的最大长度（字节）
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+/// 元数据值的最大长度（字节）
+pub const MAX_METADATA_VALUE_LEN: usize = 1024;
+
+/// 每个Quiz的标签数上限
+pub const MAX_TAGS: usize = 16;
+/// 标签的最大长度（字节）
+pub const MAX_TAG_LEN: usize = 32;
+
+/// 分类名的最大长度（字节）
+pub const MAX_CATEGORY_LEN: usize = 32;
+
+/// 组织标识的最大长度（字节）
+pub const MAX_ORG_SLUG_LEN: usize = 32;
+
+/// 昵称的最大长度（字节）
+pub const MAX_NICKNAME_LEN: usize = 32;
+
+/// 答题笔记的最大长度（字节）
+pub const MAX_ATTEMPT_NOTE_LEN: usize = 2000;
+
+/// 创建者公告的最大长度（字节）
+pub const MAX_ANNOUNCEMENT_LEN: usize = 2000;
+/// 每个Quiz的公告数上限
+pub const MAX_ANNOUNCEMENTS_PER_QUIZ: usize = 50;
+
+/// 延长答题时间的最大倍数（百分比）
+pub const MAX_TIME_MULTIPLIER: u32 = 400;
+
+/// Quiz保留策略的天数范围
+pub const MIN_RETENTION_DAYS: u32 = 7;
+pub const MAX_RETENTION_DAYS: u32 = 3650;
+
+/// 预览令牌的最长有效期（秒）
+pub const MAX_PREVIEW_TOKEN_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// 预览令牌标签的最大长度（字节）
+pub const MAX_PREVIEW_LABEL_LEN: usize = 64;
+
+/// 练习卷的最大问题数
+pub const MAX_PRACTICE_EXAM_QUESTIONS: u32 = 100;
+
+/// 参数校验违规项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct Violation {
+    pub field: String,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(field: &str, message: &str) -> Self {
+        Violation {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// 校验通过后的测验时间窗口
+#[derive(Debug, Clone, Copy)]
+pub struct QuizSchedule {
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+}
+
+/// 检查时间戳是否像微秒时间戳（当前约16位），拒绝误传的秒或毫秒时间戳
+fn check_timestamp(value: Timestamp, field: &str, label: &str) -> Result<Timestamp, Violation> {
+    let digits = value.micros().to_string().len();
+    if !(15..=17).contains(&digits) {
+        return Err(Violation::new(
+            field,
+            &format!("{label} time seems invalid (should be a microsecond timestamp)"),
+        ));
+    }
+    Ok(value)
+}
+
+/// 校验创建Quiz的参数，返回时间窗口或全部违规项
+pub fn validate_create_quiz(
+    params: &CreateQuizParams,
+    now: Timestamp,
+) -> Result<QuizSchedule, Vec<Violation>> {
+    let schedule = validate_schedule(params.start_time, params.end_time, now);
+    let mut violations = validate_questions(&params.questions);
+    violations.extend(validate_metadata(&params.metadata));
+    violations.extend(validate_tags(&params.tags));
+    if let Err(violation) = validate_category(&params.category) {
+        violations.push(violation);
+    }
+    let question_count = match &params.embargo {
+        Some(embargo) => {
+            if !params.questions.is_empty() {
+                violations.push(Violation::new(
+                    "questions",
+                    "Embargoed quizzes are created without questions",
+                ));
+            }
+            if embargo.question_count == 0 {
+                violations.push(Violation::new(
+                    "embargo",
+                    "Embargoed quizzes need at least one question",
+                ));
+            }
+            embargo.question_count as usize
+        }
+        None => params.questions.len(),
+    };
+    if let Err(violation) =
+        validate_questions_per_attempt(params.questions_per_attempt, question_count)
+    {
+        violations.push(violation);
+    }
+    if let Some(soft_launch) = &params.soft_launch {
+        if soft_launch.percent_per_wave == 0 || soft_launch.percent_per_wave > 100 {
+            violations.push(Violation::new(
+                "softLaunch",
+                "Soft launch waves must open between 1 and 100 percent of users",
+            ));
+        }
+        if soft_launch.wave_secs == 0 {
+            violations.push(Violation::new(
+                "softLaunch",
+                "Soft launch waves must be at least one second apart",
+            ));
+        }
+    }
+    if params.max_attempts == 0 {
+        violations.push(Violation::new(
+            "maxAttempts",
+            "Quizzes must allow at least one attempt",
+        ));
+    }
+    if params.entry_fee == Some(Amount::ZERO) {
+        violations.push(Violation::new(
+            "entryFee",
+            "Entry fee must be greater than zero",
+        ));
+    }
+    match params.prize_pool.or(params.entry_fee) {
+        Some(_) => {
+            if params.prize_pool == Some(Amount::ZERO) {
+                violations.push(Violation::new(
+                    "prizePool",
+                    "Prize pool must be greater than zero",
+                ));
+            }
+            if !valid_prize_splits(&params.prize_splits) {
+                violations.push(Violation::new(
+                    "prizeSplits",
+                    "Prize splits must be positive percentages adding up to 100",
+                ));
+            }
+        }
+        None if !params.prize_splits.is_empty() => {
+            violations.push(Violation::new(
+                "prizeSplits",
+                "Prize splits require a prize pool or an entry fee",
+            ));
+        }
+        None => {}
+    }
+    if params.members_only == Some(true) && params.org.is_none() {
+        violations.push(Violation::new(
+            "membersOnly",
+            "Members-only quizzes must belong to an organization",
+        ));
+    }
+    match schedule {
+        Ok(schedule) if violations.is_empty() => Ok(schedule),
+        Ok(_) => Err(violations),
+        Err(mut schedule_violations) => {
+            schedule_violations.extend(violations);
+            Err(schedule_violations)
+        }
+    }
+}
+
+/// 奖金分配比例须为正的百分比且合计为100
+pub fn valid_prize_splits(splits: &[u32]) -> bool {
+    !splits.is_empty() && !splits.contains(&0) && splits.iter().sum::<u32>() == 100
+}
+
+/// 校验平台禁排时段的时间窗口：结束时间须晚于开始时间且尚未过去
+pub fn validate_blackout_window(
+    params: &AddBlackoutWindowParams,
+    now: Timestamp,
+) -> Result<BlackoutWindow, Vec<Violation>> {
+    let mut violations = Vec::new();
+    let start_time = check_timestamp(params.start_time, "startTime", "Start")
+        .map_err(|violation| violations.push(violation))
+        .ok();
+    let end_time = check_timestamp(params.end_time, "endTime", "End")
+        .map_err(|violation| violations.push(violation))
+        .ok();
+    if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
+        if end_time <= start_time {
+            violations.push(Violation::new(
+                "endTime",
+                "End time must be after start time",
+            ));
+        } else if end_time <= now {
+            violations.push(Violation::new(
+                "endTime",
+                "Blackout window has already ended",
+            ));
+        }
+    }
+    match (start_time, end_time) {
+        (Some(start_time), Some(end_time)) if violations.is_empty() => Ok(BlackoutWindow {
+            start_time,
+            end_time,
+            reason: params.reason.clone(),
+        }),
+        _ => Err(violations),
+    }
+}
+
+/// 检查Quiz的开始时间是否落在平台禁排时段内
+pub fn validate_blackouts(start_time: Timestamp, windows: &[BlackoutWindow]) -> Vec<Violation> {
+    windows
+        .iter()
+        .filter(|window| window.contains(start_time))
+        .map(|window| {
+            Violation::new(
+                "startTime",
+                &format!(
+                    "Quizzes cannot start during a blackout window ({})",
+                    window.reason
+                ),
+            )
+        })
+        .collect()
+}
+
+/// 按组织设置校验组织内Quiz的问题数和答题时限
+pub fn validate_org_limits(params: &CreateQuizParams, settings: &OrgSettings) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let question_count = params
+        .embargo
+        .as_ref()
+        .map_or(params.questions.len(), |embargo| {
+            embargo.question_count as usize
+        });
+    if let Some(max_questions) = settings.max_questions {
+        if question_count > max_questions as usize {
+            violations.push(Violation::new(
+                "questions",
+                &format!("Organization allows at most {max_questions} questions per quiz"),
+            ));
+        }
+    }
+    if let Some(max_time_limit) = settings.max_time_limit {
+        if params.time_limit == 0 || params.time_limit > max_time_limit {
+            violations.push(Violation::new(
+                "timeLimit",
+                &format!("Organization requires a time limit of at most {max_time_limit} seconds"),
+            ));
+        }
+    }
+    violations
+}
+
+/// 校验测验时间窗口
+pub fn validate_schedule(
+    start_time: Timestamp,
+    end_time: Timestamp,
+    now: Timestamp,
+) -> Result<QuizSchedule, Vec<Violation>> {
+    let mut violations = Vec::new();
+
+    let start_time = check_timestamp(start_time, "startTime", "Start")
+        .map_err(|violation| violations.push(violation))
+        .ok();
+    let end_time = check_timestamp(end_time, "endTime", "End")
+        .map_err(|violation| violations.push(violation))
+        .ok();
+
+    if let Some(start_time) = start_time {
+        if start_time <= now {
+            violations.push(Violation::new(
+                "startTime",
+                "Start time must be in the future",
+            ));
+        }
+    }
+
+    if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
+        if end_time <= start_time {
+            violations.push(Violation::new(
+                "endTime",
+                "End time must be after start time",
+            ));
+        } else if end_time.delta_since(start_time) > TimeDelta::from_secs(MAX_QUIZ_DURATION_SECS) {
+            // 检查时间范围是否合理（不超过100年）
+            violations.push(Violation::new(
+                "endTime",
+                "Time range is too long (maximum 100 years)",
+            ));
+        }
+    }
+
+    match (start_time, end_time) {
+        (Some(start_time), Some(end_time)) if violations.is_empty() => Ok(QuizSchedule {
+            start_time,
+            end_time,
+        }),
+        _ => Err(violations),
+    }
+}
+
+/// 校验组卷参数：问题数、标签和结束时间，练习卷创建后立即开始
+pub fn validate_practice_exam(
+    params: &BuildPracticeExamParams,
+    now: Timestamp,
+) -> Result<(), Violation> {
+    if params.question_count == 0 || params.question_count > MAX_PRACTICE_EXAM_QUESTIONS {
+        return Err(Violation::new(
+            "questionCount",
+            &format!("Practice exams have 1 to {MAX_PRACTICE_EXAM_QUESTIONS} questions"),
+        ));
+    }
+    if let Some(violation) = validate_tags(&params.tags).into_iter().next() {
+        return Err(violation);
+    }
+    let end_time = check_timestamp(params.end_time, "endTime", "End")?;
+    if end_time <= now {
+        return Err(Violation::new("endTime", "End time must be in the future"));
+    }
+    if end_time.delta_since(now) > TimeDelta::from_secs(MAX_QUIZ_DURATION_SECS) {
+        return Err(Violation::new(
+            "endTime",
+            "Time range is too long (maximum 100 years)",
+        ));
+    }
+    Ok(())
+}
+
+/// 按问题类型校验选项、正确答案和判分数据
+pub fn validate_questions(questions: &[QuestionParams]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (index, question) in questions.iter().enumerate() {
+        let mut invalid = |message: &str| {
+            violations.push(Violation::new(
+                "questions",
+                &format!("Question {index}: {message}"),
+            ));
+        };
+        match &question.kind {
+            QuestionKind::MultipleChoice => {
+                if question.options.is_empty() {
+                    invalid("Multiple choice questions need at least one option");
+                }
+                if question
+                    .correct_options
+                    .iter()
+                    .any(|option| *option as usize >= question.options.len())
+                {
+                    invalid("Correct option is out of range");
+                }
+            }
+            QuestionKind::TrueFalse => {
+                if !question.options.is_empty() && question.options.len() != 2 {
+                    invalid("True/false questions have exactly two options");
+                }
+                if question.correct_options.len() != 1 || question.correct_options[0] > 1 {
+                    invalid("True/false questions need exactly one correct option (0 or 1)");
+                }
+            }
+            QuestionKind::FillInBlank { answers } => {
+                if answers.iter().all(|answer| answer.trim().is_empty()) {
+                    invalid("Fill-in-the-blank questions need at least one accepted answer");
+                }
+            }
+            QuestionKind::Numeric { answer, tolerance } => {
+                if parse_decimal(answer).is_none() {
+                    invalid("Numeric answer must be a decimal number");
+                }
+                if parse_decimal(tolerance).is_none_or(|tolerance| tolerance < 0) {
+                    invalid("Numeric tolerance must be a non-negative decimal number");
+                }
+            }
+            QuestionKind::Essay { rubric } => {
+                if rubric.is_empty() {
+                    invalid("Essay questions need at least one rubric criterion");
+                }
+                if rubric
+                    .iter()
+                    .any(|criterion| criterion.name.trim().is_empty())
+                {
+                    invalid("Rubric criteria need a name");
+                }
+                if rubric.iter().any(|criterion| criterion.weight == 0) {
+                    invalid("Rubric criterion weight must be positive");
+                }
+                if rubric.iter().any(|criterion| {
+                    criterion.levels.len() < 2
+                        || criterion.levels.iter().any(|level| level.trim().is_empty())
+                }) {
+                    invalid("Rubric criteria need at least two described levels");
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// 按应用参数的时长上限校验问题附带的媒体，blob 是否存在由合约检查
+pub fn validate_question_media(
+    questions: &[QuestionParams],
+    parameters: &QuizParameters,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (index, question) in questions.iter().enumerate() {
+        let Some(media) = &question.media else {
+            continue;
+        };
+        let label = match media.kind {
+            MediaKind::Audio => "Audio",
+            MediaKind::Video => "Video",
+        };
+        let max_duration = parameters.max_media_duration(media.kind);
+        if media.duration_secs == 0 {
+            violations.push(Violation::new(
+                "questions",
+                &format!("Question {index}: {label} duration must be positive"),
+            ));
+        } else if media.duration_secs > max_duration {
+            violations.push(Violation::new(
+                "questions",
+                &format!(
+                    "Question {index}: {label} must not be longer than {max_duration} seconds"
+                ),
+            ));
+        }
+        if media.transcript == Some(media.blob) {
+            violations.push(Violation::new(
+                "questions",
+                &format!("Question {index}: Transcript must be a separate blob"),
+            ));
+        }
+    }
+    violations
+}
+
+/// 校验题库模式的抽题数：至少1题且不超过问题总数
+pub fn validate_questions_per_attempt(
+    questions_per_attempt: Option<u32>,
+    question_count: usize,
+) -> Result<(), Violation> {
+    match questions_per_attempt {
+        Some(count) if count == 0 || count as usize > question_count => Err(Violation::new(
+            "questionsPerAttempt",
+            "Questions per attempt must be between 1 and the number of questions",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 校验Quiz标签的数量、长度以及是否重复
+pub fn validate_tags(tags: &[String]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if tags.len() > MAX_TAGS {
+        violations.push(Violation::new(
+            "tags",
+            &format!("Too many tags (maximum {MAX_TAGS})"),
+        ));
+    }
+    for (index, tag) in tags.iter().enumerate() {
+        if tag.trim().is_empty() || tag.trim() != tag || tag.len() > MAX_TAG_LEN {
+            violations.push(Violation::new(
+                "tags",
+                &format!("Tag must be 1 to {MAX_TAG_LEN} bytes without surrounding spaces"),
+            ));
+        }
+        if tags[..index].contains(tag) {
+            violations.push(Violation::new("tags", &format!("Duplicate tag `{tag}`")));
+        }
+    }
+    violations
+}
+
+/// 校验Quiz分类名：可以为空（未分类），否则不超过长度上限且没有首尾空白
+pub fn validate_category(category: &str) -> Result<(), Violation> {
+    if category.trim() != category || category.len() > MAX_CATEGORY_LEN {
+        return Err(Violation::new(
+            "category",
+            &format!(
+                "Category must be at most {MAX_CATEGORY_LEN} bytes without surrounding spaces"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验Quiz自定义元数据的条目数、键值长度以及键是否重复
+pub fn validate_metadata(metadata: &[MetadataEntry]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if metadata.len() > MAX_METADATA_ENTRIES {
+        violations.push(Violation::new(
+            "metadata",
+            &format!("Too many metadata entries (maximum {MAX_METADATA_ENTRIES})"),
+        ));
+    }
+    for (index, entry) in metadata.iter().enumerate() {
+        if entry.key.is_empty() || entry.key.len() > MAX_METADATA_KEY_LEN {
+            violations.push(Violation::new(
+                "metadata",
+                &format!("Metadata key must be 1 to {MAX_METADATA_KEY_LEN} bytes"),
+            ));
+        }
+        if entry.value.len() > MAX_METADATA_VALUE_LEN {
+            violations.push(Violation::new(
+                "metadata",
+                &format!("Metadata value exceeds {MAX_METADATA_VALUE_LEN} bytes"),
+            ));
+        }
+        if metadata[..index].iter().any(|other| other.key == entry.key) {
+            violations.push(Violation::new(
+                "metadata",
+                &format!("Duplicate metadata key `{}`", entry.key),
+            ));
+        }
+    }
+    violations
+}
+
+/// 校验组织标识：1到32个小写字母、数字或连字符
+pub fn validate_org_slug(slug: &str) -> Result<(), Violation> {
+    let valid_chars = slug
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if slug.is_empty() || slug.len() > MAX_ORG_SLUG_LEN || !valid_chars {
+        return Err(Violation::new(
+            "slug",
+            &format!(
+                "Organization slug must be 1 to {MAX_ORG_SLUG_LEN} lowercase letters, digits or hyphens"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 解析筛选条件中的创建时间范围
+pub fn validate_quiz_filter(
+    filter: &QuizFilter,
+) -> Result<(Option<Timestamp>, Option<Timestamp>), Violation> {
+    let created_after = filter
+        .created_after
+        .map(|value| check_timestamp(value, "createdAfter", "Created after"))
+        .transpose()?;
+    let created_before = filter
+        .created_before
+        .map(|value| check_timestamp(value, "createdBefore", "Created before"))
+        .transpose()?;
+    Ok((created_after, created_before))
+}
+
+/// 校验昵称：非空、无首尾空白且不超过最大长度
+pub fn validate_nickname(nick_name: &str) -> Result<(), Violation> {
+    if nick_name.trim().is_empty()
+        || nick_name.trim() != nick_name
+        || nick_name.len() > MAX_NICKNAME_LEN
+    {
+        return Err(Violation::new(
+            "nickName",
+            &format!("Nickname must be 1 to {MAX_NICKNAME_LEN} bytes without surrounding spaces"),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验延长答题时间的倍数：100（不延长）到最大倍数之间
+pub fn validate_time_multiplier(multiplier: u32) -> Result<(), Violation> {
+    if !(100..=MAX_TIME_MULTIPLIER).contains(&multiplier) {
+        return Err(Violation::new(
+            "multiplier",
+            &format!("Time multiplier must be between 100 and {MAX_TIME_MULTIPLIER} percent"),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验Quiz保留天数
+pub fn validate_retention_days(retention_days: u32) -> Result<(), Violation> {
+    if !(MIN_RETENTION_DAYS..=MAX_RETENTION_DAYS).contains(&retention_days) {
+        return Err(Violation::new(
+            "retentionDays",
+            &format!(
+                "Retention must be between {MIN_RETENTION_DAYS} and {MAX_RETENTION_DAYS} days"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验预览令牌参数：标签非空且不超过最大长度，有效期为正且不超过上限
+pub fn validate_preview_token(params: &CreatePreviewTokenParams) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if params.label.trim().is_empty() || params.label.len() > MAX_PREVIEW_LABEL_LEN {
+        violations.push(Violation::new(
+            "label",
+            &format!("Preview label must be 1 to {MAX_PREVIEW_LABEL_LEN} bytes"),
+        ));
+    }
+    if params.valid_secs == 0 || params.valid_secs > MAX_PREVIEW_TOKEN_SECS {
+        violations.push(Violation::new(
+            "validSecs",
+            &format!("Preview tokens must be valid for 1 to {MAX_PREVIEW_TOKEN_SECS} seconds"),
+        ));
+    }
+    violations
+}
+
+/// 校验创建者公告：非空且不超过最大长度
+pub fn validate_announcement(text: &str) -> Result<(), Violation> {
+    if text.trim().is_empty() || text.len() > MAX_ANNOUNCEMENT_LEN {
+        return Err(Violation::new(
+            "text",
+            &format!("Announcement must be 1 to {MAX_ANNOUNCEMENT_LEN} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+/// 校验答题笔记长度
+pub fn validate_attempt_note(note: &str) -> Result<(), Violation> {
+    if note.len() > MAX_ATTEMPT_NOTE_LEN {
+        return Err(Violation::new(
+            "note",
+            &format!("Note exceeds {MAX_ATTEMPT_NOTE_LEN} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::{CryptoHash, DataBlobHash};
+
+    use super::*;
+    use crate::{
+        AttemptPolicy, ContentEmbargo, QuestionMedia, QuizStartMode, RubricCriterion,
+        ScoringStrategy, SoftLaunch,
+    };
+
+    const NOW: u64 = 1_700_000_000_000_000;
+    const HOUR: u64 = 3_600_000_000;
+
+    fn at(micros: u64) -> Timestamp {
+        Timestamp::from(micros)
+    }
+
+    fn question(kind: QuestionKind, options: usize, correct_options: Vec<u32>) -> QuestionParams {
+        QuestionParams {
+            text: "Q".to_string(),
+            options: (0..options)
+                .map(|index| format!("Option {index}"))
+                .collect(),
+            correct_options,
+            points: 1,
+            kind,
+            explanation: None,
+            media: None,
+            screen_reader_hint: None,
+        }
+    }
+
+    fn criterion(weight: u32, levels: &[&str]) -> RubricCriterion {
+        RubricCriterion {
+            name: "Clarity".to_string(),
+            weight,
+            levels: levels.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn params() -> CreateQuizParams {
+        CreateQuizParams {
+            title: "Quiz".to_string(),
+            description: String::new(),
+            questions: vec![question(QuestionKind::MultipleChoice, 2, vec![0])],
+            time_limit: 0,
+            start_time: at(NOW + HOUR),
+            end_time: at(NOW + 2 * HOUR),
+            nick_name: "Creator".to_string(),
+            practice: false,
+            hide_results: false,
+            reveal_delay: None,
+            proctor: None,
+            owner: None,
+            metadata: Vec::new(),
+            start_mode: QuizStartMode::default(),
+            scoring: ScoringStrategy::default(),
+            allow_dont_know: false,
+            org: None,
+            members_only: None,
+            shuffle_questions: false,
+            questions_per_attempt: None,
+            embargo: None,
+            answer_key_hash: None,
+            max_attempts: 1,
+            attempt_policy: AttemptPolicy::default(),
+            attestation_app: None,
+            prize_pool: None,
+            prize_splits: Vec::new(),
+            entry_fee: None,
+            category: String::new(),
+            tags: Vec::new(),
+            pass_score: None,
+            soft_launch: None,
+        }
+    }
+
+    /// 按修改后的参数校验，断言只在给定字段上报告了包含给定信息的违规项
+    fn assert_rejected(update: impl FnOnce(&mut CreateQuizParams), field: &str, message: &str) {
+        let mut params = params();
+        update(&mut params);
+        let violations = validate_create_quiz(&params, at(NOW)).unwrap_err();
+        assert!(
+            violations.iter().all(|violation| violation.field == field)
+                && violations
+                    .iter()
+                    .any(|violation| violation.message.contains(message)),
+            "{violations:?}"
+        );
+    }
+
+    fn question_messages(question: QuestionParams) -> Vec<String> {
+        validate_questions(&[question])
+            .into_iter()
+            .map(|violation| violation.message)
+            .collect()
+    }
+
+    #[test]
+    fn valid_quiz_returns_its_schedule() {
+        let schedule = validate_create_quiz(&params(), at(NOW)).unwrap();
+        assert_eq!(schedule.start_time, at(NOW + HOUR));
+        assert_eq!(schedule.end_time, at(NOW + 2 * HOUR));
+    }
+
+    #[test]
+    fn schedule_rejections() {
+        assert_rejected(|p| p.start_time = at(NOW), "startTime", "future");
+        assert_rejected(
+            |p| p.start_time = at(1_700_000_000),
+            "startTime",
+            "microsecond",
+        );
+        assert_rejected(
+            |p| p.end_time = at(1_700_000_000_000),
+            "endTime",
+            "microsecond",
+        );
+        assert_rejected(|p| p.end_time = at(NOW + HOUR), "endTime", "after start");
+        assert_rejected(
+            |p| p.end_time = at(NOW + HOUR + (MAX_QUIZ_DURATION_SECS + 1) * 1_000_000),
+            "endTime",
+            "too long",
+        );
+    }
+
+    #[test]
+    fn all_violations_are_reported_together() {
+        let mut params = params();
+        params.start_time = at(NOW);
+        params.max_attempts = 0;
+        params.tags = vec![" spaced".to_string()];
+        let fields: Vec<String> = validate_create_quiz(&params, at(NOW))
+            .unwrap_err()
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect();
+        assert_eq!(fields, ["startTime", "tags", "maxAttempts"]);
+    }
+
+    #[test]
+    fn embargo_rejections() {
+        let embargo = |question_count| ContentEmbargo {
+            commitment: CryptoHash::from([0; 4]),
+            question_count,
+        };
+        assert_rejected(
+            |p| p.embargo = Some(embargo(1)),
+            "questions",
+            "without questions",
+        );
+        assert_rejected(
+            |p| {
+                p.questions.clear();
+                p.embargo = Some(embargo(0));
+            },
+            "embargo",
+            "at least one question",
+        );
+        let mut params = params();
+        params.questions.clear();
+        params.embargo = Some(embargo(3));
+        params.questions_per_attempt = Some(3);
+        assert!(validate_create_quiz(&params, at(NOW)).is_ok());
+    }
+
+    #[test]
+    fn questions_per_attempt_must_fit_the_pool() {
+        assert_rejected(
+            |p| p.questions_per_attempt = Some(0),
+            "questionsPerAttempt",
+            "between 1",
+        );
+        assert_rejected(
+            |p| p.questions_per_attempt = Some(2),
+            "questionsPerAttempt",
+            "between 1",
+        );
+        assert!(validate_questions_per_attempt(Some(1), 1).is_ok());
+        assert!(validate_questions_per_attempt(None, 0).is_ok());
+    }
+
+    #[test]
+    fn soft_launch_rejects_zero_percent_waves() {
+        // 批次数和用户所在批次都按 percent_per_wave 做除法
+        let soft_launch = |percent_per_wave, wave_secs| {
+            Some(SoftLaunch {
+                percent_per_wave,
+                wave_secs,
+            })
+        };
+        assert_rejected(
+            |p| p.soft_launch = soft_launch(0, 60),
+            "softLaunch",
+            "percent",
+        );
+        assert_rejected(
+            |p| p.soft_launch = soft_launch(101, 60),
+            "softLaunch",
+            "percent",
+        );
+        assert_rejected(
+            |p| p.soft_launch = soft_launch(50, 0),
+            "softLaunch",
+            "one second",
+        );
+    }
+
+    #[test]
+    fn attempt_and_prize_rejections() {
+        let tokens = Amount::from_tokens;
+        assert_rejected(|p| p.max_attempts = 0, "maxAttempts", "at least one");
+        assert_rejected(
+            |p| {
+                p.entry_fee = Some(Amount::ZERO);
+                p.prize_splits = vec![100];
+            },
+            "entryFee",
+            "greater than zero",
+        );
+        assert_rejected(
+            |p| {
+                p.prize_pool = Some(Amount::ZERO);
+                p.prize_splits = vec![100];
+            },
+            "prizePool",
+            "greater than zero",
+        );
+        assert_rejected(
+            |p| p.prize_pool = Some(tokens(10)),
+            "prizeSplits",
+            "adding up to 100",
+        );
+        assert_rejected(
+            |p| {
+                p.entry_fee = Some(tokens(1));
+                p.prize_splits = vec![60, 30];
+            },
+            "prizeSplits",
+            "adding up to 100",
+        );
+        assert_rejected(
+            |p| p.prize_splits = vec![100],
+            "prizeSplits",
+            "require a prize pool",
+        );
+        assert_rejected(
+            |p| p.members_only = Some(true),
+            "membersOnly",
+            "organization",
+        );
+        assert!(valid_prize_splits(&[50, 30, 20]));
+        assert!(!valid_prize_splits(&[]));
+        assert!(!valid_prize_splits(&[100, 0]));
+    }
+
+    #[test]
+    fn choice_question_rejections() {
+        let messages = question_messages(question(QuestionKind::MultipleChoice, 0, Vec::new()));
+        assert!(messages[0].contains("at least one option"), "{messages:?}");
+        let messages = question_messages(question(QuestionKind::MultipleChoice, 2, vec![2]));
+        assert!(messages[0].contains("out of range"), "{messages:?}");
+        let messages = question_messages(question(QuestionKind::TrueFalse, 3, vec![0]));
+        assert!(messages[0].contains("exactly two options"), "{messages:?}");
+        for correct_options in [vec![], vec![2], vec![0, 1]] {
+            let messages = question_messages(question(QuestionKind::TrueFalse, 0, correct_options));
+            assert!(messages[0].contains("exactly one correct"), "{messages:?}");
+        }
+        assert!(question_messages(question(QuestionKind::TrueFalse, 0, vec![1])).is_empty());
+    }
+
+    #[test]
+    fn fill_in_and_numeric_question_rejections() {
+        let fill_in = |answers: &[&str]| QuestionKind::FillInBlank {
+            answers: answers.iter().map(ToString::to_string).collect(),
+        };
+        let messages = question_messages(question(fill_in(&[" ", ""]), 0, Vec::new()));
+        assert!(messages[0].contains("accepted answer"), "{messages:?}");
+        assert!(question_messages(question(fill_in(&["Paris"]), 0, Vec::new())).is_empty());
+
+        let numeric = |answer: &str, tolerance: &str| QuestionKind::Numeric {
+            answer: answer.to_string(),
+            tolerance: tolerance.to_string(),
+        };
+        let messages = question_messages(question(numeric("abc", "0"), 0, Vec::new()));
+        assert!(messages[0].contains("decimal number"), "{messages:?}");
+        let messages = question_messages(question(numeric("1", "-0.5"), 0, Vec::new()));
+        assert!(messages[0].contains("non-negative"), "{messages:?}");
+        assert!(question_messages(question(numeric("-1.5", "0.1"), 0, Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn essay_rubric_rejections_guard_score_division() {
+        // rubric_score 按总权重乘以最高等级做除数，权重为0或只有一个等级时除数为0
+        let essay = |rubric| question(QuestionKind::Essay { rubric }, 0, Vec::new());
+        let cases = [
+            (vec![], "at least one rubric criterion"),
+            (
+                vec![RubricCriterion {
+                    name: " ".to_string(),
+                    ..criterion(1, &["Poor", "Good"])
+                }],
+                "need a name",
+            ),
+            (
+                vec![criterion(0, &["Poor", "Good"])],
+                "weight must be positive",
+            ),
+            (
+                vec![criterion(1, &["Only"])],
+                "at least two described levels",
+            ),
+            (
+                vec![criterion(1, &["Poor", " "])],
+                "at least two described levels",
+            ),
+        ];
+        for (rubric, message) in cases {
+            let messages = question_messages(essay(rubric));
+            assert_eq!(messages.len(), 1, "{messages:?}");
+            assert!(messages[0].contains(message), "{messages:?}");
+        }
+        assert!(question_messages(essay(vec![criterion(2, &["Poor", "Good"])])).is_empty());
+    }
+
+    #[test]
+    fn question_media_rejections() {
+        let parameters = QuizParameters::default();
+        let blob = DataBlobHash(CryptoHash::from([1; 4]));
+        let with_media = |kind, duration_secs, transcript| QuestionParams {
+            media: Some(QuestionMedia {
+                kind,
+                blob,
+                duration_secs,
+                transcript,
+                alt_text: None,
+            }),
+            ..question(QuestionKind::MultipleChoice, 2, vec![0])
+        };
+        let messages = |question| {
+            validate_question_media(&[question], &parameters)
+                .into_iter()
+                .map(|violation| violation.message)
+                .collect::<Vec<_>>()
+        };
+        assert!(messages(with_media(MediaKind::Audio, 0, None))[0].contains("must be positive"));
+        let too_long = parameters.max_media_duration(MediaKind::Video) + 1;
+        assert!(messages(with_media(MediaKind::Video, too_long, None))[0].contains("longer than"));
+        assert!(messages(with_media(MediaKind::Audio, 1, Some(blob)))[0].contains("separate blob"));
+        let transcript = DataBlobHash(CryptoHash::from([2; 4]));
+        assert!(messages(with_media(MediaKind::Audio, 1, Some(transcript))).is_empty());
+    }
+
+    #[test]
+    fn tag_category_and_metadata_rejections() {
+        let tags = |tags: &[&str]| {
+            validate_tags(&tags.iter().map(ToString::to_string).collect::<Vec<_>>())
+        };
+        assert!(tags(&["rust", "linera"]).is_empty());
+        assert_eq!(tags(&[""]).len(), 1);
+        assert_eq!(tags(&["rust "]).len(), 1);
+        assert_eq!(tags(&[&"x".repeat(MAX_TAG_LEN + 1)]).len(), 1);
+        assert!(tags(&["rust", "rust"])[0].message.contains("Duplicate"));
+        assert!(tags(&["t"; MAX_TAGS + 1])[0].message.contains("Too many"));
+
+        assert!(validate_category("").is_ok());
+        assert!(validate_category(" math").is_err());
+        assert!(validate_category(&"x".repeat(MAX_CATEGORY_LEN + 1)).is_err());
+
+        let entry = |key: &str, value: &str| MetadataEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        assert!(validate_metadata(&[entry("color", "blue")]).is_empty());
+        assert!(validate_metadata(&[entry("", "blue")])[0]
+            .message
+            .contains("key"));
+        let long_key = "k".repeat(MAX_METADATA_KEY_LEN + 1);
+        assert!(validate_metadata(&[entry(&long_key, "")])[0]
+            .message
+            .contains("key"));
+        let long_value = "v".repeat(MAX_METADATA_VALUE_LEN + 1);
+        assert!(validate_metadata(&[entry("k", &long_value)])[0]
+            .message
+            .contains("value"));
+        let duplicate = validate_metadata(&[entry("k", "1"), entry("k", "2")]);
+        assert!(duplicate[0].message.contains("Duplicate"));
+        let entries: Vec<_> = (0..=MAX_METADATA_ENTRIES)
+            .map(|index| entry(&index.to_string(), ""))
+            .collect();
+        assert!(validate_metadata(&entries)[0].message.contains("Too many"));
+    }
+
+    #[test]
+    fn blackout_window_rejections() {
+        let window = |start, end| AddBlackoutWindowParams {
+            start_time: at(start),
+            end_time: at(end),
+            reason: "Maintenance".to_string(),
+        };
+        let window_is_valid = validate_blackout_window(&window(NOW, NOW + HOUR), at(NOW));
+        assert!(window_is_valid.is_ok());
+        let errors = |params| validate_blackout_window(&params, at(NOW)).unwrap_err();
+        assert!(errors(window(NOW, NOW))[0].message.contains("after start"));
+        assert!(errors(window(NOW - 2 * HOUR, NOW - HOUR))[0]
+            .message
+            .contains("already ended"));
+        assert_eq!(errors(window(1, 2)).len(), 2);
+
+        let windows = [BlackoutWindow {
+            start_time: at(NOW),
+            end_time: at(NOW + HOUR),
+            reason: "Maintenance".to_string(),
+        }];
+        assert_eq!(validate_blackouts(at(NOW), &windows).len(), 1);
+        assert!(validate_blackouts(at(NOW + HOUR), &windows).is_empty());
+    }
+
+    #[test]
+    fn org_limit_rejections() {
+        let settings = OrgSettings {
+            default_members_only: false,
+            max_questions: Some(1),
+            max_time_limit: Some(600),
+        };
+        let mut params = params();
+        params.time_limit = 600;
+        assert!(validate_org_limits(&params, &settings).is_empty());
+        params
+            .questions
+            .push(question(QuestionKind::TrueFalse, 0, vec![0]));
+        params.time_limit = 0;
+        let fields: Vec<_> = validate_org_limits(&params, &settings)
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect();
+        assert_eq!(fields, ["questions", "timeLimit"]);
+        params.time_limit = 601;
+        params.questions.truncate(1);
+        assert_eq!(
+            validate_org_limits(&params, &settings)[0].field,
+            "timeLimit"
+        );
+    }
+
+    #[test]
+    fn practice_exam_rejections() {
+        let exam = |question_count, end_time| BuildPracticeExamParams {
+            creator: linera_sdk::linera_base_types::AccountOwner::CHAIN,
+            tags: Vec::new(),
+            difficulty: None,
+            question_count,
+            title: "Practice".to_string(),
+            nick_name: "Learner".to_string(),
+            time_limit: 0,
+            end_time: at(end_time),
+        };
+        let field = |params| validate_practice_exam(&params, at(NOW)).unwrap_err().field;
+        assert!(validate_practice_exam(&exam(10, NOW + HOUR), at(NOW)).is_ok());
+        assert_eq!(field(exam(0, NOW + HOUR)), "questionCount");
+        assert_eq!(
+            field(exam(MAX_PRACTICE_EXAM_QUESTIONS + 1, NOW + HOUR)),
+            "questionCount"
+        );
+        assert_eq!(field(exam(10, NOW)), "endTime");
+        assert_eq!(field(exam(10, 1_700_000_000)), "endTime");
+        let far = NOW + (MAX_QUIZ_DURATION_SECS + 1) * 1_000_000;
+        assert_eq!(field(exam(10, far)), "endTime");
+        let mut tagged = exam(10, NOW + HOUR);
+        tagged.tags = vec![String::new()];
+        assert_eq!(field(tagged), "tags");
+    }
+
+    #[test]
+    fn single_field_rejections() {
+        assert!(validate_org_slug("math-101").is_ok());
+        for slug in ["", "Math", "math_101", &"a".repeat(MAX_ORG_SLUG_LEN + 1)] {
+            assert!(validate_org_slug(slug).is_err(), "{slug}");
+        }
+        assert!(validate_nickname("Alice").is_ok());
+        for nick_name in ["", " ", " Alice", &"a".repeat(MAX_NICKNAME_LEN + 1)] {
+            assert!(validate_nickname(nick_name).is_err(), "{nick_name}");
+        }
+        assert!(validate_time_multiplier(100).is_ok());
+        assert!(validate_time_multiplier(MAX_TIME_MULTIPLIER).is_ok());
+        assert!(validate_time_multiplier(99).is_err());
+        assert!(validate_time_multiplier(MAX_TIME_MULTIPLIER + 1).is_err());
+        assert!(validate_retention_days(MIN_RETENTION_DAYS).is_ok());
+        assert!(validate_retention_days(MIN_RETENTION_DAYS - 1).is_err());
+        assert!(validate_retention_days(MAX_RETENTION_DAYS + 1).is_err());
+        assert!(validate_announcement("Question 3 was fixed").is_ok());
+        assert!(validate_announcement(" ").is_err());
+        assert!(validate_announcement(&"a".repeat(MAX_ANNOUNCEMENT_LEN + 1)).is_err());
+        assert!(validate_attempt_note("").is_ok());
+        assert!(validate_attempt_note(&"a".repeat(MAX_ATTEMPT_NOTE_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn preview_token_rejections() {
+        let token = |label: &str, valid_secs| CreatePreviewTokenParams {
+            quiz_id: 0,
+            label: label.to_string(),
+            valid_secs,
+        };
+        assert!(validate_preview_token(&token("Reviewer", 60)).is_empty());
+        assert_eq!(validate_preview_token(&token(" ", 60))[0].field, "label");
+        let long_label = "a".repeat(MAX_PREVIEW_LABEL_LEN + 1);
+        assert_eq!(
+            validate_preview_token(&token(&long_label, 60))[0].field,
+            "label"
+        );
+        assert_eq!(
+            validate_preview_token(&token("Reviewer", 0))[0].field,
+            "validSecs"
+        );
+        let too_long = token("Reviewer", MAX_PREVIEW_TOKEN_SECS + 1);
+        assert_eq!(validate_preview_token(&too_long)[0].field, "validSecs");
+    }
+
+    #[test]
+    fn quiz_filter_rejects_non_microsecond_bounds() {
+        let filter = QuizFilter {
+            created_after: Some(at(NOW)),
+            ..QuizFilter::default()
+        };
+        assert_eq!(
+            validate_quiz_filter(&filter).unwrap(),
+            (Some(at(NOW)), None)
+        );
+        let filter = QuizFilter {
+            created_before: Some(at(1_700_000_000_000)),
+            ..QuizFilter::default()
+        };
+        assert_eq!(
+            validate_quiz_filter(&filter).unwrap_err().field,
+            "createdBefore"
+        );
+    }
+}
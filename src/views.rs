@@ -0,0 +1,226 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! 状态到GraphQL视图的映射，各查询共用，新增视图字段只需修改此处 */
+
+use linera_sdk::linera_base_types::{AccountOwner, ChainId, Timestamp};
+
+use crate::{
+    scoring::FixedScore,
+    state::{GlobalStanding, PoolQuestion, Question, QuizSet, RemoteQuiz, UserAttempt},
+    AnswerView, BlackoutWindow, BlackoutWindowView, GlobalStandingView, PracticePoolQuestion,
+    QuestionKind, QuestionReview, QuizParameters, QuizSetView, RemoteQuizView, RubricScore,
+    UserAttemptView,
+};
+
+/// Quiz的列表视图，阶段和结果可见性按给定时间计算
+impl From<(&QuizSet, Timestamp)> for QuizSetView {
+    fn from((quiz, now): (&QuizSet, Timestamp)) -> Self {
+        QuizSetView {
+            id: quiz.id,
+            title: quiz.title.clone(),
+            description: quiz.description.clone(),
+            creator: quiz.creator,
+            creator_name: quiz.creator_name.clone(),
+            start_mode: quiz.start_mode,
+            scoring: quiz.scoring,
+            allow_dont_know: quiz.allow_dont_know,
+            is_started: quiz.has_started(now),
+            status: quiz.status(now),
+            cancelled: quiz.cancelled,
+            finalized: quiz.finalized,
+            questions: quiz.question_views(),
+            start_time: quiz.start_time,
+            end_time: quiz.end_time,
+            created_at: quiz.created_at,
+            practice: quiz.practice,
+            playoff_of: quiz.playoff_of,
+            hide_results: quiz.hide_results,
+            results_visible: quiz.results_visible(now),
+            owner: quiz.owner.as_ref().map(ToString::to_string),
+            metadata: quiz.metadata_entries(),
+            proctor: quiz.proctor,
+            org: quiz.org.clone(),
+            members_only: quiz.members_only,
+            time_limit: quiz.time_limit,
+            shuffle_questions: quiz.shuffle_questions,
+            questions_per_attempt: quiz.questions_per_attempt,
+            max_attempts: quiz.max_attempts,
+            attempt_policy: quiz.attempt_policy,
+            attestation_app: quiz.attestation_app,
+            entry_fee: quiz.entry_fee,
+            category: quiz.category.clone(),
+            tags: quiz.tags.clone(),
+            pass_score: quiz.pass_score,
+            soft_launch: quiz.soft_launch,
+            embargoed: quiz.is_embargoed(),
+            content_commitment: quiz.embargo.as_ref().map(|embargo| embargo.commitment),
+            answer_key_hash: quiz.answer_key_hash,
+            answer_key_salt: quiz.answer_key_salt.clone(),
+        }
+    }
+}
+
+/// 平台禁排时段的视图形式
+impl From<(u64, BlackoutWindow)> for BlackoutWindowView {
+    fn from((id, window): (u64, BlackoutWindow)) -> Self {
+        BlackoutWindowView {
+            id,
+            start_time: window.start_time,
+            end_time: window.end_time,
+            reason: window.reason,
+        }
+    }
+}
+
+/// 问题的复习视图，包括正确答案和解析
+impl From<Question> for QuestionReview {
+    fn from(question: Question) -> Self {
+        let (accepted_answers, rubric) = match question.kind {
+            QuestionKind::FillInBlank { answers } => (answers, Vec::new()),
+            QuestionKind::Numeric { answer, tolerance } => (vec![answer, tolerance], Vec::new()),
+            QuestionKind::Essay { rubric } => (Vec::new(), rubric),
+            QuestionKind::MultipleChoice | QuestionKind::TrueFalse => (Vec::new(), Vec::new()),
+        };
+        QuestionReview {
+            question_id: question.id,
+            text: question.text,
+            options: question.options,
+            correct_options: question.correct_options,
+            accepted_answers,
+            rubric,
+            explanation: question.explanation,
+        }
+    }
+}
+
+/// 已评分问答题各项评分标准的得分明细
+pub fn rubric_scores(question: &Question, levels: &[u32]) -> Vec<RubricScore> {
+    let QuestionKind::Essay { rubric } = &question.kind else {
+        return Vec::new();
+    };
+    rubric
+        .iter()
+        .zip(levels)
+        .map(|(criterion, level)| RubricScore {
+            criterion: criterion.name.clone(),
+            weight: criterion.weight,
+            level: *level,
+            level_count: criterion.levels.len() as u32,
+            descriptor: criterion
+                .levels
+                .get(*level as usize)
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// 练习卷候选问题的视图形式，不含正确答案
+impl From<PoolQuestion> for PracticePoolQuestion {
+    fn from(entry: PoolQuestion) -> Self {
+        PracticePoolQuestion {
+            quiz_id: entry.quiz_id,
+            question_id: entry.question.id,
+            text: entry.question.text,
+            difficulty: entry.difficulty,
+        }
+    }
+}
+
+/// 远程Quiz目录条目的视图形式
+pub fn remote_quiz_view(
+    host_chain_id: ChainId,
+    quiz_id: u64,
+    remote_quiz: &RemoteQuiz,
+) -> RemoteQuizView {
+    let announcement = &remote_quiz.announcement;
+    RemoteQuizView {
+        host_chain_id,
+        quiz_id,
+        title: announcement.title.clone(),
+        creator: announcement.creator,
+        start_time: announcement.start_time,
+        end_time: announcement.end_time,
+        refreshed_at: remote_quiz.refreshed_at,
+        standings_hash: remote_quiz.standings_hash,
+    }
+}
+
+/// 排行榜条目的视图形式，只含答题者、分数、用时和完成时间
+pub fn ranking_view(
+    user: AccountOwner,
+    nick_name: String,
+    score: FixedScore,
+    time_taken: u64,
+    completed_at: Timestamp,
+    parameters: &QuizParameters,
+) -> UserAttemptView {
+    UserAttemptView {
+        quiz_id: 0,
+        attempt_no: 0,
+        user: Some(user),
+        nick_name,
+        answers: Vec::new(),
+        score: parameters.display_score(score),
+        precise_score: parameters.format_score(score),
+        question_scores: Vec::new(),
+        time_taken,
+        completed_at,
+        results_hidden: false,
+        receipt: None,
+        question_order: Vec::new(),
+        passed: None,
+    }
+}
+
+/// 跨Quiz累计排行榜的条目视图
+pub fn global_standing_view(
+    rank: u32,
+    user: AccountOwner,
+    standing: GlobalStanding,
+    parameters: &QuizParameters,
+) -> GlobalStandingView {
+    GlobalStandingView {
+        rank,
+        user: Some(user),
+        nick_name: standing.nick_name,
+        points: parameters.display_score(standing.points),
+        precise_points: parameters.format_score(standing.points),
+        quizzes_completed: standing.quizzes_completed,
+        updated_at: standing.updated_at,
+    }
+}
+
+/// 答卷视图，结果未公布时隐藏分数
+pub fn attempt_view(
+    attempt: UserAttempt,
+    visible: bool,
+    parameters: &QuizParameters,
+) -> UserAttemptView {
+    let fixed_score = if visible { attempt.fixed_score } else { 0 };
+    UserAttemptView {
+        quiz_id: attempt.quiz_id,
+        attempt_no: attempt.attempt_no,
+        user: Some(attempt.user),
+        nick_name: attempt.nick_name,
+        answers: attempt.answers.into_iter().map(AnswerView::from).collect(),
+        score: if visible { attempt.score } else { 0 },
+        precise_score: parameters.format_score(fixed_score),
+        question_scores: if visible {
+            attempt
+                .question_results
+                .iter()
+                .map(|result| parameters.format_score(result.points_awarded))
+                .collect()
+        } else {
+            Vec::new()
+        },
+        time_taken: attempt.time_taken,
+        completed_at: attempt.completed_at,
+        results_hidden: !visible,
+        receipt: Some(attempt.receipt),
+        question_order: attempt.question_order,
+        passed: attempt.passed.filter(|_| visible),
+    }
+}